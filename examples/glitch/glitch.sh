@@ -9,8 +9,8 @@ iverilog -lglitch.v glitch_tb.v -oglitch_tb.out
 ./glitch_tb.out
 
 # Process VCD file into per clock cycle TCL glitch power activity.
-# --only-glitches causes trace2power to only export activities of signals where TC >= 2.
-# --clock-name CLOCK_NAME allows trace2power to ignore clock signal activity.
+# --only-glitches causes trace2power to only export transitions beyond the functional one between
+# consecutive rising edges of the clock given with --clock-name, whose activity is ignored.
 mkdir -p out_tcl
 trace2power \
     --clk-freq 200000000 \
@@ -0,0 +1,1074 @@
+// Copyright (c) 2024-2026 Antmicro <www.antmicro.com>
+// SPDX-License-Identifier: Apache-2.0
+
+// Programmatic configuration of trace processing, independent of the command line interface
+
+use std::collections::{BTreeSet, HashMap};
+use std::{fs, io, path};
+
+use rayon::prelude::*;
+use wellen::{self, GetItem, SignalRef};
+
+use crate::clock::{self, ClockDomain, ClockSpec, NetlistClocks};
+use crate::condition::Condition;
+use crate::energy::EnergyTable;
+use crate::error::{Error, Result, Warning};
+use crate::liberty::Liberty;
+use crate::netlist::Netlist;
+use crate::spef::Spef;
+use crate::stats::{
+    self, Activity, FilteredChanges, NetSettings, OffPeriods, PackedStats, PulseFilter,
+};
+use crate::upf::{self, TraceDomain, Upf};
+use crate::util::VarRefsIter;
+use crate::{
+    Args, Context, HashVarRef, LookupPoint, ProfileMetric, SaifBusNotation, SaifDialect,
+    SaifEscaping, TclDialect, get_scope_by_full_name, indexed_name,
+};
+
+enum NetlistSource {
+    File(path::PathBuf),
+    Loaded(Netlist),
+}
+
+enum LibertySource {
+    File(path::PathBuf),
+    Loaded(Liberty),
+}
+
+enum SpefSource {
+    File(path::PathBuf),
+    Loaded(Spef),
+}
+
+enum EnergyTableSource {
+    File(path::PathBuf),
+    Loaded(EnergyTable),
+}
+
+enum UpfSource {
+    File(path::PathBuf),
+    Loaded(Upf),
+}
+
+/// Configures how a trace is loaded and how its activity is accumulated.
+///
+/// ```no_run
+/// use libtrace2power::{ContextBuilder, OutputFormat};
+///
+/// let ctx = ContextBuilder::new("counter.vcd")
+///     .clk_freq(500e6)
+///     .limit_scope("counter_tb.counter0")
+///     .netlist_file("counter.json")
+///     .build()?;
+/// ctx.export(OutputFormat::Saif, std::io::stdout(), 0)?;
+/// # Ok::<(), libtrace2power::error::Error>(())
+/// ```
+pub struct ContextBuilder {
+    input_file: path::PathBuf,
+    clk_freq: Option<f64>,
+    clock_name: Option<String>,
+    limit_scope: Option<String>,
+    limit_scope_power: Option<String>,
+    netlist: Option<NetlistSource>,
+    top: Option<String>,
+    top_scope: Option<String>,
+    blackboxes_only: bool,
+    remove_virtual_pins: bool,
+    ignore_date: bool,
+    ignore_version: bool,
+    per_clock_cycle: bool,
+    num_of_windows: Option<u64>,
+    activity: Activity,
+    split_glitches: bool,
+    export_empty: bool,
+    input_ports_activity: bool,
+    sparse: bool,
+    profile_depth: Option<usize>,
+    profile_metric: ProfileMetric,
+    tcl_dialect: TclDialect,
+    base_clock: Option<String>,
+    saif_dialect: SaifDialect,
+    saif_divider: Option<char>,
+    saif_escaping: Option<SaifEscaping>,
+    saif_bus: Option<SaifBusNotation>,
+    saif_module_names: bool,
+    liberty: Vec<LibertySource>,
+    wire_capacitance: f64,
+    spef: Option<SpefSource>,
+    supply_voltage: Option<f64>,
+    energy_table: Option<EnergyTableSource>,
+    upf: Option<UpfSource>,
+    clocks: Vec<ClockSpec>,
+    min_pulse_width: Option<f64>,
+    cell_pulse_widths: HashMap<String, f64>,
+    keep_narrow_pulses: bool,
+    enable: Option<Condition>,
+}
+
+impl ContextBuilder {
+    pub fn new(input_file: impl Into<path::PathBuf>) -> Self {
+        Self {
+            input_file: input_file.into(),
+            clk_freq: None,
+            clock_name: None,
+            limit_scope: None,
+            limit_scope_power: None,
+            netlist: None,
+            top: None,
+            top_scope: None,
+            blackboxes_only: false,
+            remove_virtual_pins: false,
+            ignore_date: false,
+            ignore_version: false,
+            per_clock_cycle: false,
+            num_of_windows: None,
+            activity: Activity::All,
+            split_glitches: false,
+            export_empty: false,
+            input_ports_activity: false,
+            sparse: false,
+            profile_depth: None,
+            profile_metric: ProfileMetric::default(),
+            tcl_dialect: TclDialect::default(),
+            base_clock: None,
+            saif_dialect: SaifDialect::default(),
+            saif_divider: None,
+            saif_escaping: None,
+            saif_bus: None,
+            saif_module_names: false,
+            liberty: Vec::new(),
+            wire_capacitance: 0.0,
+            spef: None,
+            supply_voltage: None,
+            energy_table: None,
+            upf: None,
+            clocks: Vec::new(),
+            min_pulse_width: None,
+            cell_pulse_widths: HashMap::new(),
+            keep_narrow_pulses: false,
+            enable: None,
+        }
+    }
+
+    /// Clock frequency (in Hz). If it's not given, it's measured from the clock set with
+    /// `clock_name`.
+    pub fn clk_freq(mut self, clk_freq: f64) -> Self {
+        self.clk_freq = Some(clk_freq);
+        self
+    }
+
+    /// Path of the clock net, relative to `limit_scope` or absolute, which can contain glob
+    /// patterns. A name without a path is looked up within `limit_scope`.
+    pub fn clock_name(mut self, clock_name: impl Into<String>) -> Self {
+        self.clock_name = Some(clock_name.into());
+        self
+    }
+
+    /// Scope in which signals should be looked for
+    pub fn limit_scope(mut self, scope: impl Into<String>) -> Self {
+        self.limit_scope = Some(scope.into());
+        self
+    }
+
+    /// Scope in which power will be calculated. Must be a subset of `limit_scope`
+    pub fn limit_scope_power(mut self, scope: impl Into<String>) -> Self {
+        self.limit_scope_power = Some(scope.into());
+        self
+    }
+
+    /// Path to a Yosys JSON netlist of the DUT
+    pub fn netlist_file(mut self, path: impl Into<path::PathBuf>) -> Self {
+        self.netlist = Some(NetlistSource::File(path.into()));
+        self
+    }
+
+    /// Already loaded netlist of the DUT
+    pub fn netlist(mut self, netlist: Netlist) -> Self {
+        self.netlist = Some(NetlistSource::Loaded(netlist));
+        self
+    }
+
+    /// Name of the top module (DUT). By default it's the module marked as top in the netlist.
+    pub fn top(mut self, top: impl Into<String>) -> Self {
+        self.top = Some(top.into());
+        self
+    }
+
+    /// Scope at which the DUT is located. The netlist will be rooted at this point
+    pub fn top_scope(mut self, scope: impl Into<String>) -> Self {
+        self.top_scope = Some(scope.into());
+        self
+    }
+
+    /// Export only nets from blackboxes (undefined modules) in the netlist
+    pub fn blackboxes_only(mut self, enable: bool) -> Self {
+        self.blackboxes_only = enable;
+        self
+    }
+
+    /// Remove nets that are in blackboxes and have suspicious names
+    pub fn remove_virtual_pins(mut self, enable: bool) -> Self {
+        self.remove_virtual_pins = enable;
+        self
+    }
+
+    /// Ignore exporting current date
+    pub fn ignore_date(mut self, enable: bool) -> Self {
+        self.ignore_date = enable;
+        self
+    }
+
+    /// Ignore exporting current version
+    pub fn ignore_version(mut self, enable: bool) -> Self {
+        self.ignore_version = enable;
+        self
+    }
+
+    /// Accumulate stats for each clock cycle separately
+    pub fn per_clock_cycle(mut self, enable: bool) -> Self {
+        self.per_clock_cycle = enable;
+        self
+    }
+
+    /// Split the trace into a given number of equal windows. Takes precedence over
+    /// `per_clock_cycle`
+    pub fn windows(mut self, num_of_windows: u64) -> Self {
+        self.num_of_windows = Some(num_of_windows);
+        self
+    }
+
+    /// Accumulate stats only for glitches. Same as `activity(Activity::Glitches)`.
+    pub fn only_glitches(mut self, enable: bool) -> Self {
+        self.activity = if enable {
+            Activity::Glitches
+        } else {
+            Activity::All
+        };
+        self
+    }
+
+    /// Kind of activity accumulated for nets. Glitches are transitions beyond the functional
+    /// one between consecutive active edges of the clock of a net, so the clock must be found in
+    /// the trace.
+    pub fn activity(mut self, activity: Activity) -> Self {
+        self.activity = activity;
+        self
+    }
+
+    /// Accumulate functional activity and glitches separately, so that both can be exported from
+    /// a single context (see [`Context::set_activity`]). Takes precedence over `activity`.
+    pub fn split_glitches(mut self, enable: bool) -> Self {
+        self.split_glitches = enable;
+        self
+    }
+
+    /// Export without accumulation
+    pub fn export_empty(mut self, enable: bool) -> Self {
+        self.export_empty = enable;
+        self
+    }
+
+    /// Set activity for input ports in TCL mode
+    pub fn input_ports_activity(mut self, enable: bool) -> Self {
+        self.input_ports_activity = enable;
+        self
+    }
+
+    /// Skip nets without any transitions in CSV/TSV mode
+    pub fn sparse(mut self, enable: bool) -> Self {
+        self.sparse = enable;
+        self
+    }
+
+    /// Limit the depth of the hierarchy down to which scopes get their own signal in VCD mode
+    pub fn profile_depth(mut self, depth: usize) -> Self {
+        self.profile_depth = Some(depth);
+        self
+    }
+
+    /// Value carried by the signals in VCD mode
+    pub fn profile_metric(mut self, metric: ProfileMetric) -> Self {
+        self.profile_metric = metric;
+        self
+    }
+
+    /// Tool for which commands are generated in TCL mode
+    pub fn tcl_dialect(mut self, dialect: TclDialect) -> Self {
+        self.tcl_dialect = dialect;
+        self
+    }
+
+    /// Tool for which the file is generated in SAIF mode
+    pub fn saif_dialect(mut self, dialect: SaifDialect) -> Self {
+        self.saif_dialect = dialect;
+        self
+    }
+
+    /// Hierarchy divider in SAIF mode, `/` or `.`
+    pub fn saif_divider(mut self, divider: char) -> Self {
+        self.saif_divider = Some(divider);
+        self
+    }
+
+    /// Names with special characters escaped in SAIF mode. By default it depends on the dialect.
+    pub fn saif_escaping(mut self, escaping: SaifEscaping) -> Self {
+        self.saif_escaping = Some(escaping);
+        self
+    }
+
+    /// Notation of vector nets in SAIF mode
+    pub fn saif_bus(mut self, bus: SaifBusNotation) -> Self {
+        self.saif_bus = Some(bus);
+        self
+    }
+
+    /// Precede instance names with names of their modules in SAIF mode
+    pub fn saif_module_names(mut self, enable: bool) -> Self {
+        self.saif_module_names = enable;
+        self
+    }
+
+    /// Clock which toggle rates are relative to in TCL mode with the PrimeTime dialect. By
+    /// default it's the name of the net of `clock_name`, without its path.
+    pub fn base_clock(mut self, base_clock: impl Into<String>) -> Self {
+        self.base_clock = Some(base_clock.into());
+        self
+    }
+
+    /// Path to a Liberty library of cells used in the netlist. Can be called multiple times to
+    /// load several libraries
+    pub fn liberty_file(mut self, path: impl Into<path::PathBuf>) -> Self {
+        self.liberty.push(LibertySource::File(path.into()));
+        self
+    }
+
+    /// Already loaded Liberty library of cells used in the netlist
+    pub fn liberty(mut self, liberty: Liberty) -> Self {
+        self.liberty.push(LibertySource::Loaded(liberty));
+        self
+    }
+
+    /// Capacitance of each net (in pF), added to the capacitance of the pins it drives when
+    /// estimating power
+    pub fn wire_capacitance(mut self, capacitance: f64) -> Self {
+        self.wire_capacitance = capacitance;
+        self
+    }
+
+    /// Path to a SPEF file with parasitics of the design
+    pub fn spef_file(mut self, path: impl Into<path::PathBuf>) -> Self {
+        self.spef = Some(SpefSource::File(path.into()));
+        self
+    }
+
+    /// Already loaded parasitics of the design
+    pub fn spef(mut self, spef: Spef) -> Self {
+        self.spef = Some(SpefSource::Loaded(spef));
+        self
+    }
+
+    /// Supply voltage (in V) used to compute switching power of nets. By default it's the nominal
+    /// voltage of the Liberty libraries.
+    pub fn supply_voltage(mut self, voltage: f64) -> Self {
+        self.supply_voltage = Some(voltage);
+        self
+    }
+
+    /// Path to a CSV table with energy of pin transitions and leakage of cells or modules, used
+    /// instead of Liberty libraries to estimate power
+    pub fn energy_table_file(mut self, path: impl Into<path::PathBuf>) -> Self {
+        self.energy_table = Some(EnergyTableSource::File(path.into()));
+        self
+    }
+
+    /// Already loaded table with energy of pin transitions and leakage of cells or modules
+    pub fn energy_table(mut self, table: EnergyTable) -> Self {
+        self.energy_table = Some(EnergyTableSource::Loaded(table));
+        self
+    }
+
+    /// Path to a UPF file describing power domains, whose activity is only accumulated while
+    /// their power switches are on
+    pub fn upf_file(mut self, path: impl Into<path::PathBuf>) -> Self {
+        self.upf = Some(UpfSource::File(path.into()));
+        self
+    }
+
+    /// Already loaded description of power domains
+    pub fn upf(mut self, upf: Upf) -> Self {
+        self.upf = Some(UpfSource::Loaded(upf));
+        self
+    }
+
+    /// Declare an additional clock. Activity of nets in its domain is normalized to its period.
+    pub fn clock(mut self, clock: ClockSpec) -> Self {
+        self.clocks.push(clock);
+        self
+    }
+
+    /// Width (in s) of the narrowest pulse which passes through gates. Narrower pulses are filtered
+    /// out, as they would be by the inertial delay of real gates.
+    pub fn min_pulse_width(mut self, width: f64) -> Self {
+        self.min_pulse_width = Some(width);
+        self
+    }
+
+    /// Width (in s) of the narrowest pulse on nets of instances of a given cell type, overriding
+    /// `min_pulse_width`
+    pub fn cell_pulse_width(mut self, cell: impl Into<String>, width: f64) -> Self {
+        self.cell_pulse_widths.insert(cell.into(), width);
+        self
+    }
+
+    /// Count pulses narrower than the minimum width without removing them from the activity
+    pub fn keep_narrow_pulses(mut self, enable: bool) -> Self {
+        self.keep_narrow_pulses = enable;
+        self
+    }
+
+    /// Condition over trace signals which has to hold for activity to be accumulated. Names of
+    /// signals are relative to `limit_scope`, or absolute.
+    pub fn enable(mut self, condition: Condition) -> Self {
+        self.enable = Some(condition);
+        self
+    }
+
+    /// Load the trace and accumulate its activity
+    pub fn build(self) -> Result<Context> {
+        const LOAD_OPTS: wellen::LoadOptions = wellen::LoadOptions {
+            multi_thread: true,
+            remove_scopes_with_empty_name: false,
+        };
+
+        // wellen panics on files it can't open, so report those before handing the path over
+        fs::metadata(&self.input_file).map_err(Error::io(&self.input_file))?;
+
+        // Read the design files before the trace, so that bad ones are reported early
+        let netlist = match self.netlist {
+            None => None,
+            Some(NetlistSource::Loaded(netlist)) => Some(netlist),
+            Some(NetlistSource::File(path)) => {
+                let file = fs::File::open(&path).map_err(Error::io(&path))?;
+                Some(
+                    serde_json::from_reader::<_, Netlist>(io::BufReader::new(file))
+                        .map_err(|source| Error::Netlist { path, source })?,
+                )
+            }
+        };
+
+        // Without `top` the top module is taken from the netlist
+        let top = self
+            .top
+            .clone()
+            .or_else(|| netlist.as_ref()?.top_module().map(String::from));
+
+        let liberty = if self.liberty.is_empty() {
+            None
+        } else {
+            let mut liberty = Liberty::new();
+            for source in self.liberty {
+                match source {
+                    LibertySource::Loaded(loaded) => liberty.cells.extend(loaded.cells),
+                    LibertySource::File(file) => {
+                        let source = fs::read_to_string(&file).map_err(Error::io(&file))?;
+                        liberty
+                            .read(&source)
+                            .map_err(|source| Error::Liberty { file, source })?;
+                    }
+                }
+            }
+            Some(liberty)
+        };
+
+        let spef = match self.spef {
+            None => None,
+            Some(SpefSource::Loaded(spef)) => Some(spef),
+            Some(SpefSource::File(file)) => {
+                let source = fs::read_to_string(&file).map_err(Error::io(&file))?;
+                Some(Spef::parse(&source).map_err(|source| Error::Spef { file, source })?)
+            }
+        };
+
+        let energy_table = match self.energy_table {
+            None => None,
+            Some(EnergyTableSource::Loaded(table)) => Some(table),
+            Some(EnergyTableSource::File(file)) => {
+                let source = fs::read_to_string(&file).map_err(Error::io(&file))?;
+                Some(
+                    EnergyTable::parse(&source)
+                        .map_err(|source| Error::EnergyTable { file, source })?,
+                )
+            }
+        };
+
+        let mut wave = wellen::simple::read_with_options(&self.input_file, &LOAD_OPTS)?;
+
+        let wave_hierarchy = wave.hierarchy();
+
+        let timescale = wave_hierarchy.timescale().ok_or(Error::MissingTimescale)?;
+        let time_exponent = timescale.unit.to_exponent().ok_or(Error::UnknownTimeUnit)?;
+        let timescale_norm = (timescale.factor as f64) * (10.0_f64).powf(time_exponent as f64);
+
+        let lookup_point = match &self.limit_scope {
+            None => LookupPoint::Top,
+            Some(scope_str) => LookupPoint::Scope(
+                get_scope_by_full_name(wave_hierarchy, scope_str).ok_or_else(|| {
+                    Error::ScopeNotFound {
+                        scope: scope_str.clone(),
+                    }
+                })?,
+            ),
+        };
+
+        let lookup_scope_name_prefix = match lookup_point {
+            LookupPoint::Top => "".to_string(),
+            LookupPoint::Scope(scope_ref) => {
+                let scope = wave_hierarchy.get(scope_ref);
+                scope.full_name(wave_hierarchy).to_string() + "."
+            }
+        };
+
+        let (all_vars, all_signals): (Vec<_>, Vec<_>) = match lookup_point {
+            LookupPoint::Top => wave_hierarchy
+                .var_refs_iter()
+                .map(|var_ref| (var_ref, wave_hierarchy.get(var_ref).signal_ref()))
+                .unzip(),
+            LookupPoint::Scope(_) => wave_hierarchy
+                .var_refs_iter()
+                .map(|var_ref| (var_ref, wave_hierarchy.get(var_ref)))
+                .filter(|(_, var)| {
+                    let fname = indexed_name(var.full_name(wave_hierarchy), var);
+                    fname.starts_with(&lookup_scope_name_prefix)
+                })
+                .map(|(var_ref, var)| (var_ref, var.signal_ref()))
+                .unzip(),
+        };
+
+        let all_signals_power: BTreeSet<_> = match &self.limit_scope_power {
+            None => wave_hierarchy
+                .var_refs_iter()
+                .map(|var_ref| wave_hierarchy.get(var_ref))
+                .map(|var| indexed_name(var.full_name(wave_hierarchy), var))
+                .collect::<BTreeSet<_>>(),
+            Some(scope_str) => wave_hierarchy
+                .var_refs_iter()
+                .map(|var_ref| wave_hierarchy.get(var_ref))
+                .filter(|var| {
+                    let fname = indexed_name(var.full_name(wave_hierarchy), var);
+                    fname.starts_with(scope_str)
+                })
+                .map(|var| indexed_name(var.full_name(wave_hierarchy), var))
+                .collect::<BTreeSet<_>>(),
+        };
+
+        let clk_net = self
+            .clock_name
+            .as_ref()
+            .map(|clock| {
+                clock::find_clock(wave_hierarchy, &lookup_scope_name_prefix, clock)?.ok_or_else(
+                    || Error::ClockNotFound {
+                        clock: clock.clone(),
+                    },
+                )
+            })
+            .transpose()?;
+        let clk_signal: Option<SignalRef> = clk_net.as_ref().map(|&(sig_ref, _)| sig_ref);
+        let declared_nets = self
+            .clocks
+            .iter()
+            .map(|spec| clock::find_clock(wave_hierarchy, &lookup_scope_name_prefix, &spec.name))
+            .collect::<Result<Vec<_>>>()?;
+        let declared_signals: Vec<_> = declared_nets
+            .iter()
+            .map(|net| net.as_ref().map(|&(sig_ref, _)| sig_ref))
+            .collect();
+        // Clocks are exempt from glitch filtering, together with all their aliases
+        let clock_signals: Vec<_> = clk_signal
+            .iter()
+            .chain(declared_signals.iter().flatten())
+            .copied()
+            .collect();
+
+        // UPF names are relative to the top of the design
+        let design_prefix = match &self.top_scope {
+            Some(scope) => format!("{scope}."),
+            None => lookup_scope_name_prefix.clone(),
+        };
+        let upf = match self.upf {
+            None => None,
+            Some(UpfSource::Loaded(upf)) => Some(upf),
+            Some(UpfSource::File(file)) => {
+                let source = fs::read_to_string(&file).map_err(Error::io(&file))?;
+                Some(Upf::parse(&source).map_err(|source| Error::Upf { file, source })?)
+            }
+        };
+        // Buses may be named with or without their index range
+        let find_net = |net: &str| {
+            wave_hierarchy
+                .var_refs_iter()
+                .map(|var_ref| wave_hierarchy.get(var_ref))
+                .find(|var| {
+                    let name = var.full_name(wave_hierarchy);
+                    name == net || indexed_name(name, var) == net
+                })
+                .map(|var| var.signal_ref())
+        };
+        let controls = upf
+            .iter()
+            .flat_map(|upf| &upf.domains)
+            .filter_map(|domain| domain.switch.as_ref())
+            .map(|switch| {
+                let net = design_prefix.clone() + &switch.control;
+                find_net(&net).ok_or(Error::NetNotFound { net })
+            })
+            .collect::<Result<Vec<_>>>()?;
+        let enable_signals = self
+            .enable
+            .iter()
+            .flat_map(Condition::signals)
+            .map(|name| {
+                find_net(&(lookup_scope_name_prefix.clone() + name))
+                    .or_else(|| find_net(name))
+                    .ok_or_else(|| Error::NetNotFound {
+                        net: name.to_string(),
+                    })
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        // TODO load signals that are under a power scope
+        wave.load_signals_multi_threaded(&all_signals);
+        wave.load_signals(&controls);
+        wave.load_signals(&enable_signals);
+        wave.load_signals(&Vec::from_iter(clk_signal));
+        wave.load_signals(
+            &declared_signals
+                .iter()
+                .flatten()
+                .copied()
+                .collect::<Vec<_>>(),
+        );
+
+        // Measure clocks to check the given frequencies or to use them in place of missing ones
+        let mut warnings = Vec::new();
+        let primary_freq = match &self.clock_name {
+            Some(clock) => clock::resolve_frequency(
+                &wave,
+                timescale,
+                clock,
+                clk_signal,
+                self.clk_freq,
+                &mut warnings,
+            )?,
+            None => self.clk_freq,
+        };
+        let mut clocks = Vec::new();
+        // Signals of the clocks, in the same order
+        let clock_refs: Vec<_> = primary_freq
+            .map(|_| clk_signal)
+            .into_iter()
+            .chain(declared_signals.iter().copied())
+            .collect();
+        if let Some(freq) = primary_freq {
+            clocks.push(ClockDomain {
+                name: self.clock_name.clone().unwrap_or_default(),
+                freq,
+                period: 1.0 / freq,
+                // Sign-off tools know the clock by the name of its net rather than by its path
+                base_clock: self
+                    .base_clock
+                    .clone()
+                    .or_else(|| clk_net.as_ref().map(|(_, name)| name.clone())),
+            });
+        }
+        for ((spec, sig_ref), net) in self.clocks.iter().zip(declared_signals).zip(declared_nets) {
+            let freq = clock::resolve_frequency(
+                &wave,
+                timescale,
+                &spec.name,
+                sig_ref,
+                spec.freq,
+                &mut warnings,
+            )?
+            .ok_or_else(|| Error::ClockNotMeasured {
+                clock: spec.name.clone(),
+            })?;
+            // A clock missing from the trace keeps the name it was declared with, unless it's a
+            // pattern
+            let base_clock = match net {
+                Some((_, name)) => Some(name),
+                None if spec.name.contains(['*', '?']) => None,
+                None => spec.name.rsplit('.').next().map(String::from),
+            };
+            clocks.push(ClockDomain {
+                name: spec.name.clone(),
+                freq,
+                period: 1.0 / freq,
+                base_clock,
+            });
+        }
+        // Without a primary clock, the first declared one takes its place
+        let first_declared = if primary_freq.is_some() { 1 } else { 0 };
+        let clk_freq = clocks
+            .first()
+            .map(|clock| clock.freq)
+            .ok_or(Error::MissingClockFrequency)?;
+        let clk_period = 1.0_f64 / clk_freq;
+
+        let disabled_periods = match &self.enable {
+            None => OffPeriods::default(),
+            Some(condition) => {
+                condition
+                    .disabled_periods(&wave, &enable_signals)
+                    .map_err(|source| Error::Stats {
+                        net: condition.signals().join(", "),
+                        source,
+                    })?
+            }
+        };
+
+        let mut controls = controls.into_iter();
+        let power_domains = upf
+            .into_iter()
+            .flat_map(|upf| upf.domains)
+            .map(|domain| {
+                let off_periods = match &domain.switch {
+                    None => OffPeriods::default(),
+                    Some(switch) => {
+                        let net = design_prefix.clone() + &switch.control;
+                        let control = controls
+                            .next()
+                            .ok_or_else(|| Error::NetNotFound { net: net.clone() })?;
+                        OffPeriods::from_control(&wave, control, switch.on_value)
+                            .map_err(|source| Error::Stats { net, source })?
+                    }
+                };
+                let scopes = if domain.elements.is_empty() {
+                    vec![design_prefix.clone()]
+                } else {
+                    domain
+                        .elements
+                        .iter()
+                        .map(|element| format!("{design_prefix}{element}."))
+                        .collect()
+                };
+                Ok(TraceDomain {
+                    name: domain.name,
+                    scopes,
+                    gated_periods: off_periods.union(&disabled_periods),
+                    off_periods,
+                })
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        let time_end = *wave.time_table().last().ok_or(Error::EmptyWaveform)?;
+        if let Some(condition) = &self.enable {
+            if disabled_periods.overlap(0, time_end) == time_end {
+                warnings.push(Warning::EnableNeverHolds {
+                    condition: condition.to_string(),
+                });
+            }
+        }
+        let num_of_iterations = match (self.num_of_windows, self.per_clock_cycle) {
+            (Some(windows), _) => windows.max(1),
+            // A trace shorter than a clock period still makes up a single cycle
+            (None, true) => ((time_end as f64 * timescale_norm / clk_period) as u64).max(1),
+            (None, false) => 1,
+        };
+
+        // Nets matching patterns of declared clocks belong to their domains, others are assigned
+        // through clock pins of cells in the netlist or belong to the primary clock
+        let netlist_clocks = match &netlist {
+            Some(netlist) if clocks.len() > 1 => top
+                .as_ref()
+                .map(|top| NetlistClocks::trace(netlist, top, &design_prefix, &clocks))
+                .unwrap_or_default(),
+            _ => NetlistClocks::default(),
+        };
+        let net_clocks: HashMap<HashVarRef, usize> = all_vars
+            .iter()
+            .filter_map(|&var_ref| {
+                let var = wave.hierarchy().get(var_ref);
+                let name = var.full_name(wave.hierarchy());
+                let clock = self
+                    .clocks
+                    .iter()
+                    .position(|spec| spec.matches(&name))
+                    .map(|index| index + first_declared)
+                    .or_else(|| netlist_clocks.clock_of(&name))
+                    .or_else(|| netlist_clocks.clock_of(&indexed_name(name, var)))?;
+                (clock != 0).then_some((HashVarRef(var_ref), clock))
+            })
+            .collect();
+
+        // Glitches are told apart from functional activity in cycles of the clock of each net
+        let activity = if self.split_glitches {
+            Activity::Functional
+        } else {
+            self.activity
+        };
+        let clock_edges = clocks
+            .iter()
+            .zip(&clock_refs)
+            .map(|(clock, sig_ref)| match sig_ref {
+                Some(sig_ref) => {
+                    clock::rising_edges(&wave, *sig_ref).map_err(|source| Error::Stats {
+                        net: clock.name.clone(),
+                        source,
+                    })
+                }
+                None if activity == Activity::All => Ok(Vec::new()),
+                None => Err(Error::ClockEdgesUnknown {
+                    clock: clock.name.clone(),
+                }),
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        // Pulses narrower than the inertial delay of a net are filtered, where the delay depends on
+        // the type of the cell instance the net belongs to
+        let mut cell_types: HashMap<String, Option<String>> = HashMap::new();
+        let mut cell_type = |instance: &str| {
+            let hier = wave.hierarchy();
+            cell_types
+                .entry(instance.to_string())
+                .or_insert_with(|| {
+                    get_scope_by_full_name(hier, instance)
+                        .and_then(|scope| hier.get(scope).component(hier).map(String::from))
+                        .or_else(|| {
+                            let path = instance.strip_prefix(&design_prefix)?;
+                            netlist
+                                .as_ref()?
+                                .cell_type(top.as_ref()?, path)
+                                .map(String::from)
+                        })
+                })
+                .clone()
+        };
+        let pulse_filters: HashMap<HashVarRef, PulseFilter> = all_vars
+            .iter()
+            .filter_map(|&var_ref| {
+                let name = wave.hierarchy().get(var_ref).full_name(wave.hierarchy());
+                let cell_width = if self.cell_pulse_widths.is_empty() {
+                    None
+                } else {
+                    name.rsplit_once('.')
+                        .and_then(|(instance, _)| cell_type(instance))
+                        .and_then(|cell| self.cell_pulse_widths.get(&cell).copied())
+                };
+                let width = (cell_width.or(self.min_pulse_width)? / timescale_norm).round();
+                (width >= 1.0).then_some((
+                    HashVarRef(var_ref),
+                    PulseFilter {
+                        width: width as wellen::Time,
+                        suppress: !self.keep_narrow_pulses,
+                    },
+                ))
+            })
+            .collect();
+        // Nets sharing a signal and a filter share its filtered history
+        let mut filter_keys: HashMap<(SignalRef, PulseFilter), wellen::VarRef> = HashMap::new();
+        for (var_ref, &sig_ref) in all_vars.iter().zip(&all_signals) {
+            if let Some(&filter) = pulse_filters.get(&HashVarRef(*var_ref)) {
+                filter_keys.entry((sig_ref, filter)).or_insert(*var_ref);
+            }
+        }
+        let filtered_changes: HashMap<(SignalRef, PulseFilter), FilteredChanges> = filter_keys
+            .into_par_iter()
+            .map(|((sig_ref, filter), var_ref)| {
+                filter
+                    .filter(&wave, sig_ref)
+                    .map(|filtered| ((sig_ref, filter), filtered))
+                    .map_err(|source| Error::Stats {
+                        net: wave.hierarchy().get(var_ref).full_name(wave.hierarchy()),
+                        source,
+                    })
+            })
+            .collect::<Result<_>>()?;
+
+        // TODO: A massive optimization that can be done here is to calculate stats only
+        // for exported signals instead of all nets
+        // It's easy to do with the current implementation of DFS (see src/exporter/mod.rs).
+        // However it's single-threaded and parallelizing it efficiently is non-trivial.
+        let accumulate = |activity: Activity| -> Result<HashMap<HashVarRef, Vec<PackedStats>>> {
+            all_vars
+                .par_iter()
+                .zip(&all_signals)
+                .map(|(var_ref, &sig_ref)| {
+                    let fname = indexed_name(
+                        wave.hierarchy().get(*var_ref).full_name(wave.hierarchy()),
+                        wave.hierarchy().get(*var_ref),
+                    );
+                    let stats =
+                        if self.limit_scope_power.is_none() || all_signals_power.contains(&fname) {
+                            let clock = net_clocks
+                                .get(&HashVarRef(*var_ref))
+                                .copied()
+                                .unwrap_or_default();
+                            let (off_periods, switched_off) =
+                                upf::gating(&power_domains, &disabled_periods, &fname);
+                            let settings = NetSettings {
+                                activity,
+                                clock_signals: &clock_signals,
+                                clock_edges: match activity {
+                                    Activity::All => &[],
+                                    _ => &clock_edges[clock],
+                                },
+                                off_periods,
+                                switched_off,
+                                disabled: &disabled_periods,
+                                filtered: pulse_filters
+                                    .get(&HashVarRef(*var_ref))
+                                    .and_then(|&filter| filtered_changes.get(&(sig_ref, filter))),
+                            };
+                            stats::calc_stats_for_each_time_span(
+                                &wave,
+                                sig_ref,
+                                num_of_iterations,
+                                &settings,
+                            )
+                        } else {
+                            stats::empty_stats(&wave, sig_ref)
+                                .map(|stats| vec![stats; num_of_iterations as usize])
+                        };
+                    stats
+                        .map(|stats| (HashVarRef(*var_ref), stats))
+                        .map_err(|source| Error::Stats { net: fname, source })
+                })
+                .collect()
+        };
+        let stats = accumulate(activity)?;
+        let stashed_stats = if self.split_glitches {
+            Some((Activity::Glitches, accumulate(Activity::Glitches)?))
+        } else {
+            None
+        };
+
+        let mut net_names: Vec<_> = stats
+            .keys()
+            .map(|var_ref| {
+                let var = wave.hierarchy().get(var_ref.0);
+                (
+                    indexed_name(var.full_name(wave.hierarchy()), var),
+                    var_ref.0,
+                )
+            })
+            .collect();
+        net_names.sort_unstable_by(|a, b| a.0.cmp(&b.0));
+
+        let top_scope = self
+            .top_scope
+            .as_ref()
+            .map(|s| {
+                get_scope_by_full_name(wave.hierarchy(), s)
+                    .ok_or_else(|| Error::ScopeNotFound { scope: s.clone() })
+            })
+            .transpose()?;
+
+        let mut ctx = Context {
+            wave,
+            time_end,
+            timescale,
+            timescale_norm,
+            clk_freq,
+            clk_period,
+            stats,
+            activity,
+            stashed_stats,
+            num_of_iterations,
+            lookup_point,
+            scope_prefix_length: lookup_scope_name_prefix.len(),
+            netlist,
+            top: top.unwrap_or_default(),
+            top_scope,
+            blackboxes_only: self.blackboxes_only,
+            remove_virtual_pins: self.remove_virtual_pins,
+            ignore_date: self.ignore_date,
+            ignore_version: self.ignore_version,
+            export_empty: self.export_empty,
+            power_scope_prefix: self.limit_scope_power.unwrap_or(lookup_scope_name_prefix),
+            input_ports_activity: self.input_ports_activity,
+            sparse: self.sparse,
+            profile_depth: self.profile_depth,
+            profile_metric: self.profile_metric,
+            tcl_dialect: self.tcl_dialect,
+            saif_dialect: self.saif_dialect,
+            saif_divider: self.saif_divider,
+            saif_escaping: self.saif_escaping,
+            saif_bus: self.saif_bus,
+            saif_module_names: self.saif_module_names,
+            liberty,
+            wire_capacitance: self.wire_capacitance * 1e-12,
+            spef,
+            supply_voltage: self.supply_voltage,
+            energy_table,
+            power_domains,
+            warnings,
+            clocks,
+            clock_edges,
+            net_clocks,
+            pulse_filters,
+            filtered_changes,
+            net_names,
+            disabled_periods,
+            spef_nets: Vec::new(),
+            module_loads: HashMap::new(),
+        };
+        ctx.resolve_power_nets();
+        Ok(ctx)
+    }
+}
+
+impl From<&Args> for ContextBuilder {
+    fn from(args: &Args) -> Self {
+        Self {
+            input_file: args.input_file.clone(),
+            clk_freq: args.clk_freq,
+            clock_name: args.clock_name.clone(),
+            limit_scope: args.limit_scope.clone(),
+            limit_scope_power: args.limit_scope_power.clone(),
+            netlist: args.netlist.clone().map(NetlistSource::File),
+            top: args.top.clone(),
+            top_scope: args.top_scope.clone(),
+            blackboxes_only: args.blackboxes_only,
+            remove_virtual_pins: args.remove_virtual_pins,
+            ignore_date: args.ignore_date,
+            ignore_version: args.ignore_version,
+            per_clock_cycle: args.per_clock_cycle,
+            num_of_windows: None,
+            activity: if args.only_glitches {
+                Activity::Glitches
+            } else {
+                args.activity
+            },
+            split_glitches: args.split_glitches,
+            export_empty: args.export_empty,
+            input_ports_activity: args.input_ports_activity,
+            sparse: args.sparse,
+            profile_depth: args.profile_depth,
+            profile_metric: args.profile_metric,
+            tcl_dialect: args.tcl_dialect,
+            base_clock: args.base_clock.clone(),
+            saif_dialect: args.saif_dialect,
+            saif_divider: args.saif_divider,
+            saif_escaping: args.saif_escaping,
+            saif_bus: args.saif_bus,
+            saif_module_names: args.saif_module_names,
+            liberty: args
+                .liberty
+                .iter()
+                .cloned()
+                .map(LibertySource::File)
+                .collect(),
+            wire_capacitance: args.wire_capacitance,
+            spef: args.spef.clone().map(SpefSource::File),
+            supply_voltage: args.supply_voltage,
+            energy_table: args.energy_table.clone().map(EnergyTableSource::File),
+            upf: args.upf.clone().map(UpfSource::File),
+            clocks: args.clock.clone(),
+            min_pulse_width: args.min_pulse_width,
+            cell_pulse_widths: args.cell_pulse_width.iter().cloned().collect(),
+            keep_narrow_pulses: args.keep_narrow_pulses,
+            enable: args.enable.clone(),
+        }
+    }
+}
@@ -0,0 +1,529 @@
+// Copyright (c) 2024-2026 Antmicro <www.antmicro.com>
+// SPDX-License-Identifier: Apache-2.0
+
+// Clocks of a design: measurement of their periods in a trace and assignment of nets to their
+// domains
+
+use std::collections::HashMap;
+use std::str::FromStr;
+
+use wellen::{
+    GetItem, Hierarchy, SignalRef, TimeTableIdx, Timescale, Var, VarRef, simple::Waveform,
+};
+
+use crate::error::{Error, Warning};
+use crate::exporters::WindowInfo;
+use crate::netlist::{Module, Netlist};
+use crate::stats::{Activity, NetSettings, StatsError, cycle_toggles};
+use crate::upf::gating;
+use crate::util::{VarRefsIter, glob_match};
+use crate::{Context, HashVarRef, indexed_name};
+
+/// Periods longer than the median by this factor are treated as gaps in which the clock was
+/// stopped or gated
+const GAP_FACTOR: f64 = 1.5;
+/// Periods differing from the median by more than this fraction are treated as jitter
+const JITTER_TOLERANCE: f64 = 0.05;
+
+/// Clock frequencies given by the user and measured in a trace differing by more than this
+/// fraction are reported
+pub(crate) const FREQUENCY_TOLERANCE: f64 = 0.01;
+
+/// Period of a clock measured between its rising edges
+#[derive(Debug, Clone)]
+pub struct ClockMeasurement {
+    /// Median period (in trace time units)
+    pub period: wellen::Time,
+    /// Number of rising edges
+    pub edges: usize,
+    /// Number of periods differing from the median by more than 5%, excluding gaps
+    pub jittery_periods: usize,
+    /// Number of periods over 1.5 times longer than the median, in which the clock was stopped
+    pub gaps: usize,
+}
+
+/// Times of rising edges of the lowest bit of a clock signal
+pub fn rising_edges(wave: &Waveform, sig_ref: SignalRef) -> Result<Vec<wellen::Time>, StatsError> {
+    let sig = wave
+        .get_signal(sig_ref)
+        .ok_or(StatsError::SignalNotLoaded)?;
+
+    let mut edges = Vec::new();
+    let mut level = None;
+    for &time_idx in sig.time_indices() {
+        let time = time_value_at(wave, time_idx);
+        let offset = sig
+            .get_offset(time_idx)
+            .ok_or(StatsError::MissingValue { time })?;
+        let value = sig
+            .get_value_at(&offset, 0)
+            .to_bit_string()
+            .ok_or(StatsError::NotABitVector { time })?;
+        let bit = value.chars().last();
+        if bit == Some('1') && level == Some('0') {
+            edges.push(time);
+        }
+        if let Some(bit @ ('0' | '1')) = bit {
+            level = Some(bit);
+        }
+    }
+    Ok(edges)
+}
+
+/// Measure the period of a clock from rising edges of the lowest bit of its signal. Returns
+/// `None` if the signal has fewer than two rising edges.
+pub fn measure(
+    wave: &Waveform,
+    sig_ref: SignalRef,
+) -> Result<Option<ClockMeasurement>, StatsError> {
+    let edges = rising_edges(wave, sig_ref)?;
+    let mut periods: Vec<_> = edges.windows(2).map(|pair| pair[1] - pair[0]).collect();
+    if periods.is_empty() {
+        return Ok(None);
+    }
+    periods.sort_unstable();
+    let period = periods[periods.len() / 2];
+
+    let gap = period as f64 * GAP_FACTOR;
+    let gaps = periods.iter().filter(|&&p| p as f64 > gap).count();
+    let jittery_periods = periods
+        .iter()
+        .filter(|&&p| p as f64 <= gap)
+        .filter(|&&p| (p as f64 - period as f64).abs() > period as f64 * JITTER_TOLERANCE)
+        .count();
+
+    Ok(Some(ClockMeasurement {
+        period,
+        edges: edges.len(),
+        jittery_periods,
+        gaps,
+    }))
+}
+
+fn time_value_at(wave: &Waveform, ti: TimeTableIdx) -> u64 {
+    wave.time_table()[ti as usize]
+}
+
+/// Predicate selecting nets (given by their full names) which match a clock
+type Matcher<'a> = dyn Fn(&str, &Var) -> bool + 'a;
+
+/// Find the signal of a clock given by the path of its net, relative to the lookup scope (whose
+/// full name followed by a `.` is given as `prefix`) or absolute, i.e. starting with the name of a
+/// top-level scope of the trace. Paths can contain glob patterns. If no net has the given path,
+/// the clock is looked up by the name of the net within the lookup scope. Nets sharing a signal
+/// are aliases of the same clock, while nets with different signals make the clock ambiguous.
+/// Returns the signal together with the name of its net without the path.
+pub(crate) fn find_clock(
+    hier: &Hierarchy,
+    prefix: &str,
+    clock: &str,
+) -> Result<Option<(SignalRef, String)>, Error> {
+    let absolute = clock.split('.').next().is_some_and(|first| {
+        hier.scopes()
+            .any(|scope_ref| hier.get(scope_ref).name(hier) == first)
+    });
+    let path = match absolute {
+        true => clock.to_string(),
+        false => format!("{prefix}{clock}"),
+    };
+    let is_glob = clock.contains(['*', '?']);
+    let vars: Vec<_> = hier
+        .var_refs_iter()
+        .map(|var_ref| hier.get(var_ref))
+        .map(|var| (var.full_name(hier), var))
+        .collect();
+
+    let by_path = |name: &str, _: &Var| name == path || name == clock;
+    let by_glob = |name: &str, _: &Var| is_glob && glob_match(&path, name);
+    let by_name = |name: &str, var: &Var| name.starts_with(prefix) && var.name(hier) == clock;
+    let strategies: [&Matcher; 3] = [&by_path, &by_glob, &by_name];
+    for matches in strategies {
+        let mut signals: HashMap<SignalRef, (&str, &Var)> = HashMap::new();
+        for (name, var) in vars.iter().filter(|(name, var)| matches(name, var)) {
+            let entry = signals.entry(var.signal_ref()).or_insert((name, var));
+            if name.as_str() < entry.0 {
+                *entry = (name, var);
+            }
+        }
+        match signals.len() {
+            0 => continue,
+            1 => {
+                return Ok(signals
+                    .into_iter()
+                    .next()
+                    .map(|(sig_ref, (_, var))| (sig_ref, var.name(hier).to_string())));
+            }
+            _ => {
+                let mut candidates: Vec<_> = signals
+                    .into_values()
+                    .map(|(name, _)| String::from(name))
+                    .collect();
+                candidates.sort();
+                return Err(Error::AmbiguousClock {
+                    clock: clock.to_string(),
+                    candidates,
+                });
+            }
+        }
+    }
+    Ok(None)
+}
+
+/// Frequency of a clock (in Hz). A frequency given by the user is checked against the one measured
+/// in the trace, which is used in its place if it's missing. Problems with the clock are reported
+/// as warnings.
+pub(crate) fn resolve_frequency(
+    wave: &Waveform,
+    timescale: Timescale,
+    clock: &str,
+    sig_ref: Option<SignalRef>,
+    given: Option<f64>,
+    warnings: &mut Vec<Warning>,
+) -> Result<Option<f64>, Error> {
+    let measurement = match sig_ref {
+        Some(sig_ref) => measure(wave, sig_ref).map_err(|source| Error::Stats {
+            net: clock.to_string(),
+            source,
+        })?,
+        None => None,
+    };
+    let measured = measurement.map(|measurement| {
+        if measurement.jittery_periods > 0 {
+            warnings.push(Warning::ClockJitter {
+                clock: clock.to_string(),
+                periods: measurement.jittery_periods,
+            });
+        }
+        if measurement.gaps > 0 {
+            warnings.push(Warning::ClockGaps {
+                clock: clock.to_string(),
+                gaps: measurement.gaps,
+            });
+        }
+        // Dividing by a positive power of ten keeps round frequencies exact. The time unit has
+        // been checked while loading the trace.
+        let exponent = timescale.unit.to_exponent().unwrap_or_default();
+        (10.0_f64).powi(-exponent as i32) / (measurement.period as f64 * timescale.factor as f64)
+    });
+
+    if let (Some(given), Some(measured)) = (given, measured) {
+        if ((given - measured) / measured).abs() > FREQUENCY_TOLERANCE {
+            warnings.push(Warning::ClockFrequencyMismatch {
+                clock: clock.to_string(),
+                given,
+                measured,
+            });
+        }
+    }
+    Ok(given.or(measured))
+}
+
+/// Clock declared by the user as `NAME[=FREQ][@PATTERN,...]`. Nets matching the patterns belong to
+/// the domain of the clock. Without a frequency, it's measured on the net called `NAME`.
+#[derive(Debug, Clone)]
+pub struct ClockSpec {
+    /// Name of the clock net
+    pub name: String,
+    /// Frequency (in Hz)
+    pub freq: Option<f64>,
+    /// Glob patterns of nets in the domain of the clock. Patterns without wildcards match a net or
+    /// a scope together with everything under it.
+    pub patterns: Vec<String>,
+}
+
+impl FromStr for ClockSpec {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (clock, patterns) = match s.split_once('@') {
+            Some((clock, patterns)) => (clock, patterns.split(',').map(String::from).collect()),
+            None => (s, Vec::new()),
+        };
+        let (name, freq) = match clock.split_once('=') {
+            Some((name, freq)) => (
+                name,
+                Some(
+                    freq.parse()
+                        .map_err(|_| format!("`{freq}` is not a valid frequency"))?,
+                ),
+            ),
+            None => (clock, None),
+        };
+        if name.is_empty() {
+            return Err("clock has no name".to_string());
+        }
+        Ok(Self {
+            name: name.to_string(),
+            freq,
+            patterns,
+        })
+    }
+}
+
+impl ClockSpec {
+    /// Whether a net with a given full name matches one of the patterns
+    pub fn matches(&self, net: &str) -> bool {
+        self.patterns.iter().any(|pattern| {
+            if pattern.contains(['*', '?']) {
+                glob_match(pattern, net)
+            } else {
+                net == pattern
+                    || net
+                        .strip_prefix(pattern.as_str())
+                        .is_some_and(|rest| rest.starts_with('.'))
+            }
+        })
+    }
+}
+
+/// Clock whose period nets of its domain are normalized to
+#[derive(Debug, Clone)]
+pub struct ClockDomain {
+    /// Name of the clock net, empty if the clock wasn't named
+    pub name: String,
+    /// Frequency (in Hz)
+    pub freq: f64,
+    /// Period (in s)
+    pub period: f64,
+    /// Name of the clock in sign-off tools, which PrimeTime toggle rates of its nets are relative
+    /// to
+    pub base_clock: Option<String>,
+}
+
+/// Nets and instances assigned to clock domains through clock pins of cells in a netlist
+#[derive(Debug, Default)]
+pub(crate) struct NetlistClocks {
+    /// Full names of nets driven by clocked cells and of clock nets
+    pub nets: HashMap<String, usize>,
+    /// Full names of clocked cell instances, each followed by a `.`
+    pub instances: Vec<(String, usize)>,
+}
+
+impl NetlistClocks {
+    /// Follow clock nets from the top module of a netlist, whose instance has a given prefix of
+    /// full names in the trace
+    pub fn trace(netlist: &Netlist, top: &str, prefix: &str, clocks: &[ClockDomain]) -> Self {
+        let mut assignment = Self::default();
+        let Some(module) = netlist.modules.get(top) else {
+            return assignment;
+        };
+        let mut clock_bits = HashMap::new();
+        for (index, clock) in clocks.iter().enumerate() {
+            // Clocks can be given by their paths in the trace
+            let name = clock.name.rsplit('.').next().unwrap_or_default();
+            for bit in module
+                .netnames
+                .get(name)
+                .iter()
+                .flat_map(|net| &net.bits)
+                .filter_map(serde_json::Value::as_u64)
+            {
+                clock_bits.entry(bit).or_insert(index);
+            }
+        }
+        assignment.visit(netlist, module, prefix, &clock_bits);
+        assignment
+    }
+
+    fn visit(
+        &mut self,
+        netlist: &Netlist,
+        module: &Module,
+        prefix: &str,
+        clock_bits: &HashMap<u64, usize>,
+    ) {
+        let clock_of = |bits: &[serde_json::Value]| {
+            bits.iter()
+                .filter_map(serde_json::Value::as_u64)
+                .find_map(|bit| clock_bits.get(&bit).copied())
+        };
+
+        // Bits driven by clocked cells, which switch at the edges of their clocks
+        let mut driven = clock_bits.clone();
+        let mut cells: Vec<_> = module.cells.iter().collect();
+        cells.sort_by_key(|(name, _)| *name);
+        for (name, cell) in cells {
+            let Some(clock) = cell.connections.values().find_map(|bits| clock_of(bits)) else {
+                continue;
+            };
+            let instance = format!("{prefix}{name}.");
+            match cell.get_module(netlist) {
+                Some(child) => {
+                    // Follow the clock into a submodule through its ports
+                    let mut child_bits = HashMap::new();
+                    for (port, bits) in &cell.connections {
+                        let Some(port) = child.ports.get(port) else {
+                            continue;
+                        };
+                        for (bit, child_bit) in bits.iter().zip(&port.bits) {
+                            if let (Some(clock), Some(child_bit)) =
+                                (clock_of(std::slice::from_ref(bit)), child_bit.as_u64())
+                            {
+                                child_bits.insert(child_bit, clock);
+                            }
+                        }
+                    }
+                    self.visit(netlist, child, &instance, &child_bits);
+                }
+                None => {
+                    self.instances.push((instance, clock));
+                    for (port, bits) in &cell.connections {
+                        // Without directions, outputs are recognized by the names used for
+                        // outputs of flip-flops and latches (`Q`, `QN`, `Q_N`)
+                        let output = match cell.port_directions.get(port) {
+                            Some(direction) => direction == "output",
+                            None => port.starts_with('Q'),
+                        };
+                        if output {
+                            for bit in bits.iter().filter_map(serde_json::Value::as_u64) {
+                                driven.entry(bit).or_insert(clock);
+                            }
+                        }
+                    }
+                }
+            }
+        }
+
+        for (name, net) in &module.netnames {
+            if let Some(clock) = net
+                .bits
+                .iter()
+                .filter_map(serde_json::Value::as_u64)
+                .find_map(|bit| driven.get(&bit).copied())
+            {
+                self.nets.insert(format!("{prefix}{name}"), clock);
+            }
+        }
+    }
+
+    /// Clock of a net with a given full name, found directly or through the innermost clocked
+    /// instance containing it
+    pub fn clock_of(&self, net: &str) -> Option<usize> {
+        self.nets.get(net).copied().or_else(|| {
+            self.instances
+                .iter()
+                .filter(|(instance, _)| net.starts_with(instance.as_str()))
+                .max_by_key(|(instance, _)| instance.len())
+                .map(|&(_, clock)| clock)
+        })
+    }
+}
+
+impl Context {
+    /// Primary clock followed by clocks declared by the user
+    pub fn clocks(&self) -> &[ClockDomain] {
+        &self.clocks
+    }
+
+    /// Index of the clock in whose domain a net is
+    pub(crate) fn net_clock(&self, var_ref: VarRef) -> usize {
+        self.net_clocks
+            .get(&HashVarRef(var_ref))
+            .copied()
+            .unwrap_or_default()
+    }
+
+    /// Length of a time window expressed in cycles of each clock
+    pub(crate) fn window_cycles(&self, window: &WindowInfo) -> Vec<f64> {
+        let duration_s = window.active as f64 * self.timescale_norm;
+        self.clocks
+            .iter()
+            .map(|clock| duration_s / clock.period)
+            .collect()
+    }
+}
+
+/// Activity of nets in the domain of a clock within one of its cycles
+#[derive(Debug, Clone)]
+pub struct ClockCycleActivity {
+    /// Index of the clock among `Context::clocks`
+    pub clock: usize,
+    /// Index of the cycle within the time window
+    pub cycle: usize,
+    pub start: wellen::Time,
+    pub end: wellen::Time,
+    /// Number of nets (bits) in the domain
+    pub nets: usize,
+    /// Toggles of all nets in the domain
+    pub toggles: f64,
+}
+
+impl Context {
+    /// Start of the window followed by the end of each cycle of a clock within it. Cycles are
+    /// delimited by rising edges of the clock, or follow its nominal period if the clock wasn't
+    /// found in the trace.
+    fn cycle_boundaries(&self, clock: usize, window: &WindowInfo) -> Vec<wellen::Time> {
+        let edges = &self.clock_edges[clock];
+        let mut boundaries = vec![window.start];
+        if edges.is_empty() {
+            let period = self.clocks[clock].period / self.timescale_norm;
+            let length = (period.round() as wellen::Time).max(1);
+            boundaries.extend((window.start + length..window.end).step_by(length as usize));
+        } else {
+            let first = edges.partition_point(|&edge| edge <= window.start);
+            let last = edges.partition_point(|&edge| edge < window.end);
+            boundaries.extend(&edges[first..last]);
+        }
+        boundaries.push(window.end);
+        boundaries
+    }
+
+    /// Activity of the domain of each clock in consecutive cycles of the clock within a given
+    /// iteration (time window). Cycles start at rising edges of the clock, so the first and the
+    /// last cycle of a window may be partial.
+    pub fn clock_activity(&self, iteration: usize) -> Result<Vec<ClockCycleActivity>, Error> {
+        let hier = self.wave.hierarchy();
+        let window = self.window(iteration);
+        let mut activity = Vec::new();
+        for index in 0..self.clocks.len() {
+            let boundaries = self.cycle_boundaries(index, &window);
+            let mut cycles: Vec<_> = boundaries
+                .windows(2)
+                .enumerate()
+                .map(|(cycle, bounds)| ClockCycleActivity {
+                    clock: index,
+                    cycle,
+                    start: bounds[0],
+                    end: bounds[1],
+                    nets: 0,
+                    toggles: 0.0,
+                })
+                .collect();
+
+            let nets = self
+                .stats
+                .keys()
+                .filter(|var_ref| self.net_clock(var_ref.0) == index);
+            for var_ref in nets {
+                let var = hier.get(var_ref.0);
+                let name = indexed_name(var.full_name(hier), var);
+                if !self.in_power_scope(&name) {
+                    continue;
+                }
+                let (off_periods, switched_off) =
+                    gating(&self.power_domains, &self.disabled_periods, &name);
+                let settings = NetSettings {
+                    activity: Activity::All,
+                    clock_signals: &[],
+                    clock_edges: &[],
+                    off_periods,
+                    switched_off,
+                    disabled: &self.disabled_periods,
+                    filtered: self
+                        .pulse_filters
+                        .get(var_ref)
+                        .and_then(|&filter| self.filtered_changes.get(&(var.signal_ref(), filter))),
+                };
+                let toggles = cycle_toggles(&self.wave, var.signal_ref(), &boundaries, &settings)
+                    .map_err(|source| Error::Stats { net: name, source })?;
+                let bits = self.stats[var_ref][iteration].bits().len();
+                for (cycle, toggles) in cycles.iter_mut().zip(toggles) {
+                    cycle.nets += bits;
+                    cycle.toggles += toggles as f64 / 2.0;
+                }
+            }
+            activity.extend(cycles);
+        }
+        Ok(activity)
+    }
+}
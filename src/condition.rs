@@ -0,0 +1,353 @@
+// Copyright (c) 2024-2026 Antmicro <www.antmicro.com>
+// SPDX-License-Identifier: Apache-2.0
+
+// Conditions over trace signals which qualify the time in which activity is accumulated
+
+use std::fmt;
+use std::str::FromStr;
+
+use wellen::{SignalRef, simple::Waveform};
+
+use crate::stats::{OffPeriods, StatsError};
+
+/// Error encountered while parsing a condition
+#[derive(Debug, Clone)]
+pub struct ParseError {
+    /// Position (in characters) at which the error was found
+    pub position: usize,
+    pub message: String,
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "at {}: {}", self.position, self.message)
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+/// Boolean expression over signals of a trace, e.g. `busy && !(state == 4'b0011)`. A signal on
+/// its own holds if its value is known and non-zero. Signals without a value or with X/Z bits
+/// are unknown, and so is any part of the condition they decide, including its negation.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Condition {
+    Signal(String),
+    /// Signal holding a given value
+    Equal(String, u128),
+    Not(Box<Condition>),
+    And(Box<Condition>, Box<Condition>),
+    Or(Box<Condition>, Box<Condition>),
+}
+
+impl fmt::Display for Condition {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Signal(name) => write!(f, "{name}"),
+            Self::Equal(name, value) => write!(f, "{name} == {value}"),
+            Self::Not(condition) => match condition.as_ref() {
+                Self::Equal(name, value) => write!(f, "{name} != {value}"),
+                Self::Signal(_) => write!(f, "!{condition}"),
+                _ => write!(f, "!({condition})"),
+            },
+            Self::And(left, right) => write!(f, "({left} && {right})"),
+            Self::Or(left, right) => write!(f, "({left} || {right})"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Token {
+    Name(String),
+    Number(u128),
+    Not,
+    And,
+    Or,
+    Equal,
+    NotEqual,
+    Open,
+    Close,
+}
+
+fn is_name_char(c: char) -> bool {
+    c.is_ascii_alphanumeric() || matches!(c, '_' | '.' | '$' | '[' | ']')
+}
+
+/// Parse a number given in decimal, as `0x`/`0b` followed by digits or as a Verilog literal
+/// (`4'b0011`, `'hff`, `8'd12`)
+fn parse_number(literal: &str) -> Option<u128> {
+    let literal = literal.replace('_', "");
+    let (radix, digits) = if let Some((_, based)) = literal.split_once('\'') {
+        let mut chars = based.chars();
+        let radix = match chars.next()?.to_ascii_lowercase() {
+            'b' => 2,
+            'o' => 8,
+            'd' => 10,
+            'h' => 16,
+            _ => return None,
+        };
+        (radix, chars.as_str().to_string())
+    } else if let Some(digits) = literal.strip_prefix("0x") {
+        (16, digits.to_string())
+    } else if let Some(digits) = literal.strip_prefix("0b") {
+        (2, digits.to_string())
+    } else {
+        (10, literal)
+    };
+    u128::from_str_radix(&digits, radix).ok()
+}
+
+fn tokenize(source: &str) -> Result<Vec<(usize, Token)>, ParseError> {
+    let chars: Vec<char> = source.chars().collect();
+    let mut tokens = Vec::new();
+    let mut position = 0;
+    while position < chars.len() {
+        let start = position;
+        let pair = chars[position..].iter().take(2).collect::<String>();
+        let token = match chars[position] {
+            c if c.is_whitespace() => {
+                position += 1;
+                continue;
+            }
+            _ if pair == "&&" => Token::And,
+            _ if pair == "||" => Token::Or,
+            _ if pair == "==" => Token::Equal,
+            _ if pair == "!=" => Token::NotEqual,
+            '!' | '~' => Token::Not,
+            '(' => Token::Open,
+            ')' => Token::Close,
+            c if c.is_ascii_digit() || c == '\'' => {
+                while position < chars.len()
+                    && (chars[position].is_ascii_alphanumeric()
+                        || matches!(chars[position], '\'' | '_'))
+                {
+                    position += 1;
+                }
+                let literal: String = chars[start..position].iter().collect();
+                let number = parse_number(&literal).ok_or_else(|| ParseError {
+                    position: start,
+                    message: format!("`{literal}` is not a valid number"),
+                })?;
+                tokens.push((start, Token::Number(number)));
+                continue;
+            }
+            c if is_name_char(c) => {
+                while position < chars.len() && is_name_char(chars[position]) {
+                    position += 1;
+                }
+                tokens.push((start, Token::Name(chars[start..position].iter().collect())));
+                continue;
+            }
+            c => {
+                return Err(ParseError {
+                    position,
+                    message: format!("unexpected character `{c}`"),
+                });
+            }
+        };
+        position += match token {
+            Token::And | Token::Or | Token::Equal | Token::NotEqual => 2,
+            _ => 1,
+        };
+        tokens.push((start, token));
+    }
+    Ok(tokens)
+}
+
+/// Recursive descent parser of conditions
+struct Parser {
+    tokens: Vec<(usize, Token)>,
+    next: usize,
+    /// Length of the source, reported as the position of errors at its end
+    end: usize,
+}
+
+impl Parser {
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.next).map(|(_, token)| token)
+    }
+
+    fn error(&self, message: &str) -> ParseError {
+        ParseError {
+            position: self
+                .tokens
+                .get(self.next)
+                .map_or(self.end, |&(position, _)| position),
+            message: message.to_string(),
+        }
+    }
+
+    fn or(&mut self) -> Result<Condition, ParseError> {
+        let mut condition = self.and()?;
+        while self.peek() == Some(&Token::Or) {
+            self.next += 1;
+            condition = Condition::Or(Box::new(condition), Box::new(self.and()?));
+        }
+        Ok(condition)
+    }
+
+    fn and(&mut self) -> Result<Condition, ParseError> {
+        let mut condition = self.unary()?;
+        while self.peek() == Some(&Token::And) {
+            self.next += 1;
+            condition = Condition::And(Box::new(condition), Box::new(self.unary()?));
+        }
+        Ok(condition)
+    }
+
+    fn unary(&mut self) -> Result<Condition, ParseError> {
+        match self.peek().cloned() {
+            Some(Token::Not) => {
+                self.next += 1;
+                Ok(Condition::Not(Box::new(self.unary()?)))
+            }
+            Some(Token::Open) => {
+                self.next += 1;
+                let condition = self.or()?;
+                if self.peek() != Some(&Token::Close) {
+                    return Err(self.error("expected `)`"));
+                }
+                self.next += 1;
+                Ok(condition)
+            }
+            Some(Token::Name(name)) => {
+                self.next += 1;
+                let negated = match self.peek() {
+                    Some(Token::Equal) => false,
+                    Some(Token::NotEqual) => true,
+                    _ => return Ok(Condition::Signal(name)),
+                };
+                self.next += 1;
+                let Some(Token::Number(value)) = self.peek().cloned() else {
+                    return Err(self.error("expected a number"));
+                };
+                self.next += 1;
+                let condition = Condition::Equal(name, value);
+                Ok(match negated {
+                    false => condition,
+                    true => Condition::Not(Box::new(condition)),
+                })
+            }
+            _ => Err(self.error("expected a signal, `!` or `(`")),
+        }
+    }
+}
+
+impl FromStr for Condition {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut parser = Parser {
+            tokens: tokenize(s)?,
+            next: 0,
+            end: s.chars().count(),
+        };
+        let condition = parser.or()?;
+        if parser.next < parser.tokens.len() {
+            return Err(parser.error("unexpected token"));
+        }
+        Ok(condition)
+    }
+}
+
+/// Value of a bit string, `None` if it has unknown bits or doesn't fit
+fn value_of(bits: &str) -> Option<u128> {
+    u128::from_str_radix(bits.get(bits.len().saturating_sub(128)..)?, 2)
+        .ok()
+        .filter(|_| bits.len() <= 128 || !bits[..bits.len() - 128].contains(|c| c != '0'))
+}
+
+impl Condition {
+    /// Names of signals the condition depends on, in order of appearance
+    pub fn signals(&self) -> Vec<&str> {
+        match self {
+            Self::Signal(name) | Self::Equal(name, _) => vec![name.as_str()],
+            Self::Not(condition) => condition.signals(),
+            Self::And(left, right) | Self::Or(left, right) => {
+                let mut signals = left.signals();
+                signals.extend(right.signals());
+                signals
+            }
+        }
+    }
+
+    /// Evaluate the condition given the current value of each signal, `None` if it is unknown.
+    /// Unknown values propagate, so the result is `None` unless the known values decide it.
+    fn eval(&self, value: &dyn Fn(&str) -> Option<u128>) -> Option<bool> {
+        match self {
+            Self::Signal(name) => value(name).map(|value| value != 0),
+            Self::Equal(name, expected) => value(name).map(|value| value == *expected),
+            Self::Not(condition) => condition.eval(value).map(|holds| !holds),
+            Self::And(left, right) => match (left.eval(value), right.eval(value)) {
+                (Some(false), _) | (_, Some(false)) => Some(false),
+                (Some(true), Some(true)) => Some(true),
+                _ => None,
+            },
+            Self::Or(left, right) => match (left.eval(value), right.eval(value)) {
+                (Some(true), _) | (_, Some(true)) => Some(true),
+                (Some(false), Some(false)) => Some(false),
+                _ => None,
+            },
+        }
+    }
+
+    /// Time spans in which the condition doesn't hold. `signals` gives the signal of each name
+    /// returned by [`Condition::signals`]. A condition that is unknown because of signals
+    /// without values or with X/Z bits doesn't hold.
+    pub(crate) fn disabled_periods(
+        &self,
+        wave: &Waveform,
+        signals: &[SignalRef],
+    ) -> Result<OffPeriods, StatsError> {
+        let names = self.signals();
+        let mut changes = Vec::new();
+        for (index, &sig_ref) in signals.iter().enumerate() {
+            let sig = wave
+                .get_signal(sig_ref)
+                .ok_or(StatsError::SignalNotLoaded)?;
+            for &time_idx in sig.time_indices() {
+                let time = wave.time_table()[time_idx as usize];
+                let offset = sig
+                    .get_offset(time_idx)
+                    .ok_or(StatsError::MissingValue { time })?;
+                let bits = sig
+                    .get_value_at(&offset, 0)
+                    .to_bit_string()
+                    .ok_or(StatsError::NotABitVector { time })?;
+                changes.push((time, index, value_of(&bits)));
+            }
+        }
+        changes.sort_by_key(|&(time, index, _)| (time, index));
+
+        let mut values = vec![None; signals.len()];
+        let mut periods = Vec::new();
+        let mut disabled_since = Some(0);
+        for (position, &(time, index, value)) in changes.iter().enumerate() {
+            values[index] = value;
+            // Evaluate once all changes at a time stamp are applied
+            if changes
+                .get(position + 1)
+                .is_some_and(|&(next, _, _)| next == time)
+            {
+                continue;
+            }
+            let value = |name: &str| {
+                let index = names.iter().position(|&signal| signal == name)?;
+                values[index]
+            };
+            match (disabled_since, self.eval(&value) == Some(true)) {
+                (Some(start), true) => {
+                    if time > start {
+                        periods.push((start, time));
+                    }
+                    disabled_since = None;
+                }
+                (None, false) => disabled_since = Some(time),
+                _ => {}
+            }
+        }
+        if let Some(start) = disabled_since {
+            periods.push((start, wellen::Time::MAX));
+        }
+        Ok(OffPeriods::from_spans(periods))
+    }
+}
@@ -0,0 +1,107 @@
+// Copyright (c) 2024-2026 Antmicro <www.antmicro.com>
+// SPDX-License-Identifier: Apache-2.0
+
+// User-supplied model of energy dissipated by cells when their pins toggle, for designs without
+// Liberty libraries
+
+use std::collections::HashMap;
+use std::fmt;
+
+/// Error encountered while parsing an energy table
+#[derive(Debug, Clone)]
+pub struct ParseError {
+    /// Line at which the error was found
+    pub line: usize,
+    pub message: String,
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "line {}: {}", self.line, self.message)
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+const HEADER: [&str; 5] = ["cell", "pin", "rise", "fall", "leakage"];
+
+/// Energy dissipated by transitions of a pin (in J)
+#[derive(Debug, Clone, Default)]
+pub struct PinEnergy {
+    pub rise: f64,
+    pub fall: f64,
+}
+
+/// Energy model of a cell type or a module
+#[derive(Debug, Clone, Default)]
+pub struct CellEnergy {
+    pub pins: HashMap<String, PinEnergy>,
+    /// Leakage power (in W)
+    pub leakage: f64,
+}
+
+/// Energy models of cells, read from a CSV table with `cell,pin,rise,fall,leakage` columns.
+/// Energy of rising and falling transitions is given in pJ and leakage in nW. Leakage of a cell is
+/// the sum of the `leakage` column over all its rows, so it can be given in a row without a pin.
+/// Empty fields are treated as zeros and lines starting with `#` are skipped.
+#[derive(Debug, Clone, Default)]
+pub struct EnergyTable {
+    pub cells: HashMap<String, CellEnergy>,
+}
+
+impl EnergyTable {
+    pub fn parse(source: &str) -> Result<Self, ParseError> {
+        let mut table = Self::default();
+        let mut lines = source
+            .lines()
+            .enumerate()
+            .map(|(index, line)| (index + 1, line.trim()))
+            .filter(|(_, line)| !line.is_empty() && !line.starts_with('#'));
+
+        let Some((line, header)) = lines.next() else {
+            return Ok(table);
+        };
+        let columns: Vec<_> = header.split(',').map(str::trim).collect();
+        if columns != HEADER {
+            return Err(ParseError {
+                line,
+                message: format!("expected a `{}` header", HEADER.join(",")),
+            });
+        }
+
+        for (line, row) in lines {
+            let fields: Vec<_> = row.split(',').map(str::trim).collect();
+            let [cell, pin, rise, fall, leakage] = fields.as_slice() else {
+                return Err(ParseError {
+                    line,
+                    message: format!("expected {} fields", HEADER.len()),
+                });
+            };
+            let number = |value: &str| -> Result<f64, ParseError> {
+                if value.is_empty() {
+                    return Ok(0.0);
+                }
+                value.parse().map_err(|_| ParseError {
+                    line,
+                    message: format!("`{value}` is not a number"),
+                })
+            };
+            let entry = table.cells.entry(cell.to_string()).or_default();
+            entry.leakage += number(leakage)? * 1e-9;
+            if !pin.is_empty() {
+                entry.pins.insert(
+                    pin.to_string(),
+                    PinEnergy {
+                        rise: number(rise)? * 1e-12,
+                        fall: number(fall)? * 1e-12,
+                    },
+                );
+            }
+        }
+        Ok(table)
+    }
+
+    pub fn cell(&self, name: &str) -> Option<&CellEnergy> {
+        self.cells.get(name)
+    }
+}
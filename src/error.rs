@@ -0,0 +1,272 @@
+// Copyright (c) 2024-2026 Antmicro <www.antmicro.com>
+// SPDX-License-Identifier: Apache-2.0
+
+use std::{fmt, io, path};
+
+use crate::liberty::ParseError;
+use crate::stats::{Activity, StatsError};
+
+/// Errors that can be reported while loading a trace, computing its statistics or exporting them
+#[derive(Debug)]
+#[non_exhaustive]
+pub enum Error {
+    /// Opening, reading or creating a file failed
+    Io {
+        path: path::PathBuf,
+        source: io::Error,
+    },
+    /// Writing the output failed
+    Output(io::Error),
+    /// The trace could not be parsed
+    Waveform(wellen::WellenError),
+    /// The netlist file could not be parsed
+    Netlist {
+        path: path::PathBuf,
+        source: serde_json::Error,
+    },
+    /// No clock frequency was provided
+    MissingClockFrequency,
+    /// A clock name doesn't match any net
+    ClockNotFound { clock: String },
+    /// A clock name matches nets with different signals
+    AmbiguousClock {
+        clock: String,
+        candidates: Vec<String>,
+    },
+    /// Glitches were requested, but a clock whose edges delimit cycles of its nets isn't in the
+    /// trace
+    ClockEdgesUnknown { clock: String },
+    /// Activity of a kind the context wasn't built with was requested
+    ActivityNotAccumulated { activity: Activity },
+    /// Splitting glitches from functional activity was requested without an output path
+    MissingOutput,
+    /// Frequency of a declared clock wasn't given and couldn't be measured in the trace
+    ClockNotMeasured { clock: String },
+    /// The trace has no timescale
+    MissingTimescale,
+    /// The trace uses a time unit that can't be converted into seconds
+    UnknownTimeUnit,
+    /// The trace contains no value changes
+    EmptyWaveform,
+    /// A scope given by the user doesn't exist in the trace
+    ScopeNotFound { scope: String },
+    /// The top module isn't defined in the netlist
+    TopModuleNotFound { module: String },
+    /// A scope of the trace has no corresponding cell in its parent netlist module
+    CellNotFound { scope: String, cell: String },
+    /// Traversal attempted to descend into a scope below a blackbox
+    BlackBoxDescent { scope: String },
+    /// Writing a Parquet file failed
+    #[cfg(feature = "parquet")]
+    Parquet(parquet::errors::ParquetError),
+    /// No exporter is registered under a requested name
+    UnknownExporter { name: String },
+    /// Statistics of a net couldn't be computed
+    Stats { net: String, source: StatsError },
+    /// A Liberty file could not be parsed
+    Liberty {
+        file: std::path::PathBuf,
+        source: ParseError,
+    },
+    /// A SPEF file could not be parsed
+    Spef {
+        file: std::path::PathBuf,
+        source: crate::spef::ParseError,
+    },
+    /// An energy table could not be parsed
+    EnergyTable {
+        file: std::path::PathBuf,
+        source: crate::energy::ParseError,
+    },
+    /// A UPF file could not be parsed
+    Upf {
+        file: std::path::PathBuf,
+        source: crate::upf::ParseError,
+    },
+    /// A net given by the user doesn't exist in the trace
+    NetNotFound { net: String },
+    /// Power estimation was requested without a Liberty library or an energy table
+    MissingLiberty,
+    /// Power estimation was requested without a netlist
+    MissingNetlist,
+    /// Net switching power was requested without a SPEF file
+    MissingSpef,
+    /// A SAIF file for Vivado was requested without the scope of the DUT
+    MissingTopScope,
+    /// Net switching power was requested without a supply voltage or a Liberty library
+    MissingSupplyVoltage,
+}
+
+pub type Result<T> = std::result::Result<T, Error>;
+
+/// Problems which don't stop processing a trace, but may make the results inaccurate
+#[derive(Debug, Clone)]
+pub enum Warning {
+    /// Clock frequency given by the user differs from the one measured in the trace (in Hz)
+    ClockFrequencyMismatch {
+        clock: String,
+        given: f64,
+        measured: f64,
+    },
+    /// Some periods of the clock differ from its median period
+    ClockJitter { clock: String, periods: usize },
+    /// The clock stops in some parts of the trace
+    ClockGaps { clock: String, gaps: usize },
+    /// The enable condition doesn't hold anywhere in the trace, so no activity is accumulated
+    EnableNeverHolds { condition: String },
+}
+
+impl fmt::Display for Warning {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::ClockFrequencyMismatch {
+                clock,
+                given,
+                measured,
+            } => write!(
+                f,
+                "Clock frequency of {given} Hz differs from {measured} Hz measured on `{clock}`"
+            ),
+            Self::ClockJitter { clock, periods } => write!(
+                f,
+                "Periods of clock `{clock}` differ from its median period by over 5% \
+                 ({periods} times)"
+            ),
+            Self::ClockGaps { clock, gaps } => {
+                write!(f, "Clock `{clock}` stops in the trace ({gaps} times)")
+            }
+            Self::EnableNeverHolds { condition } => {
+                write!(f, "Enable condition `{condition}` never holds in the trace")
+            }
+        }
+    }
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Io { path, source } => write!(f, "I/O error on `{}`: {source}", path.display()),
+            Self::Output(err) => write!(f, "Couldn't write the output: {err}"),
+            Self::Waveform(err) => write!(f, "Couldn't load the trace: {err}"),
+            Self::Netlist { path, source } => write!(
+                f,
+                "Couldn't parse netlist file `{}`: {source}",
+                path.display()
+            ),
+            Self::MissingClockFrequency => write!(f, "Clock frequency was not provided"),
+            Self::AmbiguousClock { clock, candidates } => write!(
+                f,
+                "Clock `{clock}` is ambiguous, it matches: {}",
+                candidates.join(", ")
+            ),
+            Self::ClockNotFound { clock } => {
+                write!(f, "Clock `{clock}` was not found in the trace")
+            }
+            Self::ClockEdgesUnknown { clock } if clock.is_empty() => write!(
+                f,
+                "Glitches can't be told apart from functional activity without `--clock-name`"
+            ),
+            Self::ClockEdgesUnknown { clock } => write!(
+                f,
+                "Clock `{clock}` was not found in the trace, so glitches can't be told apart \
+                 from functional activity"
+            ),
+            Self::ActivityNotAccumulated { activity } => {
+                write!(f, "Activity `{activity}` was not accumulated")
+            }
+            Self::MissingOutput => write!(
+                f,
+                "Output path is required to split glitches from functional activity"
+            ),
+            Self::ClockNotMeasured { clock } => write!(
+                f,
+                "Frequency of clock `{clock}` was not provided and couldn't be measured"
+            ),
+            Self::MissingTimescale => write!(f, "Trace file doesn't contain a timescale"),
+            Self::UnknownTimeUnit => write!(f, "Trace file uses an unknown time unit"),
+            Self::EmptyWaveform => write!(f, "Trace file doesn't contain any value changes"),
+            Self::ScopeNotFound { scope } => write!(f, "Scope `{scope}` not found in the trace"),
+            Self::TopModuleNotFound { module } => {
+                write!(f, "Top module `{module}` was not found in the netlist")
+            }
+            Self::CellNotFound { scope, cell } => {
+                write!(
+                    f,
+                    "Cell `{cell}` of scope `{scope}` not found in the netlist"
+                )
+            }
+            Self::BlackBoxDescent { scope } => {
+                write!(f, "Attempted to descend into blackbox `{scope}`")
+            }
+            #[cfg(feature = "parquet")]
+            Self::Parquet(err) => write!(f, "Couldn't write the Parquet file: {err}"),
+            Self::UnknownExporter { name } => write!(f, "No exporter registered as `{name}`"),
+            Self::Stats { net, source } => write!(f, "Net `{net}`: {source}"),
+            Self::Liberty { file, source } => {
+                write!(
+                    f,
+                    "Couldn't parse Liberty file `{}`: {source}",
+                    file.display()
+                )
+            }
+            Self::Spef { file, source } => {
+                write!(f, "Couldn't parse SPEF file `{}`: {source}", file.display())
+            }
+            Self::EnergyTable { file, source } => {
+                write!(
+                    f,
+                    "Couldn't parse energy table `{}`: {source}",
+                    file.display()
+                )
+            }
+            Self::Upf { file, source } => {
+                write!(f, "Couldn't parse UPF file `{}`: {source}", file.display())
+            }
+            Self::NetNotFound { net } => write!(f, "Net `{net}` not found in the trace"),
+            Self::MissingLiberty => write!(f, "Liberty library or energy table was not provided"),
+            Self::MissingNetlist => write!(f, "Netlist was not provided"),
+            Self::MissingSpef => write!(f, "SPEF file was not provided"),
+            Self::MissingTopScope => write!(f, "Top scope was not provided"),
+            Self::MissingSupplyVoltage => write!(f, "Supply voltage was not provided"),
+        }
+    }
+}
+
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::Io { source, .. } => Some(source),
+            Self::Output(err) => Some(err),
+            Self::Waveform(err) => Some(err),
+            Self::Netlist { source, .. } => Some(source),
+            #[cfg(feature = "parquet")]
+            Self::Parquet(err) => Some(err),
+            Self::Stats { source, .. } => Some(source),
+            Self::Liberty { source, .. } => Some(source),
+            Self::Spef { source, .. } => Some(source),
+            Self::EnergyTable { source, .. } => Some(source),
+            Self::Upf { source, .. } => Some(source),
+            _ => None,
+        }
+    }
+}
+
+impl From<io::Error> for Error {
+    fn from(err: io::Error) -> Self {
+        Self::Output(err)
+    }
+}
+
+impl Error {
+    /// Wrap an error of an operation on a file, given its path
+    pub(crate) fn io(path: impl Into<path::PathBuf>) -> impl FnOnce(io::Error) -> Self {
+        let path = path.into();
+        move |source| Self::Io { path, source }
+    }
+}
+
+impl From<wellen::WellenError> for Error {
+    fn from(err: wellen::WellenError) -> Self {
+        Self::Waveform(err)
+    }
+}
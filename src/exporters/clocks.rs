@@ -0,0 +1,56 @@
+// Copyright (c) 2024-2026 Antmicro <www.antmicro.com>
+// SPDX-License-Identifier: Apache-2.0
+
+use std::ops::Range;
+
+use super::csv::field;
+use crate::Context;
+use crate::error::Error;
+
+const COLUMNS: [&str; 9] = [
+    "window",
+    "clock",
+    "cycle",
+    "start_time",
+    "end_time",
+    "nets",
+    "toggles",
+    "activity",
+    "frequency",
+];
+
+/// Write activity of the domain of each clock in each of its cycles in the given iterations as a
+/// single CSV table. Activity is the average number of toggles of a net in the cycle and the
+/// frequency of the clock is expressed in Hz.
+pub fn export<W>(ctx: &Context, mut out: W, iterations: Range<usize>) -> Result<(), Error>
+where
+    W: std::io::Write,
+{
+    writeln!(out, "{}", COLUMNS.join(","))?;
+
+    for iteration in iterations {
+        for cycle in ctx.clock_activity(iteration)? {
+            let clock = &ctx.clocks()[cycle.clock];
+            let activity = if cycle.nets == 0 {
+                0.0
+            } else {
+                cycle.toggles / cycle.nets as f64
+            };
+            writeln!(
+                out,
+                "{},{},{},{},{},{},{},{},{}",
+                iteration,
+                field(&clock.name, ','),
+                cycle.cycle,
+                cycle.start,
+                cycle.end,
+                cycle.nets,
+                cycle.toggles,
+                activity,
+                clock.freq,
+            )?;
+        }
+    }
+
+    Ok(())
+}
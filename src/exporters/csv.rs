@@ -0,0 +1,165 @@
+// Copyright (c) 2024-2026 Antmicro <www.antmicro.com>
+// SPDX-License-Identifier: Apache-2.0
+
+use std::collections::HashMap;
+use std::ops::Range;
+
+use wellen::{GetItem, Scope, VarRef};
+
+use super::{TraceVisit, TraceVisitCtx, TraceVisitorAgent, WindowInfo};
+use crate::error::Error;
+use crate::stats::{PackedStats, SignalStats};
+use crate::util::ratio;
+use crate::{Context, HashVarRef, bit_index};
+
+const COLUMNS: [&str; 15] = [
+    "window",
+    "start_time",
+    "end_time",
+    "net",
+    "bit",
+    "T0",
+    "T1",
+    "TX",
+    "TZ",
+    "TC",
+    "IG",
+    "rise",
+    "fall",
+    "duty",
+    "activity",
+];
+
+/// Quote a field if it contains characters that would break the table (RFC 4180)
+pub(super) fn field(value: &str, separator: char) -> String {
+    if value.contains([separator, '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+/// Writes one row per bit of each net
+struct CsvAgent<'a> {
+    context: &'a Context,
+    stats: &'a HashMap<HashVarRef, Vec<PackedStats>>,
+    window: WindowInfo,
+    /// Length of the window expressed in cycles of each clock
+    cycles: Vec<f64>,
+    separator: char,
+    sparse: bool,
+}
+
+impl CsvAgent<'_> {
+    fn write_row<W>(
+        &self,
+        out: &mut W,
+        net: &str,
+        bit: Option<i64>,
+        stat: &SignalStats,
+        cycles: f64,
+    ) -> std::io::Result<()>
+    where
+        W: std::io::Write,
+    {
+        if self.sparse && stat.trans_count_doubled == 0 {
+            return Ok(());
+        }
+        let sep = self.separator;
+        writeln!(
+            out,
+            "{}{sep}{}{sep}{}{sep}{}{sep}{}{sep}{}{sep}{}{sep}{}{sep}{}{sep}{}{sep}{}{sep}{}{sep}{}{sep}{}{sep}{}",
+            self.window.index,
+            self.window.start,
+            self.window.end,
+            field(net, sep),
+            bit.map(|bit| bit.to_string()).unwrap_or_default(),
+            stat.low_time,
+            stat.high_time,
+            stat.x_time,
+            stat.z_time,
+            stat.clean_trans_count,
+            stat.glitch_trans_count,
+            stat.rise_count,
+            stat.fall_count,
+            ratio(stat.high_time as f64, self.window.active as f64),
+            ratio(stat.trans_count_doubled as f64 / 2.0, cycles),
+        )
+    }
+}
+
+impl<'w, W> TraceVisitorAgent<'w, W> for CsvAgent<'w>
+where
+    W: std::io::Write,
+{
+    type Error = Error;
+
+    fn enter_scope(
+        &mut self,
+        _ctx: &mut TraceVisitCtx<W>,
+        _scope: &'w Scope,
+    ) -> Result<(), Self::Error> {
+        Ok(())
+    }
+
+    fn enter_net(
+        &mut self,
+        ctx: &mut TraceVisitCtx<W>,
+        var_ref: VarRef,
+    ) -> Result<(), Self::Error> {
+        let hier = ctx.waveform.hierarchy();
+        let net = hier.get(var_ref);
+        let name = net.full_name(hier);
+        let zero = !name.contains(ctx.power_scope);
+        let empty = SignalStats::default();
+        let cycles = self.cycles[self.context.net_clock(var_ref)];
+
+        match &self.stats[&HashVarRef(var_ref)][self.window.index] {
+            PackedStats::OneBit(stat) => {
+                let bit = net.index().map(|idx| idx.lsb());
+                let stat = if zero { &empty } else { stat };
+                self.write_row(ctx.out, &name, bit, stat, cycles)?;
+            }
+            PackedStats::Vector(stats) => {
+                for (idx, stat) in stats.iter().enumerate() {
+                    let stat = if zero { &empty } else { stat };
+                    self.write_row(ctx.out, &name, Some(bit_index(net, idx)), stat, cycles)?;
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Write stats of the given iterations as a single table, with a row per bit of each net in each
+/// iteration
+pub fn export<W>(
+    ctx: &Context,
+    mut out: W,
+    iterations: Range<usize>,
+    separator: char,
+) -> Result<(), Error>
+where
+    W: std::io::Write,
+{
+    writeln!(out, "{}", COLUMNS.join(&separator.to_string()))?;
+
+    for iteration in iterations {
+        let window = ctx.window(iteration);
+        let cycles = ctx.window_cycles(&window);
+
+        let mut visitor_ctx = TraceVisitCtx::new(ctx, &mut out);
+        let mut agent = CsvAgent {
+            context: ctx,
+            stats: &ctx.stats,
+            window,
+            cycles,
+            separator,
+            sparse: ctx.sparse,
+        };
+        agent.visit_hierarchy(ctx.lookup_point, &mut visitor_ctx)?;
+    }
+
+    Ok(())
+}
@@ -0,0 +1,57 @@
+// Copyright (c) 2024-2026 Antmicro <www.antmicro.com>
+// SPDX-License-Identifier: Apache-2.0
+
+use std::ops::Range;
+
+use super::csv::field;
+use crate::Context;
+use crate::error::Error;
+
+const COLUMNS: [&str; 9] = [
+    "window",
+    "start_time",
+    "end_time",
+    "domain",
+    "on_time",
+    "nets",
+    "toggles",
+    "glitches",
+    "activity",
+];
+
+/// Write activity of each power domain in the given iterations as a single CSV table. Activity is
+/// the average number of toggles of a net in a clock cycle, counted over the time in which the
+/// domain is on.
+pub fn export<W>(ctx: &Context, mut out: W, iterations: Range<usize>) -> Result<(), Error>
+where
+    W: std::io::Write,
+{
+    writeln!(out, "{}", COLUMNS.join(","))?;
+
+    for iteration in iterations {
+        let window = ctx.window(iteration);
+        for domain in ctx.domain_activity(iteration) {
+            let cycles = domain.on_time as f64 * ctx.timescale_norm / ctx.clk_period;
+            let activity = if domain.nets == 0 || cycles == 0.0 {
+                0.0
+            } else {
+                domain.toggles / domain.nets as f64 / cycles
+            };
+            writeln!(
+                out,
+                "{},{},{},{},{},{},{},{},{}",
+                window.index,
+                window.start,
+                window.end,
+                field(&domain.domain, ','),
+                domain.on_time,
+                domain.nets,
+                domain.toggles,
+                domain.glitches,
+                activity,
+            )?;
+        }
+    }
+
+    Ok(())
+}
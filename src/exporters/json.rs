@@ -0,0 +1,266 @@
+// Copyright (c) 2024-2026 Antmicro <www.antmicro.com>
+// SPDX-License-Identifier: Apache-2.0
+
+//! JSON activity export.
+//!
+//! The output is a single JSON object with the following schema (version 4):
+//!
+//! ```text
+//! {
+//!   "schema_version": 4,
+//!   "metadata": {
+//!     "program": "trace2power",
+//!     "version": "0.4.3",            // omitted with `--ignore-version`
+//!     "date": "2026-01-01T00:00:00Z", // omitted with `--ignore-date`
+//!     "timescale": "1ns",
+//!     "duration": 300,               // length of the window in which the enable condition
+//!                                    // holds, in timescale units
+//!     "clock_frequency": 500000000.0, // in Hz
+//!     "window": { "index": 0, "count": 1, "start": 0, "end": 300 }
+//!   },
+//!   "hierarchy": [ <scope>, ... ]
+//! }
+//! ```
+//!
+//! where `<scope>` is
+//!
+//! ```text
+//! { "name": "counter0", "nets": [ <net>, ... ], "scopes": [ <scope>, ... ] }
+//! ```
+//!
+//! and `<net>` describes a single bit of a net:
+//!
+//! ```text
+//! {
+//!   "name": "cnt[2]",
+//!   "T0": 150, "T1": 150, "TX": 0, "TZ": 0, // time spent in each state, in timescale units
+//!   "TC": 4, "IG": 0,                       // clean and glitch (through X) transitions
+//!   "rise": 2, "fall": 2,                   // 0 -> 1 and 1 -> 0 transitions, also through X/Z
+//!   "duty": 0.5,                            // T1 / duration
+//!   "toggle_rate": 6666666.666666667,       // toggles per second
+//!   "narrow_pulses": 0                      // pulses narrower than the minimum pulse width,
+//!                                           // only with `--min-pulse-width`
+//! }
+//! ```
+//!
+//! Ratios are 0 in windows in which the enable condition never holds.
+//!
+//! Version 2 added `rise` and `fall` to nets. Version 3 added `narrow_pulses` to nets. Version 4
+//! made `duration` exclude time in which the enable condition doesn't hold.
+
+use std::collections::HashMap;
+
+use chrono::{SecondsFormat, Utc};
+use serde::Serialize;
+use wellen::{GetItem, Scope, VarRef};
+
+use super::{DisplayTimescaleUnit, TraceVisit, TraceVisitCtx, TraceVisitorAgent, WindowInfo};
+use crate::error::Error;
+use crate::stats::{PackedStats, SignalStats};
+use crate::util::ratio;
+use crate::{Context, HashVarRef, bit_index, indexed_name};
+
+pub const SCHEMA_VERSION: u32 = 4;
+
+#[derive(Serialize)]
+struct JsonWindow {
+    index: usize,
+    count: usize,
+    start: wellen::Time,
+    end: wellen::Time,
+}
+
+#[derive(Serialize)]
+struct JsonMetadata {
+    program: &'static str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    version: Option<&'static str>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    date: Option<String>,
+    timescale: String,
+    duration: wellen::Time,
+    clock_frequency: f64,
+    window: JsonWindow,
+}
+
+#[derive(Serialize)]
+#[allow(non_snake_case)]
+struct JsonNet {
+    name: String,
+    T0: u32,
+    T1: u32,
+    TX: u32,
+    TZ: u32,
+    TC: u32,
+    IG: u32,
+    rise: u32,
+    fall: u32,
+    duty: f64,
+    toggle_rate: f64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    narrow_pulses: Option<u32>,
+}
+
+#[derive(Serialize)]
+struct JsonScope {
+    name: String,
+    nets: Vec<JsonNet>,
+    scopes: Vec<JsonScope>,
+}
+
+#[derive(Serialize)]
+struct JsonDocument {
+    schema_version: u32,
+    metadata: JsonMetadata,
+    hierarchy: Vec<JsonScope>,
+}
+
+struct JsonAgent<'a> {
+    stats: &'a HashMap<HashVarRef, Vec<PackedStats>>,
+    span_index: usize,
+    /// Duration of the exported window (in timescale units)
+    duration: f64,
+    /// Duration of the exported window (in seconds)
+    duration_s: f64,
+    /// Whether narrow pulses are counted
+    narrow_pulses: bool,
+    scopes: Vec<JsonScope>,
+    roots: Vec<JsonScope>,
+}
+
+impl JsonAgent<'_> {
+    fn net(&self, name: String, stat: &SignalStats) -> JsonNet {
+        JsonNet {
+            name,
+            T0: stat.low_time,
+            T1: stat.high_time,
+            TX: stat.x_time,
+            TZ: stat.z_time,
+            TC: stat.clean_trans_count,
+            IG: stat.glitch_trans_count,
+            rise: stat.rise_count,
+            fall: stat.fall_count,
+            duty: ratio(stat.high_time as f64, self.duration),
+            toggle_rate: ratio(stat.trans_count_doubled as f64 / 2.0, self.duration_s),
+            narrow_pulses: self.narrow_pulses.then_some(stat.narrow_pulse_count),
+        }
+    }
+}
+
+impl<'w, W> TraceVisitorAgent<'w, W> for JsonAgent<'w>
+where
+    W: std::io::Write,
+{
+    type Error = Error;
+
+    fn enter_scope(
+        &mut self,
+        ctx: &mut TraceVisitCtx<W>,
+        scope: &'w Scope,
+    ) -> Result<(), Self::Error> {
+        self.scopes.push(JsonScope {
+            name: scope.name(ctx.waveform.hierarchy()).to_string(),
+            nets: Vec::new(),
+            scopes: Vec::new(),
+        });
+        Ok(())
+    }
+
+    fn exit_scope(
+        &mut self,
+        _ctx: &mut TraceVisitCtx<W>,
+        _scope: &'w Scope,
+    ) -> Result<(), Self::Error> {
+        let scope = self.scopes.pop().expect("Scope context should be valid");
+        match self.scopes.last_mut() {
+            Some(parent) => parent.scopes.push(scope),
+            None => self.roots.push(scope),
+        }
+        Ok(())
+    }
+
+    fn enter_net(
+        &mut self,
+        ctx: &mut TraceVisitCtx<W>,
+        var_ref: VarRef,
+    ) -> Result<(), Self::Error> {
+        let hier = ctx.waveform.hierarchy();
+        let net = hier.get(var_ref);
+        let zero = !net.full_name(hier).contains(ctx.power_scope);
+
+        let entries: Vec<_> = match &self.stats[&HashVarRef(var_ref)][self.span_index] {
+            PackedStats::OneBit(stat) => {
+                vec![(indexed_name(net.name(hier).into(), net), stat)]
+            }
+            PackedStats::Vector(stats) => stats
+                .iter()
+                .enumerate()
+                .map(|(idx, stat)| (format!("{}[{}]", net.name(hier), bit_index(net, idx)), stat))
+                .collect(),
+        };
+
+        let empty = SignalStats::default();
+        let nets: Vec<_> = entries
+            .into_iter()
+            .map(|(name, stat)| self.net(name, if zero { &empty } else { stat }))
+            .collect();
+        self.scopes
+            .last_mut()
+            .expect("Scope context should be valid")
+            .nets
+            .extend(nets);
+
+        Ok(())
+    }
+}
+
+fn metadata(ctx: &Context, window: &WindowInfo) -> JsonMetadata {
+    JsonMetadata {
+        program: clap::crate_name!(),
+        version: (!ctx.ignore_version).then_some(clap::crate_version!()),
+        date: (!ctx.ignore_date).then(|| Utc::now().to_rfc3339_opts(SecondsFormat::Secs, true)),
+        timescale: format!(
+            "{}{}",
+            window.timescale.factor,
+            DisplayTimescaleUnit(window.timescale.unit)
+        ),
+        duration: window.active,
+        clock_frequency: ctx.clk_freq,
+        window: JsonWindow {
+            index: window.index,
+            count: window.count,
+            start: window.start,
+            end: window.end,
+        },
+    }
+}
+
+pub fn export<W>(ctx: &Context, mut out: W, iteration: usize) -> Result<(), Error>
+where
+    W: std::io::Write,
+{
+    let window = ctx.window(iteration);
+    let duration = window.active as f64;
+
+    let mut visitor_ctx = TraceVisitCtx::new(ctx, &mut out);
+    let mut agent = JsonAgent {
+        stats: &ctx.stats,
+        span_index: iteration,
+        duration,
+        duration_s: duration * ctx.timescale_norm,
+        narrow_pulses: !ctx.pulse_filters.is_empty(),
+        scopes: Vec::new(),
+        roots: Vec::new(),
+    };
+    agent.visit_hierarchy(ctx.lookup_point, &mut visitor_ctx)?;
+
+    let document = JsonDocument {
+        schema_version: SCHEMA_VERSION,
+        metadata: metadata(ctx, &window),
+        hierarchy: agent.roots,
+    };
+    serde_json::to_writer_pretty(&mut out, &document).map_err(std::io::Error::from)?;
+    writeln!(out)?;
+
+    Ok(())
+}
@@ -1,14 +1,46 @@
 // Copyright (c) 2024-2026 Antmicro <www.antmicro.com>
 // SPDX-License-Identifier: Apache-2.0
 
+pub mod clocks;
+pub mod csv;
+pub mod domains;
+pub mod json;
+pub mod net_power;
+#[cfg(feature = "parquet")]
+pub mod parquet;
+pub(crate) mod plugin;
+pub mod power;
+pub mod profile;
 pub mod saif;
+pub mod tcf;
 pub mod tcl;
 
-use crate::LookupPoint;
+pub use plugin::{Exporter, ExporterRegistry, NetInfo, ScopeInfo, WindowInfo};
+
+use crate::error::Error;
 use crate::netlist::{Module, ModuleLookupError, Netlist};
+use crate::{Context, LookupPoint};
 use std::io::Write;
 use wellen::simple::Waveform;
-use wellen::{GetItem, Scope, VarRef};
+use wellen::{GetItem, Scope, TimescaleUnit, VarRef};
+
+pub(crate) struct DisplayTimescaleUnit(TimescaleUnit);
+
+impl std::fmt::Display for DisplayTimescaleUnit {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        use TimescaleUnit::*;
+        let s = match self.0 {
+            FemtoSeconds => "fs",
+            PicoSeconds => "ps",
+            NanoSeconds => "ns",
+            MicroSeconds => "us",
+            MilliSeconds => "ms",
+            Seconds => "s",
+            Unknown => return Err(std::fmt::Error),
+        };
+        f.write_str(s)
+    }
+}
 
 #[derive(Debug, Copy, Clone)]
 enum ModuleRef<'n> {
@@ -34,6 +66,36 @@ where
     power_scope: &'w String,
 }
 
+impl<'b, 'w, W> TraceVisitCtx<'b, 'w, 'w, W>
+where
+    W: std::io::Write,
+{
+    fn new(ctx: &'w Context, out: &'b mut W) -> Self {
+        let hier = ctx.wave.hierarchy();
+        let netlist_root = match ctx.top_scope {
+            Some(scope_ref) => hier
+                .get(scope_ref)
+                .full_name(hier)
+                .split('.')
+                .map(String::from)
+                .collect::<Vec<_>>(),
+            None => Vec::new(),
+        };
+
+        Self {
+            out,
+            waveform: &ctx.wave,
+            netlist_root,
+            top_module: &ctx.top,
+            netlist: ctx.netlist.as_ref(),
+            netlist_prefix: Vec::new(),
+            blackboxes_only: ctx.blackboxes_only,
+            remove_virtual_pins: ctx.remove_virtual_pins,
+            power_scope: &ctx.power_scope_prefix,
+        }
+    }
+}
+
 /// Traverses a hierarchy of scopes and variables loaded from a trace. For a given scope nets are
 /// visited first, then scopes
 trait TraceVisitorAgent<'w, W>
@@ -100,40 +162,48 @@ fn get_child_module_reference<'p, 'b, 'w, 'n, W>(
     ctx: &TraceVisitCtx<'b, 'w, 'n, W>,
     scope: &'w Scope,
     parent_module: ModuleRef<'p>,
-) -> ModuleRef<'n>
+) -> Result<ModuleRef<'n>, Error>
 where
     'n: 'p,
     W: Write,
 {
-    ctx.netlist
-        .map(|netlist| {
-            let name = scope.name(ctx.waveform.hierarchy());
-            match (parent_module, &ctx.netlist_prefix) {
-                (ModuleRef::OutsideNetlist, scope @ _) => {
-                    if scope == &ctx.netlist_root {
-                        match netlist.modules.get(ctx.top_module) {
-                            Some(module) => ModuleRef::Netlist(module),
-                            None => panic!("Top module `{}` was not found", ctx.top_module),
-                        }
-                    } else {
-                        ModuleRef::OutsideNetlist
-                    }
+    let Some(netlist) = ctx.netlist else {
+        return Ok(ModuleRef::OutsideNetlist);
+    };
+    let hier = ctx.waveform.hierarchy();
+    let name = scope.name(hier);
+    match (parent_module, &ctx.netlist_prefix) {
+        (ModuleRef::OutsideNetlist, scope) => {
+            if scope == &ctx.netlist_root {
+                match netlist.modules.get(ctx.top_module) {
+                    Some(module) => Ok(ModuleRef::Netlist(module)),
+                    None => Err(Error::TopModuleNotFound {
+                        module: ctx.top_module.clone(),
+                    }),
                 }
-                (ModuleRef::Netlist(parent), _) => match parent.get_module_of_cell(netlist, name) {
-                    Ok(module) => ModuleRef::Netlist(module),
-                    Err(ModuleLookupError::ModuleUndefined) => ModuleRef::BlackBox,
-                    Err(ModuleLookupError::CellNotFound) => panic!("cell {} not found", name),
-                },
-                (ModuleRef::BlackBox, _) => panic!("Error: attempted to descnd into a blackbox"),
+            } else {
+                Ok(ModuleRef::OutsideNetlist)
             }
-        })
-        .unwrap_or(ModuleRef::OutsideNetlist)
+        }
+        (ModuleRef::Netlist(parent), _) => match parent.get_module_of_cell(netlist, name) {
+            Ok(module) => Ok(ModuleRef::Netlist(module)),
+            Err(ModuleLookupError::ModuleUndefined) => Ok(ModuleRef::BlackBox),
+            Err(ModuleLookupError::CellNotFound) => Err(Error::CellNotFound {
+                scope: scope.full_name(hier),
+                cell: name.to_string(),
+            }),
+        },
+        (ModuleRef::BlackBox, _) => Err(Error::BlackBoxDescent {
+            scope: scope.full_name(hier),
+        }),
+    }
 }
 
 impl<'b, 'w, 'n, W, A> TraceVisit<'b, 'w, 'n, W> for A
 where
     W: Write,
     A: TraceVisitorAgent<'w, W>,
+    A::Error: From<Error>,
 {
     type Error = A::Error;
 
@@ -155,13 +225,9 @@ where
         if let ModuleRef::OutsideNetlist = parent_module {
             ctx.netlist_prefix.push(name.to_string());
         }
-        let module = get_child_module_reference(ctx, scope, parent_module);
+        let module = get_child_module_reference(ctx, scope, parent_module)?;
 
-        let export_nets = match (ctx.blackboxes_only, module) {
-            (false, _) => true,
-            (true, ModuleRef::BlackBox) => true,
-            _ => false,
-        };
+        let export_nets = !ctx.blackboxes_only || matches!(module, ModuleRef::BlackBox);
         if export_nets {
             for var_ref in scope.vars(hier) {
                 if let ModuleRef::BlackBox = module {
@@ -212,26 +278,29 @@ where
                     .map(ToString::to_string)
                     .collect();
                 let mut module_ref = ModuleRef::OutsideNetlist;
+                let scope_not_found = || Error::ScopeNotFound {
+                    scope: scope.full_name(hier),
+                };
                 let mut child_scope = ctx
                     .waveform
                     .hierarchy()
                     .scopes()
                     .map(|scope_ref| hier.get(scope_ref))
                     .find(|scope| scope.name(hier) == full_path[0])
-                    .expect("Child scope should be valid");
+                    .ok_or_else(scope_not_found)?;
 
                 ctx.netlist_prefix.push(full_path[0].to_string());
 
                 full_path.pop(); // We need to point to parent module
 
-                if full_path.len() >= 1 {
+                if !full_path.is_empty() {
                     for scope_name in &full_path[1..] {
                         child_scope = child_scope
                             .scopes(hier)
                             .map(|scope_ref| hier.get(scope_ref))
                             .find(|scope| scope.name(hier) == scope_name)
-                            .expect("Child scope should be valid");
-                        module_ref = get_child_module_reference(ctx, child_scope, module_ref);
+                            .ok_or_else(scope_not_found)?;
+                        module_ref = get_child_module_reference(ctx, child_scope, module_ref)?;
                         if let ModuleRef::OutsideNetlist = module_ref {
                             ctx.netlist_prefix.push(scope_name.to_string());
                         }
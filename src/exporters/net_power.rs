@@ -0,0 +1,97 @@
+// Copyright (c) 2024-2026 Antmicro <www.antmicro.com>
+// SPDX-License-Identifier: Apache-2.0
+
+use std::collections::BTreeMap;
+use std::ops::Range;
+
+use super::csv::field;
+use crate::Context;
+use crate::error::Error;
+
+const COLUMNS: [&str; 8] = [
+    "window",
+    "start_time",
+    "end_time",
+    "kind",
+    "name",
+    "capacitance",
+    "toggles",
+    "switching",
+];
+
+/// Nets of a scope and all scopes under it, rolled up
+#[derive(Default)]
+struct ScopeTotals {
+    capacitance: f64,
+    toggles: u64,
+    switching: f64,
+}
+
+/// Write switching power of each net of the SPEF file, followed by totals of each scope, in the
+/// given iterations as a single CSV table. Capacitance is expressed in pF and power in mW. Nets
+/// which weren't found in the trace have no toggles.
+pub fn export<W>(ctx: &Context, mut out: W, iterations: Range<usize>) -> Result<(), Error>
+where
+    W: std::io::Write,
+{
+    writeln!(out, "{}", COLUMNS.join(","))?;
+
+    for iteration in iterations {
+        let window = ctx.window(iteration);
+        let nets = ctx.net_power(iteration)?;
+
+        let mut scopes: BTreeMap<&str, ScopeTotals> = BTreeMap::new();
+        for net in &nets {
+            let scope = net.scope();
+            let prefixes = scope
+                .match_indices('.')
+                .map(|(end, _)| &scope[..end])
+                .chain((!scope.is_empty()).then_some(scope));
+            for prefix in prefixes {
+                let totals = scopes.entry(prefix).or_default();
+                totals.capacitance += net.capacitance;
+                totals.toggles += net.toggles.unwrap_or_default() as u64;
+                totals.switching += net.switching;
+            }
+        }
+
+        let rows = nets
+            .iter()
+            .map(|net| {
+                let toggles = net.toggles.map(|toggles| toggles.to_string());
+                (
+                    "net",
+                    net.name.as_str(),
+                    net.capacitance,
+                    toggles,
+                    net.switching,
+                )
+            })
+            .chain(scopes.iter().map(|(name, totals)| {
+                let toggles = Some(totals.toggles.to_string());
+                (
+                    "scope",
+                    *name,
+                    totals.capacitance,
+                    toggles,
+                    totals.switching,
+                )
+            }));
+        for (kind, name, capacitance, toggles, switching) in rows {
+            writeln!(
+                out,
+                "{},{},{},{},{},{},{},{}",
+                window.index,
+                window.start,
+                window.end,
+                kind,
+                field(name, ','),
+                capacitance * 1e12,
+                toggles.unwrap_or_default(),
+                switching * 1e3,
+            )?;
+        }
+    }
+
+    Ok(())
+}
@@ -0,0 +1,224 @@
+// Copyright (c) 2024-2026 Antmicro <www.antmicro.com>
+// SPDX-License-Identifier: Apache-2.0
+
+use std::collections::HashMap;
+use std::ops::Range;
+use std::sync::Arc;
+
+use arrow_array::builder::{
+    ArrayBuilder, Float64Builder, Int64Builder, StringDictionaryBuilder, UInt32Builder,
+};
+use arrow_array::types::Int32Type;
+use arrow_array::{ArrayRef, RecordBatch, UInt32Array, UInt64Array};
+use arrow_schema::{DataType, Field, Schema, SchemaRef};
+use parquet::arrow::ArrowWriter;
+use parquet::basic::Compression;
+use parquet::file::properties::WriterProperties;
+use wellen::{GetItem, Scope, VarRef};
+
+use super::{TraceVisit, TraceVisitCtx, TraceVisitorAgent, WindowInfo};
+use crate::error::Error;
+use crate::stats::{PackedStats, SignalStats};
+use crate::util::ratio;
+use crate::{Context, HashVarRef, bit_index};
+
+fn schema() -> SchemaRef {
+    let dictionary = DataType::Dictionary(Box::new(DataType::Int32), Box::new(DataType::Utf8));
+    Arc::new(Schema::new(vec![
+        Field::new("window", DataType::UInt32, false),
+        Field::new("start_time", DataType::UInt64, false),
+        Field::new("end_time", DataType::UInt64, false),
+        Field::new("scope", dictionary.clone(), false),
+        Field::new("net", dictionary, false),
+        Field::new("bit", DataType::Int64, true),
+        Field::new("T0", DataType::UInt32, false),
+        Field::new("T1", DataType::UInt32, false),
+        Field::new("TX", DataType::UInt32, false),
+        Field::new("TZ", DataType::UInt32, false),
+        Field::new("TC", DataType::UInt32, false),
+        Field::new("IG", DataType::UInt32, false),
+        Field::new("rise", DataType::UInt32, false),
+        Field::new("fall", DataType::UInt32, false),
+        Field::new("duty", DataType::Float64, false),
+        Field::new("activity", DataType::Float64, false),
+    ]))
+}
+
+/// Collects one row per bit of each net into column builders
+struct ParquetAgent<'a> {
+    context: &'a Context,
+    stats: &'a HashMap<HashVarRef, Vec<PackedStats>>,
+    window: WindowInfo,
+    /// Length of the window expressed in cycles of each clock
+    cycles: Vec<f64>,
+    sparse: bool,
+    scope: StringDictionaryBuilder<Int32Type>,
+    net: StringDictionaryBuilder<Int32Type>,
+    bit: Int64Builder,
+    t0: UInt32Builder,
+    t1: UInt32Builder,
+    tx: UInt32Builder,
+    tz: UInt32Builder,
+    tc: UInt32Builder,
+    ig: UInt32Builder,
+    rise: UInt32Builder,
+    fall: UInt32Builder,
+    duty: Float64Builder,
+    activity: Float64Builder,
+}
+
+impl<'a> ParquetAgent<'a> {
+    fn new(context: &'a Context, window: WindowInfo) -> Self {
+        Self {
+            context,
+            stats: &context.stats,
+            window,
+            cycles: Vec::new(),
+            sparse: false,
+            scope: StringDictionaryBuilder::new(),
+            net: StringDictionaryBuilder::new(),
+            bit: Int64Builder::new(),
+            t0: UInt32Builder::new(),
+            t1: UInt32Builder::new(),
+            tx: UInt32Builder::new(),
+            tz: UInt32Builder::new(),
+            tc: UInt32Builder::new(),
+            ig: UInt32Builder::new(),
+            rise: UInt32Builder::new(),
+            fall: UInt32Builder::new(),
+            duty: Float64Builder::new(),
+            activity: Float64Builder::new(),
+        }
+    }
+
+    fn append_row(
+        &mut self,
+        scope: &str,
+        net: &str,
+        bit: Option<i64>,
+        stat: &SignalStats,
+        cycles: f64,
+    ) {
+        if self.sparse && stat.trans_count_doubled == 0 {
+            return;
+        }
+        self.scope.append_value(scope);
+        self.net.append_value(net);
+        self.bit.append_option(bit);
+        self.t0.append_value(stat.low_time);
+        self.t1.append_value(stat.high_time);
+        self.tx.append_value(stat.x_time);
+        self.tz.append_value(stat.z_time);
+        self.tc.append_value(stat.clean_trans_count);
+        self.ig.append_value(stat.glitch_trans_count);
+        self.rise.append_value(stat.rise_count);
+        self.fall.append_value(stat.fall_count);
+        self.duty
+            .append_value(ratio(stat.high_time as f64, self.window.active as f64));
+        self.activity
+            .append_value(ratio(stat.trans_count_doubled as f64 / 2.0, cycles));
+    }
+
+    /// Turn rows collected so far into a batch
+    fn finish_batch(&mut self, schema: SchemaRef) -> Result<RecordBatch, Error> {
+        let rows = self.t0.len();
+        let columns: Vec<ArrayRef> = vec![
+            Arc::new(UInt32Array::from(vec![self.window.index as u32; rows])),
+            Arc::new(UInt64Array::from(vec![self.window.start; rows])),
+            Arc::new(UInt64Array::from(vec![self.window.end; rows])),
+            Arc::new(self.scope.finish()),
+            Arc::new(self.net.finish()),
+            Arc::new(self.bit.finish()),
+            Arc::new(self.t0.finish()),
+            Arc::new(self.t1.finish()),
+            Arc::new(self.tx.finish()),
+            Arc::new(self.tz.finish()),
+            Arc::new(self.tc.finish()),
+            Arc::new(self.ig.finish()),
+            Arc::new(self.rise.finish()),
+            Arc::new(self.fall.finish()),
+            Arc::new(self.duty.finish()),
+            Arc::new(self.activity.finish()),
+        ];
+        RecordBatch::try_new(schema, columns).map_err(|err| Error::Parquet(err.into()))
+    }
+}
+
+impl<'w, W> TraceVisitorAgent<'w, W> for ParquetAgent<'w>
+where
+    W: std::io::Write,
+{
+    type Error = Error;
+
+    fn enter_scope(
+        &mut self,
+        _ctx: &mut TraceVisitCtx<W>,
+        _scope: &'w Scope,
+    ) -> Result<(), Self::Error> {
+        Ok(())
+    }
+
+    fn enter_net(
+        &mut self,
+        ctx: &mut TraceVisitCtx<W>,
+        var_ref: VarRef,
+    ) -> Result<(), Self::Error> {
+        let hier = ctx.waveform.hierarchy();
+        let net = hier.get(var_ref);
+        let full_name = net.full_name(hier);
+        let zero = !full_name.contains(ctx.power_scope);
+        let scope = full_name.rsplit_once('.').map_or("", |(scope, _)| scope);
+        let name = net.name(hier);
+        let empty = SignalStats::default();
+        let cycles = self.cycles[self.context.net_clock(var_ref)];
+
+        match &self.stats[&HashVarRef(var_ref)][self.window.index] {
+            PackedStats::OneBit(stat) => {
+                let bit = net.index().map(|idx| idx.lsb());
+                let stat = if zero { &empty } else { stat };
+                self.append_row(scope, name, bit, stat, cycles);
+            }
+            PackedStats::Vector(stats) => {
+                for (idx, stat) in stats.iter().enumerate() {
+                    let stat = if zero { &empty } else { stat };
+                    self.append_row(scope, name, Some(bit_index(net, idx)), stat, cycles);
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Write stats of the given iterations as a Parquet file, with a row per bit of each net in each
+/// iteration. Each iteration is written as a separate record batch.
+pub fn export<W>(ctx: &Context, out: W, iterations: Range<usize>) -> Result<(), Error>
+where
+    W: std::io::Write + Send,
+{
+    let schema = schema();
+    let props = WriterProperties::builder()
+        .set_compression(Compression::SNAPPY)
+        .set_dictionary_enabled(true)
+        .build();
+    let mut writer =
+        ArrowWriter::try_new(out, schema.clone(), Some(props)).map_err(Error::Parquet)?;
+
+    // Traversal doesn't write anything, so the visitor context is given a dummy output
+    let mut sink = std::io::sink();
+    let mut agent = ParquetAgent::new(ctx, ctx.window(0));
+    agent.sparse = ctx.sparse;
+    for iteration in iterations {
+        agent.window = ctx.window(iteration);
+        agent.cycles = ctx.window_cycles(&agent.window);
+
+        let mut visitor_ctx = TraceVisitCtx::new(ctx, &mut sink);
+        agent.visit_hierarchy(ctx.lookup_point, &mut visitor_ctx)?;
+
+        let batch = agent.finish_batch(schema.clone())?;
+        writer.write(&batch).map_err(Error::Parquet)?;
+    }
+    writer.close().map_err(Error::Parquet)?;
+
+    Ok(())
+}
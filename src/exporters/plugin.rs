@@ -0,0 +1,201 @@
+// Copyright (c) 2024-2026 Antmicro <www.antmicro.com>
+// SPDX-License-Identifier: Apache-2.0
+
+// Public interface for output formats implemented outside of this crate
+
+use std::borrow::Cow;
+use std::collections::{BTreeMap, HashMap};
+use std::io::Write;
+
+use wellen::{GetItem, Scope, Timescale, Var, VarRef};
+
+use super::{TraceVisit, TraceVisitCtx, TraceVisitorAgent};
+use crate::error::{Error, Result};
+use crate::stats::{PackedStats, SignalStats};
+use crate::{Context, HashVarRef, indexed_name};
+
+/// Describes a time window whose stats are being exported
+#[derive(Debug, Clone)]
+pub struct WindowInfo {
+    /// Index of the window
+    pub index: usize,
+    /// Number of windows the trace has been split into
+    pub count: usize,
+    /// Time stamp at which the window begins (in `timescale` units)
+    pub start: wellen::Time,
+    /// Time stamp at which the window ends (in `timescale` units)
+    pub end: wellen::Time,
+    /// Time within the window in which the enable condition holds (in `timescale` units). It's
+    /// equal to the duration of the window if no condition is given.
+    pub active: wellen::Time,
+    pub timescale: Timescale,
+    /// Clock period (in seconds)
+    pub clk_period: f64,
+}
+
+impl WindowInfo {
+    /// Length of the window (in `timescale` units)
+    pub fn duration(&self) -> wellen::Time {
+        self.end - self.start
+    }
+}
+
+/// Describes a scope entered or left during export
+pub struct ScopeInfo<'a> {
+    /// Local name of the scope
+    pub name: &'a str,
+    /// Full hierarchical name of the scope, with `.` as a divider
+    pub full_name: String,
+    pub scope: &'a Scope,
+}
+
+/// Describes a net visited during export
+pub struct NetInfo<'a> {
+    /// Local name of the net, including the index for bit-blasted nets
+    pub name: String,
+    /// Full hierarchical name of the net, with `.` as a divider
+    pub full_name: String,
+    pub var: &'a Var,
+    /// Stats of each bit of the net in the exported window. Stats of nets located outside of
+    /// the power scope are zeroed.
+    pub stats: Cow<'a, [SignalStats]>,
+}
+
+/// Output format which can be implemented outside of this crate.
+///
+/// Scopes are visited depth-first. Within a scope, its nets are visited before its child scopes.
+/// Scopes and nets filtered out by the netlist are skipped.
+pub trait Exporter {
+    /// Called once before the hierarchy is traversed
+    fn begin(&mut self, _out: &mut dyn Write, _window: &WindowInfo) -> Result<()> {
+        Ok(())
+    }
+    /// Called upon entering a new scope
+    fn enter_scope(&mut self, _out: &mut dyn Write, _scope: &ScopeInfo) -> Result<()> {
+        Ok(())
+    }
+    /// Called upon leaving a scope
+    fn exit_scope(&mut self, _out: &mut dyn Write, _scope: &ScopeInfo) -> Result<()> {
+        Ok(())
+    }
+    /// Called for each exported net
+    fn net(&mut self, out: &mut dyn Write, net: &NetInfo) -> Result<()>;
+    /// Called once the whole hierarchy has been traversed
+    fn end(&mut self, _out: &mut dyn Write, _window: &WindowInfo) -> Result<()> {
+        Ok(())
+    }
+}
+
+type ExporterFactory = Box<dyn Fn() -> Box<dyn Exporter> + Send + Sync>;
+
+/// Collection of named exporters, used to select an output format by its name
+#[derive(Default)]
+pub struct ExporterRegistry {
+    factories: BTreeMap<String, ExporterFactory>,
+}
+
+impl ExporterRegistry {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Register a format under a given name. Replaces a format previously registered under the
+    /// same name.
+    pub fn register<F>(&mut self, name: impl Into<String>, factory: F)
+    where
+        F: Fn() -> Box<dyn Exporter> + Send + Sync + 'static,
+    {
+        self.factories.insert(name.into(), Box::new(factory));
+    }
+
+    /// Create a new instance of exporter registered under a given name
+    pub fn create(&self, name: &str) -> Result<Box<dyn Exporter>> {
+        self.factories
+            .get(name)
+            .map(|factory| factory())
+            .ok_or_else(|| Error::UnknownExporter {
+                name: name.to_string(),
+            })
+    }
+
+    /// Names of all registered formats
+    pub fn names(&self) -> impl Iterator<Item = &str> {
+        self.factories.keys().map(String::as_str)
+    }
+}
+
+/// Forwards hierarchy traversal to an `Exporter`
+struct ExporterAgent<'a, 'e> {
+    exporter: &'e mut dyn Exporter,
+    stats: &'a HashMap<HashVarRef, Vec<PackedStats>>,
+    span_index: usize,
+}
+
+impl<'w, W> TraceVisitorAgent<'w, W> for ExporterAgent<'w, '_>
+where
+    W: Write,
+{
+    type Error = Error;
+
+    fn enter_scope(&mut self, ctx: &mut TraceVisitCtx<W>, scope: &'w Scope) -> Result<()> {
+        let hier = ctx.waveform.hierarchy();
+        let info = ScopeInfo {
+            name: scope.name(hier),
+            full_name: scope.full_name(hier),
+            scope,
+        };
+        self.exporter.enter_scope(ctx.out, &info)
+    }
+
+    fn exit_scope(&mut self, ctx: &mut TraceVisitCtx<W>, scope: &'w Scope) -> Result<()> {
+        let hier = ctx.waveform.hierarchy();
+        let info = ScopeInfo {
+            name: scope.name(hier),
+            full_name: scope.full_name(hier),
+            scope,
+        };
+        self.exporter.exit_scope(ctx.out, &info)
+    }
+
+    fn enter_net(&mut self, ctx: &mut TraceVisitCtx<W>, var_ref: VarRef) -> Result<()> {
+        let hier = ctx.waveform.hierarchy();
+        let var = hier.get(var_ref);
+        let full_name = var.full_name(hier);
+        let bits = self.stats[&HashVarRef(var_ref)][self.span_index].bits();
+        let stats = if full_name.contains(ctx.power_scope) {
+            Cow::Borrowed(bits)
+        } else {
+            Cow::Owned(vec![SignalStats::default(); bits.len()])
+        };
+        let info = NetInfo {
+            name: indexed_name(var.name(hier).to_string(), var),
+            full_name: indexed_name(full_name, var),
+            var,
+            stats,
+        };
+        self.exporter.net(ctx.out, &info)
+    }
+}
+
+pub(crate) fn export<W>(
+    ctx: &Context,
+    exporter: &mut dyn Exporter,
+    mut out: W,
+    iteration: usize,
+) -> Result<()>
+where
+    W: Write,
+{
+    let window = ctx.window(iteration);
+    exporter.begin(&mut out, &window)?;
+
+    let mut visitor_ctx = TraceVisitCtx::new(ctx, &mut out);
+    let mut agent = ExporterAgent {
+        exporter,
+        stats: &ctx.stats,
+        span_index: iteration,
+    };
+    agent.visit_hierarchy(ctx.lookup_point, &mut visitor_ctx)?;
+
+    agent.exporter.end(&mut out, &window)
+}
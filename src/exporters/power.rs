@@ -0,0 +1,157 @@
+// Copyright (c) 2024-2026 Antmicro <www.antmicro.com>
+// SPDX-License-Identifier: Apache-2.0
+
+use std::ops::Range;
+
+use wellen::{GetItem, Scope, VarRef};
+
+use super::csv::field;
+use super::{TraceVisit, TraceVisitCtx, TraceVisitorAgent};
+use crate::energy::EnergyTable;
+use crate::error::Error;
+use crate::power::InstancePower;
+use crate::util::ratio;
+use crate::{Context, HashVarRef};
+
+const COLUMNS: [&str; 9] = [
+    "window",
+    "start_time",
+    "end_time",
+    "instance",
+    "cell",
+    "internal",
+    "switching",
+    "leakage",
+    "total",
+];
+
+/// Sums up energy dissipated by pins of cells found in an energy table
+struct EnergyAgent<'a> {
+    context: &'a Context,
+    table: &'a EnergyTable,
+    iteration: usize,
+    /// Instances on the current path, `None` for scopes which aren't cells of the table. Energy
+    /// is accumulated in their `internal` field.
+    stack: Vec<Option<InstancePower>>,
+    instances: Vec<InstancePower>,
+}
+
+impl<'w, W> TraceVisitorAgent<'w, W> for EnergyAgent<'w>
+where
+    W: std::io::Write,
+{
+    type Error = Error;
+
+    fn enter_scope(
+        &mut self,
+        ctx: &mut TraceVisitCtx<W>,
+        scope: &'w Scope,
+    ) -> Result<(), Self::Error> {
+        let instance = self.context.scope_module_name(scope).and_then(|module| {
+            let cell = self.table.cell(&module)?;
+            Some(InstancePower {
+                instance: scope.full_name(ctx.waveform.hierarchy()),
+                cell: module,
+                internal: 0.0,
+                switching: 0.0,
+                leakage: cell.leakage,
+            })
+        });
+        self.stack.push(instance);
+        Ok(())
+    }
+
+    fn exit_scope(
+        &mut self,
+        ctx: &mut TraceVisitCtx<W>,
+        _scope: &'w Scope,
+    ) -> Result<(), Self::Error> {
+        if let Some(Some(instance)) = self.stack.pop() {
+            if format!("{}.", instance.instance).contains(ctx.power_scope) {
+                self.instances.push(instance);
+            }
+        }
+        Ok(())
+    }
+
+    fn enter_net(
+        &mut self,
+        ctx: &mut TraceVisitCtx<W>,
+        var_ref: VarRef,
+    ) -> Result<(), Self::Error> {
+        let Some(Some(instance)) = self.stack.last_mut() else {
+            return Ok(());
+        };
+        let hier = ctx.waveform.hierarchy();
+        let net = hier.get(var_ref);
+        let Some(pin) = self
+            .table
+            .cell(&instance.cell)
+            .and_then(|cell| cell.pins.get(net.name(hier)))
+        else {
+            return Ok(());
+        };
+        if !net.full_name(hier).contains(ctx.power_scope) {
+            return Ok(());
+        }
+        for stat in self.context.stats[&HashVarRef(var_ref)][self.iteration].bits() {
+            instance.internal +=
+                stat.rise_count as f64 * pin.rise + stat.fall_count as f64 * pin.fall;
+        }
+        Ok(())
+    }
+}
+
+/// Power of each cell instance found in an energy table, based on the activity of its pins
+pub(crate) fn energy_table_power(
+    ctx: &Context,
+    table: &EnergyTable,
+    iteration: usize,
+) -> Result<Vec<InstancePower>, Error> {
+    let mut agent = EnergyAgent {
+        context: ctx,
+        table,
+        iteration,
+        stack: Vec::new(),
+        instances: Vec::new(),
+    };
+    let mut sink = std::io::sink();
+    let mut visitor_ctx = TraceVisitCtx::new(ctx, &mut sink);
+    agent.visit_hierarchy(ctx.lookup_point, &mut visitor_ctx)?;
+
+    let duration_s = ctx.window(iteration).active as f64 * ctx.timescale_norm;
+    for instance in &mut agent.instances {
+        instance.internal = ratio(instance.internal, duration_s);
+    }
+    Ok(agent.instances)
+}
+
+/// Write power estimated for each cell instance in the given iterations as a single
+/// CSV table. Power is expressed in mW.
+pub fn export<W>(ctx: &Context, mut out: W, iterations: Range<usize>) -> Result<(), Error>
+where
+    W: std::io::Write,
+{
+    writeln!(out, "{}", COLUMNS.join(","))?;
+
+    for iteration in iterations {
+        let window = ctx.window(iteration);
+        for power in ctx.power(iteration)? {
+            writeln!(
+                out,
+                "{},{},{},{},{},{},{},{},{}",
+                window.index,
+                window.start,
+                window.end,
+                field(&power.instance, ','),
+                field(&power.cell, ','),
+                power.internal * 1e3,
+                power.switching * 1e3,
+                power.leakage * 1e3,
+                power.total() * 1e3,
+            )?;
+        }
+    }
+
+    Ok(())
+}
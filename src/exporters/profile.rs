@@ -0,0 +1,236 @@
+// Copyright (c) 2024-2026 Antmicro <www.antmicro.com>
+// SPDX-License-Identifier: Apache-2.0
+
+use std::collections::HashMap;
+use std::ops::Range;
+
+use chrono::Utc;
+use wellen::{GetItem, Scope, VarRef};
+
+use super::{DisplayTimescaleUnit, TraceVisit, TraceVisitCtx, TraceVisitorAgent};
+use crate::error::Error;
+use crate::power::InstancePower;
+use crate::stats::PackedStats;
+use crate::util::ratio;
+use crate::{Context, HashVarRef};
+
+/// Value carried by the signals of an activity profile
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, clap::ValueEnum)]
+pub enum ProfileMetric {
+    /// Number of toggles within a window
+    #[default]
+    Toggles,
+    /// Number of toggles per second within a window
+    ToggleRate,
+    /// Power (in mW) estimated for cell instances under a scope
+    Power,
+}
+
+impl ProfileMetric {
+    fn signal_name(&self) -> &'static str {
+        match self {
+            Self::Toggles => "toggles",
+            Self::ToggleRate => "toggle_rate",
+            Self::Power => "power",
+        }
+    }
+}
+
+enum Definition {
+    EnterScope(String),
+    Signal(usize),
+    ExitScope,
+}
+
+/// Scope which gets its own signal in the profile
+struct ProfiledScope {
+    /// Full hierarchical name of the scope
+    name: String,
+    nets: Vec<VarRef>,
+}
+
+/// Collects nets located under each profiled scope
+struct ProfileAgent {
+    max_depth: Option<usize>,
+    definitions: Vec<Definition>,
+    scopes: Vec<ProfiledScope>,
+    /// Indices of profiled scopes on the current path
+    stack: Vec<Option<usize>>,
+}
+
+impl<'w, W> TraceVisitorAgent<'w, W> for ProfileAgent
+where
+    W: std::io::Write,
+{
+    type Error = Error;
+
+    fn enter_scope(
+        &mut self,
+        ctx: &mut TraceVisitCtx<W>,
+        scope: &'w Scope,
+    ) -> Result<(), Self::Error> {
+        let depth = self.stack.len();
+        if self.max_depth.is_some_and(|max_depth| depth >= max_depth) {
+            self.stack.push(None);
+            return Ok(());
+        }
+        let index = self.scopes.len();
+        self.scopes.push(ProfiledScope {
+            name: scope.full_name(ctx.waveform.hierarchy()),
+            nets: Vec::new(),
+        });
+        self.stack.push(Some(index));
+        let name = scope.name(ctx.waveform.hierarchy()).replace(' ', "_");
+        self.definitions.push(Definition::EnterScope(name));
+        self.definitions.push(Definition::Signal(index));
+        Ok(())
+    }
+
+    fn exit_scope(
+        &mut self,
+        _ctx: &mut TraceVisitCtx<W>,
+        _scope: &'w Scope,
+    ) -> Result<(), Self::Error> {
+        if let Some(Some(_)) = self.stack.pop() {
+            self.definitions.push(Definition::ExitScope);
+        }
+        Ok(())
+    }
+
+    fn enter_net(
+        &mut self,
+        ctx: &mut TraceVisitCtx<W>,
+        var_ref: VarRef,
+    ) -> Result<(), Self::Error> {
+        let hier = ctx.waveform.hierarchy();
+        if !hier.get(var_ref).full_name(hier).contains(ctx.power_scope) {
+            return Ok(());
+        }
+        for index in self.stack.iter().flatten() {
+            self.scopes[*index].nets.push(var_ref);
+        }
+        Ok(())
+    }
+}
+
+/// Identifier of the `index`-th signal in a VCD file
+fn vcd_id(mut index: usize) -> String {
+    const FIRST: u8 = b'!';
+    const COUNT: usize = (b'~' - b'!' + 1) as usize;
+    let mut id = String::new();
+    loop {
+        id.push((FIRST + (index % COUNT) as u8) as char);
+        index /= COUNT;
+        if index == 0 {
+            break id;
+        }
+        index -= 1;
+    }
+}
+
+fn toggles(stats: &HashMap<HashVarRef, Vec<PackedStats>>, nets: &[VarRef], window: usize) -> f64 {
+    let doubled: u64 = nets
+        .iter()
+        .flat_map(|var_ref| stats[&HashVarRef(*var_ref)][window].bits())
+        .map(|stat| stat.trans_count_doubled as u64)
+        .sum();
+    doubled as f64 / 2.0
+}
+
+/// Total power of instances located in a scope (in W)
+fn scope_power(power: &[InstancePower], scope: &str) -> f64 {
+    power
+        .iter()
+        .filter(|power| {
+            power
+                .instance
+                .strip_prefix(scope)
+                .is_some_and(|rest| rest.is_empty() || rest.starts_with('.'))
+        })
+        .map(InstancePower::total)
+        .sum()
+}
+
+/// Write a VCD file in which each scope (down to `ctx.profile_depth`) carries a real-valued signal
+/// with the activity of all nets under it, or the power of all cell instances under it, changing at
+/// the beginning of each iteration
+pub fn export<W>(ctx: &Context, mut out: W, iterations: Range<usize>) -> Result<(), Error>
+where
+    W: std::io::Write,
+{
+    let mut agent = ProfileAgent {
+        max_depth: ctx.profile_depth,
+        definitions: Vec::new(),
+        scopes: Vec::new(),
+        stack: Vec::new(),
+    };
+    let mut sink = std::io::sink();
+    let mut visitor_ctx = TraceVisitCtx::new(ctx, &mut sink);
+    agent.visit_hierarchy(ctx.lookup_point, &mut visitor_ctx)?;
+
+    if !ctx.ignore_date {
+        writeln!(
+            out,
+            "$date\n\t{}\n$end",
+            Utc::now().format("%a %b %-d %T %Y")
+        )?;
+    }
+    if !ctx.ignore_version {
+        writeln!(
+            out,
+            "$version\n\t{} {}\n$end",
+            clap::crate_name!(),
+            clap::crate_version!()
+        )?;
+    }
+    writeln!(
+        out,
+        "$timescale\n\t{}{}\n$end",
+        ctx.timescale.factor,
+        DisplayTimescaleUnit(ctx.timescale.unit)
+    )?;
+    for definition in &agent.definitions {
+        match definition {
+            Definition::EnterScope(name) => writeln!(out, "$scope module {name} $end")?,
+            Definition::Signal(index) => writeln!(
+                out,
+                "$var real 64 {} {} $end",
+                vcd_id(*index),
+                ctx.profile_metric.signal_name()
+            )?,
+            Definition::ExitScope => writeln!(out, "$upscope $end")?,
+        }
+    }
+    writeln!(out, "$enddefinitions $end")?;
+
+    let mut values: Vec<Option<f64>> = vec![None; agent.scopes.len()];
+    let mut last_end = None;
+    for iteration in iterations {
+        let window = ctx.window(iteration);
+        let duration_s = window.active as f64 * ctx.timescale_norm;
+        let power = match ctx.profile_metric {
+            ProfileMetric::Power => ctx.power(iteration)?,
+            _ => Vec::new(),
+        };
+        writeln!(out, "#{}", window.start)?;
+        for (index, scope) in agent.scopes.iter().enumerate() {
+            let value = match ctx.profile_metric {
+                ProfileMetric::Toggles => toggles(&ctx.stats, &scope.nets, iteration),
+                ProfileMetric::ToggleRate => {
+                    ratio(toggles(&ctx.stats, &scope.nets, iteration), duration_s)
+                }
+                ProfileMetric::Power => scope_power(&power, &scope.name) * 1e3,
+            };
+            if values[index] != Some(value) {
+                writeln!(out, "r{} {}", value, vcd_id(index))?;
+                values[index] = Some(value);
+            }
+        }
+        last_end = Some(window.end);
+    }
+    if let Some(end) = last_end {
+        writeln!(out, "#{end}")?;
+    }
+
+    Ok(())
+}
@@ -1,74 +1,157 @@
 // Copyright (c) 2024-2026 Antmicro <www.antmicro.com>
 // SPDX-License-Identifier: Apache-2.0
 
+use crate::error::Error;
 use crate::stats::{PackedStats, SignalStats};
-use crate::{Context, indexed_name};
+use crate::{Context, bit_index};
 use chrono::Utc;
 use indoc::indoc;
 use std::collections::HashMap;
-use wellen::{GetItem, Scope, TimescaleUnit, VarRef};
+use wellen::{GetItem, Scope, VarRef};
 
-use super::{TraceVisit, TraceVisitCtx, TraceVisitorAgent};
+use super::{DisplayTimescaleUnit, TraceVisit, TraceVisitCtx, TraceVisitorAgent};
 use crate::HashVarRef;
 
-struct DisplayTimescaleUnit(TimescaleUnit);
-
-impl std::fmt::Display for DisplayTimescaleUnit {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        use TimescaleUnit::*;
-        let s = match self.0 {
-            FemtoSeconds => "fs",
-            PicoSeconds => "ps",
-            NanoSeconds => "ns",
-            MicroSeconds => "us",
-            MilliSeconds => "ms",
-            Seconds => "s",
-            Unknown => panic!("Unknown time unit"),
+/// Flavour of the SAIF file
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, clap::ValueEnum)]
+pub enum SaifDialect {
+    #[default]
+    Standard,
+    /// SAIF accepted by Vivado's `read_saif`. Only the subtree of the DUT (`--top-scope`, which
+    /// is required) is written, without the testbench around it, and brackets in net names are
+    /// escaped.
+    Xilinx,
+}
+
+/// Which names get special characters (brackets and the hierarchy divider) escaped with a backslash
+#[derive(Copy, Clone, Debug, PartialEq, Eq, clap::ValueEnum)]
+pub enum SaifEscaping {
+    /// Instance names only, e.g. `(INSTANCE cnt\[0\]_reg` and `(cnt[0]`
+    Instances,
+    /// Instance and net names, including bit indices, e.g. `(cnt\[0\]`
+    All,
+    /// Names are written as they appear in the trace
+    None,
+}
+
+/// How the bits of vector nets are written
+#[derive(Copy, Clone, Debug, PartialEq, Eq, clap::ValueEnum)]
+pub enum SaifBusNotation {
+    /// Separate entry for each bit, e.g. `(cnt[0] (T0 1) ...)`
+    Bits,
+    /// Single entry for the whole range, with a value for each bit, starting from the most
+    /// significant one, e.g. `(cnt[1:0] (T0 1 2) ...)`
+    Range,
+}
+
+/// Notation used for names in a SAIF file
+#[derive(Copy, Clone)]
+struct SaifFormat {
+    dialect: SaifDialect,
+    divider: char,
+    escaping: SaifEscaping,
+    bus: SaifBusNotation,
+    module_names: bool,
+}
+
+impl SaifFormat {
+    fn new(ctx: &Context) -> Self {
+        let (escaping, bus) = match ctx.saif_dialect {
+            SaifDialect::Standard => (SaifEscaping::Instances, SaifBusNotation::Bits),
+            SaifDialect::Xilinx => (SaifEscaping::All, SaifBusNotation::Bits),
         };
-        f.write_str(s)
+        Self {
+            dialect: ctx.saif_dialect,
+            divider: ctx.saif_divider.unwrap_or('/'),
+            escaping: ctx.saif_escaping.unwrap_or(escaping),
+            bus: ctx.saif_bus.unwrap_or(bus),
+            module_names: ctx.saif_module_names,
+        }
+    }
+
+    fn escape(&self, name: &str) -> String {
+        let mut escaped = String::with_capacity(name.len());
+        for c in name.chars() {
+            if c == '[' || c == ']' || c == self.divider {
+                escaped.push('\\');
+            }
+            escaped.push(c);
+        }
+        escaped
+    }
+
+    fn instance_name(&self, name: &str) -> String {
+        match self.escaping {
+            SaifEscaping::Instances | SaifEscaping::All => self.escape(name),
+            SaifEscaping::None => name.to_string(),
+        }
+    }
+
+    /// Name of a net, or a bit of a net if `index` is given
+    fn net_name(&self, name: &str, index: Option<i64>) -> String {
+        let name = match self.dialect {
+            SaifDialect::Standard => name.replace('\\', "\\\\"),
+            // Escaped identifiers produced by bit-blasting a bus (e.g. `\\cnt[3]`) refer to bits
+            // of the bus
+            SaifDialect::Xilinx => name.strip_prefix('\\').unwrap_or(name).to_string(),
+        };
+        let name = match index {
+            Some(index) => format!("{name}[{index}]"),
+            None => name,
+        };
+        match self.escaping {
+            SaifEscaping::All => self.escape(&name),
+            SaifEscaping::Instances | SaifEscaping::None => name,
+        }
     }
 }
 
 /// Holds SAIF exporter's state corresponding to a given scope
 struct ScopeCtx {
-    name_escaped: String,
+    /// Designator of the instance: its name, optionally preceded by its module name
+    instance: String,
     instance_empty: bool,
+    /// Scope wraps the DUT and isn't exported
+    hidden: bool,
 }
 
 struct SaifAgent<'a> {
+    context: &'a Context,
     stats: &'a HashMap<HashVarRef, Vec<PackedStats>>,
     span_index: usize,
     scope_ctx: Vec<ScopeCtx>,
     indent: usize,
+    format: SaifFormat,
+    /// Full name of the DUT, scopes above which are not exported
+    dut: Option<String>,
 }
 
 impl<'a> SaifAgent<'a> {
-    fn new(
-        stats: &'a HashMap<HashVarRef, Vec<PackedStats>>,
-        span_index: usize,
-        indent: usize,
-    ) -> Self {
+    fn new(context: &'a Context, span_index: usize, indent: usize, format: SaifFormat) -> Self {
         Self {
-            stats,
+            context,
+            stats: &context.stats,
             span_index,
             scope_ctx: Vec::new(),
             indent,
+            format,
+            dut: None,
         }
     }
 }
 
 impl<'a> SaifAgent<'a> {
-    fn get_ctx<'s>(&'s self) -> &'s ScopeCtx {
+    fn get_ctx(&self) -> &ScopeCtx {
         self.scope_ctx
             .last()
             .expect("Scope context should be valid")
     }
-    fn get_ctx_mut<'s>(&'s mut self) -> &'s mut ScopeCtx {
+    fn get_ctx_mut(&mut self) -> &mut ScopeCtx {
         self.scope_ctx
             .last_mut()
             .expect("Scope context should be valid")
     }
-    fn get_parent_ctx_mut<'s>(&'s mut self) -> Option<&'s mut ScopeCtx> {
+    fn get_parent_ctx_mut(&mut self) -> Option<&mut ScopeCtx> {
         let len = self.scope_ctx.len();
         if len >= 2 {
             Some(&mut self.scope_ctx[len - 2])
@@ -77,6 +160,29 @@ impl<'a> SaifAgent<'a> {
         }
     }
 
+    fn instance(&self, scope: &Scope) -> String {
+        let name = self
+            .format
+            .instance_name(scope.name(self.context.wave.hierarchy()));
+        let module = self
+            .format
+            .module_names
+            .then(|| self.context.scope_module_name(scope))
+            .flatten();
+        match module {
+            Some(module) => format!("\"{module}\" {name}"),
+            None => name,
+        }
+    }
+
+    /// Whether the scope lies outside of the subtree of the DUT, i.e. above it or next to it
+    fn outside_dut(&self, scope: &Scope, hier: &wellen::Hierarchy) -> bool {
+        self.dut.as_ref().is_some_and(|dut| {
+            let name = scope.full_name(hier);
+            name != *dut && !name.starts_with(&format!("{dut}."))
+        })
+    }
+
     fn write_indent<W>(&self, out: &mut W) -> std::io::Result<()>
     where
         W: std::io::Write,
@@ -93,7 +199,7 @@ impl<'a> SaifAgent<'a> {
     {
         self.write_indent(out)?;
         self.indent += 1;
-        write!(out, "({params}\n")
+        writeln!(out, "({params}")
     }
 
     fn end_scope<W>(&mut self, out: &mut W) -> std::io::Result<()>
@@ -102,31 +208,50 @@ impl<'a> SaifAgent<'a> {
     {
         self.indent -= 1;
         self.write_indent(out)?;
-        write!(out, ")\n")
+        writeln!(out, ")")
     }
 
-    fn write_net_stat<W, S>(
+    /// Write stats of a net, with a value for each of its bits
+    fn write_net_stats<W>(
         &self,
         ctx: &mut TraceVisitCtx<W>,
-        name: S,
-        stat: &SignalStats,
+        name: &str,
+        stats: &[SignalStats],
     ) -> Result<(), std::io::Error>
     where
         W: std::io::Write,
-        S: Into<String>,
     {
+        let values = |value: fn(&SignalStats) -> u32| {
+            stats
+                .iter()
+                .map(|stat| value(stat).to_string())
+                .collect::<Vec<_>>()
+                .join(" ")
+        };
         self.write_indent(ctx.out)?;
         write!(
             ctx.out,
-            "({} (T0 {}) (T1 {}) (TX {}) (TZ {}) (TC {}) (IG {}))\n",
-            name.into().replace('\\', "\\\\"),
-            stat.low_time,
-            stat.high_time,
-            stat.x_time,
-            stat.z_time,
-            stat.clean_trans_count,
-            stat.glitch_trans_count
-        )
+            "({} (T0 {}) (T1 {}) (TX {}) (TZ {}) (TC {}) (IG {})",
+            name,
+            values(|stat| stat.low_time),
+            values(|stat| stat.high_time),
+            values(|stat| stat.x_time),
+            values(|stat| stat.z_time),
+            values(|stat| stat.clean_trans_count),
+            values(|stat| stat.glitch_trans_count)
+        )?;
+        // Optional fields are only written if they hold any information
+        if stats.iter().any(|stat| stat.interconnect_glitch_count != 0) {
+            write!(
+                ctx.out,
+                " (IK {})",
+                values(|stat| stat.interconnect_glitch_count)
+            )?;
+        }
+        if stats.iter().any(|stat| stat.bus_contention_time != 0) {
+            write!(ctx.out, " (TB {})", values(|stat| stat.bus_contention_time))?;
+        }
+        writeln!(ctx.out, ")")
     }
 }
 
@@ -134,7 +259,7 @@ impl<'w, W> TraceVisitorAgent<'w, W> for SaifAgent<'w>
 where
     W: std::io::Write,
 {
-    type Error = std::io::Error;
+    type Error = Error;
 
     fn enter_net(
         &mut self,
@@ -145,35 +270,49 @@ where
         let net = hier.get(var_ref);
         let zero = !net.full_name(hier).contains(ctx.power_scope);
 
+        if self.get_ctx().hidden {
+            return Ok(());
+        }
+
         if self.get_ctx().instance_empty {
-            let scope_str = format!("INSTANCE {}", self.get_ctx().name_escaped);
+            let scope_str = format!("INSTANCE {}", self.get_ctx().instance);
             self.begin_scope(ctx.out, scope_str.as_str())?;
             self.begin_scope(ctx.out, "NET")?;
             self.get_ctx_mut().instance_empty = false;
         }
 
-        let my_stats = &self.stats[&HashVarRef(var_ref)][self.span_index];
-        match my_stats {
-            PackedStats::OneBit(stat) => {
-                let name = indexed_name(net.name(ctx.waveform.hierarchy()).into(), net);
-                if zero {
-                    let stat = SignalStats::default();
-                    self.write_net_stat(ctx, name, &stat)?;
-                } else {
-                    self.write_net_stat(ctx, name, stat)?;
-                }
+        let bits = self.stats[&HashVarRef(var_ref)][self.span_index].bits();
+        let empty = vec![SignalStats::default(); bits.len()];
+        let bits = if zero { &empty } else { bits };
+        let name = net.name(hier);
+        match self.stats[&HashVarRef(var_ref)][self.span_index] {
+            PackedStats::OneBit(_) => {
+                let name = self.format.net_name(name, net.index().map(|idx| idx.lsb()));
+                self.write_net_stats(ctx, &name, bits)?;
             }
-            PackedStats::Vector(stats) => {
-                for (idx, stat) in stats.iter().enumerate() {
-                    let name = format!("{}[{}]", net.name(ctx.waveform.hierarchy()), idx);
-                    if zero {
-                        let stat = SignalStats::default();
-                        self.write_net_stat(ctx, name, &stat)?;
-                    } else {
-                        self.write_net_stat(ctx, name, stat)?;
+            PackedStats::Vector(_) if bits.is_empty() => {}
+            PackedStats::Vector(_) => match self.format.bus {
+                SaifBusNotation::Bits => {
+                    for (idx, stat) in bits.iter().enumerate() {
+                        let idx = match self.format.dialect {
+                            SaifDialect::Standard => idx as i64,
+                            SaifDialect::Xilinx => bit_index(net, idx),
+                        };
+                        let name = self.format.net_name(name, Some(idx));
+                        self.write_net_stats(ctx, &name, std::slice::from_ref(stat))?;
                     }
                 }
-            }
+                SaifBusNotation::Range => {
+                    let name = format!(
+                        "{}[{}:{}]",
+                        self.format.net_name(name, None),
+                        bit_index(net, bits.len() - 1),
+                        bit_index(net, 0)
+                    );
+                    let msb_first: Vec<_> = bits.iter().rev().cloned().collect();
+                    self.write_net_stats(ctx, &name, &msb_first)?;
+                }
+            },
         }
 
         Ok(())
@@ -181,7 +320,7 @@ where
 
     fn end_nets(&mut self, ctx: &mut TraceVisitCtx<W>) -> Result<(), Self::Error> {
         if !self.get_ctx().instance_empty {
-            self.end_scope(ctx.out) // End NETS scope
+            Ok(self.end_scope(ctx.out)?) // End NETS scope
         } else {
             Ok(())
         }
@@ -192,13 +331,16 @@ where
         ctx: &mut TraceVisitCtx<W>,
         scope: &'w Scope,
     ) -> Result<(), Self::Error> {
+        let hier = ctx.waveform.hierarchy();
+        let hidden = self.outside_dut(scope, hier);
         self.scope_ctx.push(ScopeCtx {
-            name_escaped: scope
-                .name(ctx.waveform.hierarchy())
-                .replace('[', "\\[")
-                .replace(']', "\\]"),
+            instance: self.instance(scope),
             instance_empty: true,
+            hidden,
         });
+        if hidden {
+            return Ok(());
+        }
 
         // TODO: Scope export should be deferred until it's determined there's at least one
         // net down the hierarchy tha should be exported. The reason for that is to avoid exporting
@@ -208,11 +350,12 @@ where
         // Begin parent's scope if it was empty
         if let Some(ScopeCtx {
             ref instance_empty,
-            name_escaped,
+            instance,
+            hidden,
         }) = self.get_parent_ctx_mut()
         {
-            if *instance_empty {
-                let scope_str = format!("INSTANCE {}", name_escaped);
+            if *instance_empty && !*hidden {
+                let scope_str = format!("INSTANCE {}", instance);
                 self.begin_scope(ctx.out, scope_str.as_str())?;
             }
         }
@@ -223,25 +366,29 @@ where
     fn exit_scope(
         &mut self,
         ctx: &mut TraceVisitCtx<W>,
-        _scope: &'w Scope,
+        scope: &'w Scope,
     ) -> Result<(), Self::Error> {
-        self.end_scope(ctx.out)
+        if self.outside_dut(scope, ctx.waveform.hierarchy()) {
+            return Ok(());
+        }
+        Ok(self.end_scope(ctx.out)?)
     }
 }
 
-pub fn export<W>(ctx: &Context, mut out: W, iteration: usize) -> std::io::Result<()>
+pub fn export<W>(ctx: &Context, mut out: W, iteration: usize) -> Result<(), Error>
 where
     W: std::io::Write,
 {
-    let hier = ctx.wave.hierarchy();
-    let time_end = *ctx
-        .wave
-        .time_table()
-        .last()
-        .expect("Waveform shouldn't be empty");
-    let timescale = hier
-        .timescale()
-        .expect("Waveform should contain a timescale");
+    let timescale = ctx.timescale;
+    let format = SaifFormat::new(ctx);
+    let design = match ctx.netlist.as_ref() {
+        _ if !ctx.top.is_empty() => format!("\"{}\"", ctx.top),
+        Some(netlist) => netlist
+            .top_module()
+            .map(|top| format!("\"{top}\""))
+            .unwrap_or_default(),
+        None => String::new(),
+    };
 
     write!(
         out,
@@ -250,17 +397,25 @@ where
             (SAIFILE
               (SAIFVERSION \"2.0\")
               (DIRECTION \"backward\")
-              (DESIGN )
+              (DESIGN {})
               (PROGRAM_NAME \"{}\")
-              (DIVIDER / )
-              (TIMESCALE {}{})
+              (DIVIDER {} )
+              (TIMESCALE {}{}{})
               (DURATION {})
         "
         ),
+        design,
         clap::crate_name!(),
+        format.divider,
         timescale.factor,
+        // Vivado separates the unit from the factor
+        if ctx.saif_dialect == SaifDialect::Xilinx {
+            " "
+        } else {
+            ""
+        },
         DisplayTimescaleUnit(timescale.unit),
-        time_end / ctx.num_of_iterations
+        ctx.window(iteration).active
     )?;
 
     if !ctx.ignore_date {
@@ -271,32 +426,17 @@ where
         writeln!(out, "  (VERSION \"{}\")", clap::crate_version!())?;
     }
 
-    let netlist_root = match ctx.top_scope {
-        Some(scope_ref) => hier
-            .get(scope_ref)
-            .full_name(hier)
-            .split('.')
-            .map(String::from)
-            .collect::<Vec<_>>(),
-        None => Vec::new(),
-    };
+    let mut visitor_ctx = TraceVisitCtx::new(ctx, &mut out);
 
-    let mut visitor_ctx = TraceVisitCtx {
-        out: &mut out,
-        waveform: &ctx.wave,
-        netlist_root,
-        top_module: &ctx.top,
-        netlist: ctx.netlist.as_ref(),
-        netlist_prefix: Vec::new(),
-        blackboxes_only: ctx.blackboxes_only,
-        remove_virtual_pins: ctx.remove_virtual_pins,
-        power_scope: &ctx.power_scope_prefix,
-    };
-
-    let mut agent = SaifAgent::new(&ctx.stats, iteration, 1);
+    let mut agent = SaifAgent::new(ctx, iteration, 1, format);
+    if ctx.saif_dialect == SaifDialect::Xilinx {
+        let hier = ctx.wave.hierarchy();
+        let top_scope = ctx.top_scope.ok_or(Error::MissingTopScope)?;
+        agent.dut = Some(hier.get(top_scope).full_name(hier));
+    }
     agent.visit_hierarchy(ctx.lookup_point, &mut visitor_ctx)?;
 
-    write!(out, ")\n")?;
+    writeln!(out, ")")?;
 
     Ok(())
 }
@@ -0,0 +1,187 @@
+// Copyright (c) 2024-2026 Antmicro <www.antmicro.com>
+// SPDX-License-Identifier: Apache-2.0
+
+use std::collections::HashMap;
+
+use chrono::Utc;
+use wellen::{GetItem, Scope, VarRef};
+
+use super::{DisplayTimescaleUnit, TraceVisit, TraceVisitCtx, TraceVisitorAgent};
+use crate::error::Error;
+use crate::stats::{PackedStats, SignalStats};
+use crate::util::ratio;
+use crate::{Context, HashVarRef, bit_index, indexed_name};
+
+/// Escape a name so that it can be placed within a quoted TCF string
+fn quoted(name: &str) -> String {
+    format!("\"{}\"", name.replace('\\', "\\\\").replace('"', "\\\""))
+}
+
+struct TcfAgent<'a> {
+    stats: &'a HashMap<HashVarRef, Vec<PackedStats>>,
+    span_index: usize,
+    /// Duration of the exported window (in timescale units)
+    duration: f64,
+    /// Whether a `net()` group has been opened in the current scope
+    nets_open: bool,
+    indent: usize,
+}
+
+impl TcfAgent<'_> {
+    fn write_indent<W>(&self, out: &mut W) -> std::io::Result<()>
+    where
+        W: std::io::Write,
+    {
+        for _ in 0..self.indent {
+            write!(out, "  ")?;
+        }
+        Ok(())
+    }
+
+    fn begin_group<W>(&mut self, out: &mut W, group: &str) -> std::io::Result<()>
+    where
+        W: std::io::Write,
+    {
+        self.write_indent(out)?;
+        self.indent += 1;
+        writeln!(out, "{group} {{")
+    }
+
+    fn end_group<W>(&mut self, out: &mut W) -> std::io::Result<()>
+    where
+        W: std::io::Write,
+    {
+        self.indent -= 1;
+        self.write_indent(out)?;
+        writeln!(out, "}}")
+    }
+
+    /// Write probability of a net being high and the number of its toggles
+    fn write_net_stat<W>(&self, out: &mut W, name: &str, stat: &SignalStats) -> std::io::Result<()>
+    where
+        W: std::io::Write,
+    {
+        self.write_indent(out)?;
+        writeln!(
+            out,
+            "{} : \"{} {}\";",
+            quoted(name),
+            ratio(stat.high_time as f64, self.duration),
+            stat.clean_trans_count
+        )
+    }
+}
+
+impl<'w, W> TraceVisitorAgent<'w, W> for TcfAgent<'w>
+where
+    W: std::io::Write,
+{
+    type Error = Error;
+
+    fn enter_scope(
+        &mut self,
+        ctx: &mut TraceVisitCtx<W>,
+        scope: &'w Scope,
+    ) -> Result<(), Self::Error> {
+        let name = quoted(scope.name(ctx.waveform.hierarchy()));
+        self.begin_group(ctx.out, &format!("instance({name})"))?;
+        self.nets_open = false;
+        Ok(())
+    }
+
+    fn exit_scope(
+        &mut self,
+        ctx: &mut TraceVisitCtx<W>,
+        _scope: &'w Scope,
+    ) -> Result<(), Self::Error> {
+        Ok(self.end_group(ctx.out)?)
+    }
+
+    fn enter_net(
+        &mut self,
+        ctx: &mut TraceVisitCtx<W>,
+        var_ref: VarRef,
+    ) -> Result<(), Self::Error> {
+        let hier = ctx.waveform.hierarchy();
+        let net = hier.get(var_ref);
+        let zero = !net.full_name(hier).contains(ctx.power_scope);
+        let empty = SignalStats::default();
+
+        if !self.nets_open {
+            self.begin_group(ctx.out, "net()")?;
+            self.nets_open = true;
+        }
+
+        match &self.stats[&HashVarRef(var_ref)][self.span_index] {
+            PackedStats::OneBit(stat) => {
+                let name = indexed_name(net.name(hier).into(), net);
+                self.write_net_stat(ctx.out, &name, if zero { &empty } else { stat })?;
+            }
+            PackedStats::Vector(stats) => {
+                for (idx, stat) in stats.iter().enumerate() {
+                    let name = format!("{}[{}]", net.name(hier), bit_index(net, idx));
+                    self.write_net_stat(ctx.out, &name, if zero { &empty } else { stat })?;
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    fn end_nets(&mut self, ctx: &mut TraceVisitCtx<W>) -> Result<(), Self::Error> {
+        if self.nets_open {
+            self.end_group(ctx.out)?;
+            self.nets_open = false;
+        }
+        Ok(())
+    }
+}
+
+/// Write stats of a single iteration as a Toggle Count Format file. Each net gets its
+/// probability of being high and its number of clean transitions.
+pub fn export<W>(ctx: &Context, mut out: W, iteration: usize) -> Result<(), Error>
+where
+    W: std::io::Write,
+{
+    let window = ctx.window(iteration);
+
+    writeln!(out, "tcfFile() {{")?;
+    writeln!(out, "  header() {{")?;
+    writeln!(out, "    version(\"1.0\");")?;
+    writeln!(out, "    generator(\"{}\");", clap::crate_name!())?;
+    if !ctx.ignore_version {
+        writeln!(
+            out,
+            "    generator_version(\"{}\");",
+            clap::crate_version!()
+        )?;
+    }
+    if !ctx.ignore_date {
+        writeln!(
+            out,
+            "    date(\"{}\");",
+            Utc::now().format("%a %b %-d %T %Y")
+        )?;
+    }
+    writeln!(
+        out,
+        "    duration(\"{}{}\");",
+        window.active * ctx.timescale.factor as u64,
+        DisplayTimescaleUnit(ctx.timescale.unit)
+    )?;
+    writeln!(out, "  }}")?;
+
+    let mut visitor_ctx = TraceVisitCtx::new(ctx, &mut out);
+    let mut agent = TcfAgent {
+        stats: &ctx.stats,
+        span_index: iteration,
+        duration: window.active as f64,
+        nets_open: false,
+        indent: 1,
+    };
+    agent.visit_hierarchy(ctx.lookup_point, &mut visitor_ctx)?;
+
+    writeln!(out, "}}")?;
+
+    Ok(())
+}
@@ -5,38 +5,119 @@ use std::collections::BTreeMap;
 use std::{collections::HashMap, hash::Hash};
 use wellen::{GetItem, VarRef};
 
+use crate::error::Error;
 use crate::stats::{PackedStats, SignalStats};
-use crate::{HashVarRef, LookupPoint};
+use crate::util::ratio;
+use crate::{Context, HashVarRef, LookupPoint, bit_index};
 
 use super::{TraceVisit, TraceVisitCtx, TraceVisitorAgent};
 
+/// Flavour of Tcl commands setting switching activity
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, clap::ValueEnum)]
+pub enum TclDialect {
+    /// OpenSTA's `set_power_activity`
+    #[default]
+    Opensta,
+    /// PrimeTime's `set_switching_activity` applied to groups of pins
+    Primetime,
+    /// Voltus' `set_switching_activity` applied to each pin separately
+    Voltus,
+    /// Vivado's `set_switching_activity` applied to groups of nets
+    Vivado,
+}
+
+/// Hierarchical name of a pin
+struct PinName {
+    /// Names of the scopes, starting from the root of the hierarchy, and the net
+    path: Vec<String>,
+    /// Index of the bit within a net
+    index: Option<i64>,
+    /// Whether the net has been bit-blasted by trace2power (as opposed to a one-bit variable
+    /// that has an index in the trace)
+    blasted: bool,
+    /// Index of the bit as declared in the HDL
+    bit: Option<i64>,
+}
+
+impl PinName {
+    /// Name in OpenSTA's notation, still including the lookup scope prefix
+    fn opensta(&self) -> String {
+        let name = self.path.join("/");
+        match (self.index, self.blasted) {
+            (Some(index), true) => format!("{name}[{index}]"),
+            _ => name,
+        }
+    }
+
+    /// Name of a net after synthesis in Vivado. Escaped identifiers produced by bit-blasting a bus
+    /// (e.g. `\\cnt[3]`) refer to bits of the bus.
+    fn vivado(&self, skip: usize) -> String {
+        let name = self.path[skip..]
+            .iter()
+            .map(|part| part.strip_prefix('\\').unwrap_or(part))
+            .collect::<Vec<_>>()
+            .join("/");
+        match self.bit {
+            Some(bit) => format!("{name}[{bit}]"),
+            None => name,
+        }
+    }
+
+    /// Name in the notation shared by PrimeTime and Voltus, to be placed within braces. Brackets
+    /// which are a part of instance or net names (e.g. escaped identifiers) get escaped, so that
+    /// they aren't mistaken for bus bits.
+    fn signoff(&self, skip: usize) -> String {
+        let name = self.path[skip..]
+            .iter()
+            .map(|part| {
+                part.strip_prefix('\\')
+                    .unwrap_or(part)
+                    .replace('{', "\\{")
+                    .replace('}', "\\}")
+                    .replace('[', "\\[")
+                    .replace(']', "\\]")
+            })
+            .collect::<Vec<_>>()
+            .join("/");
+        match self.bit {
+            Some(bit) => format!("{name}[{bit}]"),
+            None => name,
+        }
+    }
+}
+
 /// Minimal stats, used for Tcl export, hashable to allow grouping
 #[derive(Hash, Eq, Default, PartialEq, PartialOrd, Ord)]
 struct TclStat {
     high_time: u32,
     trans_count_doubled: u32,
+    /// Index of the clock the activity is normalized to
+    clock: usize,
 }
 
-impl From<&SignalStats> for TclStat {
-    fn from(value: &SignalStats) -> Self {
+impl TclStat {
+    fn new(value: &SignalStats, clock: usize) -> Self {
         Self {
             high_time: value.high_time,
             trans_count_doubled: value.trans_count_doubled,
+            clock,
         }
     }
 }
 
 struct TclAgent<'a> {
+    context: &'a Context,
     stats: &'a HashMap<HashVarRef, Vec<PackedStats>>,
     span_index: usize,
-    grouped_stats: BTreeMap<TclStat, Vec<String>>,
+    grouped_stats: BTreeMap<TclStat, Vec<PinName>>,
     scope: Vec<String>,
 }
 
 impl<'a> TclAgent<'a> {
-    fn new(stats: &'a HashMap<HashVarRef, Vec<PackedStats>>, span_index: usize) -> Self {
+    fn new(context: &'a Context, span_index: usize) -> Self {
         Self {
-            stats,
+            context,
+            stats: &context.stats,
             span_index,
             grouped_stats: BTreeMap::new(),
             scope: Vec::new(),
@@ -48,7 +129,7 @@ impl<'w, W> TraceVisitorAgent<'w, W> for TclAgent<'w>
 where
     W: std::io::Write,
 {
-    type Error = std::io::Error;
+    type Error = Error;
 
     fn enter_net(
         &mut self,
@@ -57,12 +138,13 @@ where
     ) -> Result<(), Self::Error> {
         let hier = ctx.waveform.hierarchy();
         let net = hier.get(var_ref);
-        let scope_str = self.scope.join("/");
         let zero = !net.full_name(hier).contains(ctx.power_scope);
 
         let stats = &self.stats[&HashVarRef(var_ref)];
+        let clock = self.context.net_clock(var_ref);
 
-        let fname = format!("{}/{}", scope_str, net.name(ctx.waveform.hierarchy()));
+        let mut path = self.scope.clone();
+        path.push(net.name(ctx.waveform.hierarchy()).to_string());
 
         match &stats[self.span_index] {
             PackedStats::OneBit(stat) => {
@@ -70,10 +152,15 @@ where
                     .entry(if zero {
                         Default::default()
                     } else {
-                        TclStat::from(stat)
+                        TclStat::new(stat, clock)
                     })
-                    .or_insert_with(|| vec![])
-                    .push(fname);
+                    .or_default()
+                    .push(PinName {
+                        path,
+                        index: net.index().map(|idx| idx.lsb()),
+                        blasted: false,
+                        bit: net.index().map(|idx| idx.lsb()),
+                    });
             }
             PackedStats::Vector(stats) => {
                 for (idx, stat) in stats.iter().enumerate() {
@@ -81,10 +168,15 @@ where
                         .entry(if zero {
                             Default::default()
                         } else {
-                            TclStat::from(stat)
+                            TclStat::new(stat, clock)
                         })
-                        .or_insert_with(|| vec![])
-                        .push(format!("{}[{}]", fname, idx));
+                        .or_default()
+                        .push(PinName {
+                            path: path.clone(),
+                            index: Some(idx as i64),
+                            blasted: true,
+                            bit: Some(bit_index(net, idx)),
+                        });
                 }
             }
         }
@@ -111,40 +203,15 @@ where
     }
 }
 
-pub fn export<W>(ctx: &crate::Context, mut out: W, iteration: usize) -> std::io::Result<()>
+pub fn export<W>(ctx: &Context, mut out: W, iteration: usize) -> Result<(), Error>
 where
     W: std::io::Write,
 {
-    let hier = ctx.wave.hierarchy();
-    let time_end = *ctx
-        .wave
-        .time_table()
-        .last()
-        .expect("Waveform shouldn't be empty");
-
-    let netlist_root = match ctx.top_scope {
-        Some(scope_ref) => hier
-            .get(scope_ref)
-            .full_name(hier)
-            .split('.')
-            .map(String::from)
-            .collect::<Vec<_>>(),
-        None => Vec::new(),
-    };
-
-    let mut visitor_ctx = TraceVisitCtx {
-        out: &mut out,
-        waveform: &ctx.wave,
-        netlist_root,
-        top_module: &ctx.top,
-        netlist: ctx.netlist.as_ref(),
-        netlist_prefix: Vec::new(),
-        blackboxes_only: ctx.blackboxes_only,
-        remove_virtual_pins: ctx.remove_virtual_pins,
-        power_scope: &ctx.power_scope_prefix,
-    };
-
-    let mut agent = TclAgent::new(&ctx.stats, iteration);
+    let mut visitor_ctx = TraceVisitCtx::new(ctx, &mut out);
+
+    let mut agent = TclAgent::new(ctx, iteration);
+    // Number of scopes above the exported nets which are dropped from their names
+    let mut prefix_len = 0;
     if let LookupPoint::Scope(scope_ref) = ctx.lookup_point {
         let scope_name = ctx
             .wave
@@ -152,44 +219,122 @@ where
             .get(scope_ref)
             .full_name(ctx.wave.hierarchy());
         let mut scope: Vec<_> = scope_name.split('.').map(ToString::to_string).collect();
+        prefix_len = scope.len();
         scope.pop();
         agent.scope = scope;
     }
     agent.visit_hierarchy(ctx.lookup_point, &mut visitor_ctx)?;
 
-    let timescale = ctx
-        .wave
-        .hierarchy()
-        .timescale()
-        .expect("Waveform should contain a timescale");
-    let timescale_norm = (timescale.factor as f64)
-        * (10.0_f64).powf(
-            timescale
-                .unit
-                .to_exponent()
-                .expect("Waveform should contain a time unit") as f64,
-        );
+    let timescale_norm = ctx.timescale_norm;
+    let active = ctx.window(iteration).active as f64;
+    let window_s = active * timescale_norm;
 
     writeln!(out, "proc set_pin_activity_and_duty {{}} {{")?;
     for (stats, pins) in agent.grouped_stats {
+        let clock = &ctx.clocks[stats.clock];
         let (duty, activity) = if ctx.export_empty {
             (0.0, 0.0)
         } else {
             (
-                (stats.high_time as f64) / ((time_end / ctx.num_of_iterations) as f64),
-                ((stats.trans_count_doubled as f64) / 2.0_f64)
-                    / (((time_end / ctx.num_of_iterations) as f64) * timescale_norm
-                        / ctx.clk_period),
+                ratio(stats.high_time as f64, active),
+                ratio(
+                    (stats.trans_count_doubled as f64) / 2.0_f64,
+                    window_s / clock.period,
+                ),
             )
         };
 
+        match ctx.tcl_dialect {
+            TclDialect::Opensta => write_opensta(ctx, &mut out, &pins, activity, duty)?,
+            TclDialect::Primetime => {
+                // Without a base clock toggle rate is expressed in toggles per nanosecond
+                let (toggle_rate, base_clock) = match &clock.base_clock {
+                    Some(base_clock) => (activity, format!(" -base_clock {{{base_clock}}}")),
+                    None => (activity / (clock.period * 1e9), String::new()),
+                };
+                let pins = pins
+                    .iter()
+                    .map(|pin| pin.signoff(prefix_len))
+                    .collect::<Vec<_>>()
+                    .join(" ");
+                writeln!(
+                    out,
+                    "  set_switching_activity -toggle_rate {toggle_rate} -static_probability {duty}{base_clock} [get_pins {{{pins}}}]"
+                )?;
+                if ctx.input_ports_activity {
+                    writeln!(
+                        out,
+                        "  set_switching_activity -toggle_rate {toggle_rate} -static_probability {duty}{base_clock} [get_ports {{{pins}}}]"
+                    )?;
+                }
+            }
+            TclDialect::Vivado => {
+                // Vivado expects toggle rate as a percentage of the clock frequency
+                let toggle_rate = activity * 100.0;
+                let nets = pins
+                    .iter()
+                    .map(|pin| pin.vivado(prefix_len))
+                    .collect::<Vec<_>>()
+                    .join(" ");
+                writeln!(
+                    out,
+                    "  set_switching_activity -toggle_rate {toggle_rate} -static_probability {duty} [get_nets {{{nets}}}]"
+                )?;
+                if ctx.input_ports_activity {
+                    writeln!(
+                        out,
+                        "  set_switching_activity -toggle_rate {toggle_rate} -static_probability {duty} [get_ports {{{nets}}}]"
+                    )?;
+                }
+            }
+            TclDialect::Voltus => {
+                let period_ns = clock.period * 1e9;
+                for pin in &pins {
+                    writeln!(
+                        out,
+                        "  set_switching_activity -pin {{{}}} -activity {activity} -duty {duty} -period {period_ns}",
+                        pin.signoff(prefix_len)
+                    )?;
+                }
+            }
+        }
+    }
+    writeln!(out, "}}")?;
+
+    Ok(())
+}
+
+fn write_opensta<W>(
+    ctx: &Context,
+    out: &mut W,
+    pins: &[PinName],
+    activity: f64,
+    duty: f64,
+) -> std::io::Result<()>
+where
+    W: std::io::Write,
+{
+    writeln!(
+        out,
+        "  set_power_activity -pins \"{}\" -activity {} -duty {}",
+        itertools::Itertools::intersperse(
+            pins.iter()
+                .map(|pin| pin.opensta()[ctx.scope_prefix_length..]
+                    .replace('\\', "")
+                    .replace('$', "\\$")),
+            " ".into()
+        )
+        .collect::<String>(),
+        activity,
+        duty
+    )?;
+    if ctx.input_ports_activity {
         writeln!(
             out,
-            "  set_power_activity -pins \"{}\" -activity {} -duty {}",
+            "  set_power_activity -input_ports \"{}\" -activity {} -duty {}",
             itertools::Itertools::intersperse(
-                pins.clone()
-                    .into_iter()
-                    .map(|n| n[ctx.scope_prefix_length..]
+                pins.iter()
+                    .map(|pin| pin.opensta()[ctx.scope_prefix_length..]
                         .replace('\\', "")
                         .replace('$', "\\$")),
                 " ".into()
@@ -198,23 +343,6 @@ where
             activity,
             duty
         )?;
-        if ctx.input_ports_activity {
-            writeln!(
-                out,
-                "  set_power_activity -input_ports \"{}\" -activity {} -duty {}",
-                itertools::Itertools::intersperse(
-                    pins.into_iter().map(|n| n[ctx.scope_prefix_length..]
-                        .replace('\\', "")
-                        .replace('$', "\\$")),
-                    " ".into()
-                )
-                .collect::<String>(),
-                activity,
-                duty
-            )?;
-        }
     }
-    writeln!(out, "}}")?;
-
     Ok(())
 }
@@ -1,23 +1,43 @@
 // Copyright (c) 2024-2026 Antmicro <www.antmicro.com>
 // SPDX-License-Identifier: Apache-2.0
 
-use std::collections::BTreeSet;
 use std::str::FromStr;
 use std::{collections::HashMap, io};
 use std::{fs, hash, path};
 
 use clap::Parser;
-use rayon::prelude::*;
-use stats::PackedStats;
-use wellen::{self, GetItem, Hierarchy, ScopeRef, SignalRef, Var, VarRef, simple::Waveform};
-
-mod exporters;
+use clap::builder::TypedValueParser;
+use stats::{Activity, FilteredChanges, OffPeriods, PackedStats, PulseFilter};
+use wellen::{self, Hierarchy, ScopeRef, Timescale, Var, VarRef, simple::Waveform};
+
+pub mod builder;
+pub mod clock;
+pub mod condition;
+pub mod energy;
+pub mod error;
+pub mod exporters;
+pub mod liberty;
 pub mod netlist;
+pub mod power;
+pub mod query;
+pub mod spef;
 pub mod stats;
+pub mod upf;
 pub mod util;
 
+pub use builder::ContextBuilder;
+use clock::{ClockDomain, ClockSpec};
+use condition::Condition;
+use energy::EnergyTable;
+use error::{Result, Warning};
+pub use exporters::profile::ProfileMetric;
+pub use exporters::saif::{SaifBusNotation, SaifDialect, SaifEscaping};
+pub use exporters::tcl::TclDialect;
+use exporters::{Exporter, ExporterRegistry, WindowInfo};
+use liberty::Liberty;
 use netlist::Netlist;
-use util::VarRefsIter;
+use spef::Spef;
+use upf::TraceDomain;
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 struct HashVarRef(VarRef);
@@ -29,19 +49,24 @@ impl hash::Hash for HashVarRef {
 }
 
 /// trace2power - Extract acccumulated power activity data from VCD/FST
-#[derive(Parser)]
+#[derive(Parser, Default)]
 pub struct Args {
     /// Trace file
     pub input_file: path::PathBuf,
-    /// Clock frequency (in Hz)
+    /// Clock frequency (in Hz). Measured from the clock given with `--clock-name` if omitted.
     #[arg(short, long, value_parser = clap::value_parser!(f64))]
-    pub clk_freq: f64,
-    /// Clock signal name
+    pub clk_freq: Option<f64>,
+    /// Path of the clock net, relative to `--limit-scope` or absolute, which can contain glob
+    /// patterns. A name without a path is looked up within `--limit-scope`.
     #[arg(long)]
     pub clock_name: Option<String>,
     /// Format to extract data into
     #[arg(short = 'f', long, default_value = "tcl")]
     pub output_format: OutputFormat,
+    /// Name of a format registered by a program embedding trace2power (see [`process_with`]),
+    /// used instead of `output_format`
+    #[arg(long)]
+    pub custom_format: Option<String>,
     /// Scope in which signals should be looked for. By default it's the global hierarchy scope.
     #[arg(long, short)]
     pub limit_scope: Option<String>,
@@ -53,7 +78,7 @@ pub struct Args {
     /// Allows skipping unnecessary or unwanted signals
     #[arg(short, long)]
     pub netlist: Option<path::PathBuf>,
-    /// Name of the top module (DUT)
+    /// Name of the top module (DUT). By default it's the module marked as top in the netlist.
     #[arg(short, long)]
     pub top: Option<String>,
     /// Scope at which the DUT is located. The loaded netlist will be rooted at this point.
@@ -79,15 +104,114 @@ pub struct Args {
     /// Accumulate stats for each clock cycle separately. Output path is required to be a directory.
     #[arg(long)]
     pub per_clock_cycle: bool,
-    /// Write stats only for glitches
+    /// Write stats only for glitches. Same as `--activity glitches`.
     #[arg(long)]
     pub only_glitches: bool,
+    /// Kind of activity written for nets. Glitches are transitions beyond the functional one
+    /// between consecutive rising edges of the clock of a net.
+    #[arg(long, value_enum, default_value_t)]
+    pub activity: Activity,
+    /// Write functional activity and glitches into separate files, named after `--output` with
+    /// `.functional` and `.glitches` inserted before its extension
+    #[arg(long)]
+    pub split_glitches: bool,
     /// Export without accumulation
     #[arg(long)]
     pub export_empty: bool,
     /// Set activity for input ports in TCL mode
     #[arg(long)]
     pub input_ports_activity: bool,
+    /// Skip nets without any transitions in CSV/TSV mode
+    #[arg(long)]
+    pub sparse: bool,
+    /// Depth of the hierarchy down to which scopes get their own signal in VCD mode
+    #[arg(long)]
+    pub profile_depth: Option<usize>,
+    /// Value carried by the signals in VCD mode
+    #[arg(long, value_enum, default_value_t)]
+    pub profile_metric: ProfileMetric,
+    /// Tool for which commands are generated in TCL mode
+    #[arg(long, value_enum, default_value_t)]
+    pub tcl_dialect: TclDialect,
+    /// Tool for which the file is generated in SAIF mode
+    #[arg(long, value_enum, default_value_t)]
+    pub saif_dialect: SaifDialect,
+    /// Hierarchy divider in SAIF mode. By default it's `/`.
+    #[arg(
+        long,
+        value_parser = clap::builder::PossibleValuesParser::new(["/", "."])
+            .map(|divider| divider.chars().next().unwrap_or('/'))
+    )]
+    pub saif_divider: Option<char>,
+    /// Names with special characters escaped in SAIF mode. By default it depends on the dialect.
+    #[arg(long, value_enum)]
+    pub saif_escaping: Option<SaifEscaping>,
+    /// Notation of vector nets in SAIF mode. By default each bit gets a separate entry.
+    #[arg(long, value_enum)]
+    pub saif_bus: Option<SaifBusNotation>,
+    /// Precede instance names with names of their modules in SAIF mode
+    #[arg(long)]
+    pub saif_module_names: bool,
+    /// Clock which toggle rates are relative to in TCL mode with the PrimeTime dialect. By default
+    /// it's the name of the net of `clock_name`, without its path.
+    #[arg(long)]
+    pub base_clock: Option<String>,
+    /// Liberty library of cells used in the netlist, required in POWER mode. Can be given multiple
+    /// times.
+    #[arg(long)]
+    pub liberty: Vec<path::PathBuf>,
+    /// Capacitance of each net (in pF), added to the capacitance of the pins it drives in POWER
+    /// mode
+    #[arg(long, default_value_t = 0.0)]
+    pub wire_capacitance: f64,
+    /// Parasitics of the design, required in NET-POWER mode
+    #[arg(long)]
+    pub spef: Option<path::PathBuf>,
+    /// Supply voltage (in V) in NET-POWER mode. By default it's the nominal voltage of the Liberty
+    /// libraries.
+    #[arg(long)]
+    pub supply_voltage: Option<f64>,
+    /// CSV table with energy of pin transitions (in pJ) and leakage (in nW) of cells or modules,
+    /// used instead of Liberty libraries in POWER mode and with the `power` VCD metric. Other
+    /// table formats, such as TOML, aren't supported.
+    #[arg(long)]
+    pub energy_table: Option<path::PathBuf>,
+    /// Additional clock given as `NAME[=FREQ][@PATTERN,...]`. Activity of nets matching the glob
+    /// patterns, or clocked by the net `NAME` according to the netlist, is normalized to its
+    /// period. The frequency is measured in the trace if it's omitted. Can be given multiple times.
+    #[arg(long, value_parser = clap::value_parser!(ClockSpec))]
+    pub clock: Vec<ClockSpec>,
+    /// UPF file describing power domains. Activity of nets in a domain isn't accumulated while
+    /// the control signal of its power switch turns it off.
+    #[arg(long)]
+    pub upf: Option<path::PathBuf>,
+    /// Width (in s) of the narrowest pulse which passes through gates. Narrower pulses are
+    /// filtered out, as they would be by the inertial delay of real gates.
+    #[arg(long)]
+    pub min_pulse_width: Option<f64>,
+    /// Width of the narrowest pulse on nets of instances of a cell type, given as `CELL=WIDTH`,
+    /// overriding `--min-pulse-width`. Can be given multiple times.
+    #[arg(long, value_parser = parse_cell_pulse_width)]
+    pub cell_pulse_width: Vec<(String, f64)>,
+    /// Count pulses narrower than the minimum width without filtering them out
+    #[arg(long)]
+    pub keep_narrow_pulses: bool,
+    /// Condition over trace signals, e.g. `busy && state == 4'b0011`. Activity is only
+    /// accumulated while it holds, and so are the durations rates and duty cycles are relative
+    /// to. Supports `&&`, `||`, `!`, parentheses and `==`/`!=` comparisons of buses. Names are
+    /// relative to `--limit-scope`, or absolute.
+    #[arg(long, value_parser = clap::value_parser!(Condition))]
+    pub enable: Option<Condition>,
+}
+
+fn parse_cell_pulse_width(s: &str) -> std::result::Result<(String, f64), String> {
+    let (cell, width) = s
+        .split_once('=')
+        .ok_or_else(|| format!("`{s}` is not given as `CELL=WIDTH`"))?;
+    let width = width
+        .parse()
+        .map_err(|_| format!("`{width}` is not a valid width"))?;
+    Ok((cell.to_string(), width))
 }
 
 impl Args {
@@ -96,13 +220,25 @@ impl Args {
     }
 }
 
+/// Name of a variable, followed by its index if it's a single bit of a bit-blasted vector
 fn indexed_name(mut name: String, variable: &Var) -> String {
-    if let Some(idx) = variable.index() {
+    if let Some(idx) = variable.index().filter(|idx| idx.msb() == idx.lsb()) {
         name += format!("[{}]", idx.lsb()).as_str();
     }
     name
 }
 
+/// Index of a bit of a vector variable given its position within the stats of the variable, where
+/// position 0 is the least significant bit
+fn bit_index(variable: &Var, position: usize) -> i64 {
+    let position = position as i64;
+    match variable.index() {
+        Some(idx) if idx.msb() >= idx.lsb() => idx.lsb() + position,
+        Some(idx) => idx.lsb() - position,
+        None => position,
+    }
+}
+
 fn get_scope_by_full_name(hier: &Hierarchy, scope_str: &str) -> Option<ScopeRef> {
     hier.lookup_scope(scope_str.split('.').collect::<Vec<_>>().as_slice())
 }
@@ -115,32 +251,80 @@ enum LookupPoint {
     Scope(ScopeRef),
 }
 
-#[derive(Copy, Clone)]
+#[derive(Copy, Clone, Default)]
 pub enum OutputFormat {
+    #[default]
     Tcl,
     Saif,
+    Tcf,
+    Json,
+    Csv,
+    Tsv,
+    Vcd,
+    Power,
+    NetPower,
+    Domains,
+    Clocks,
+    #[cfg(feature = "parquet")]
+    Parquet,
 }
 
 impl clap::ValueEnum for OutputFormat {
     fn value_variants<'a>() -> &'a [Self] {
-        &[Self::Tcl, Self::Saif]
+        &[
+            Self::Tcl,
+            Self::Saif,
+            Self::Tcf,
+            Self::Json,
+            Self::Csv,
+            Self::Tsv,
+            Self::Vcd,
+            Self::Power,
+            Self::NetPower,
+            Self::Domains,
+            Self::Clocks,
+            #[cfg(feature = "parquet")]
+            Self::Parquet,
+        ]
     }
     fn to_possible_value(&self) -> Option<clap::builder::PossibleValue> {
         use clap::builder::PossibleValue;
         match self {
             Self::Tcl => Some(PossibleValue::new("tcl")),
             Self::Saif => Some(PossibleValue::new("saif")),
+            Self::Tcf => Some(PossibleValue::new("tcf")),
+            Self::Json => Some(PossibleValue::new("json")),
+            Self::Csv => Some(PossibleValue::new("csv")),
+            Self::Tsv => Some(PossibleValue::new("tsv")),
+            Self::Vcd => Some(PossibleValue::new("vcd")),
+            Self::Power => Some(PossibleValue::new("power")),
+            Self::NetPower => Some(PossibleValue::new("net-power")),
+            Self::Domains => Some(PossibleValue::new("domains")),
+            Self::Clocks => Some(PossibleValue::new("clocks")),
+            #[cfg(feature = "parquet")]
+            Self::Parquet => Some(PossibleValue::new("parquet")),
         }
     }
 }
 
 impl FromStr for OutputFormat {
     type Err = io::Error;
-    fn from_str(s: &str) -> Result<Self, Self::Err> {
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
         match s.to_lowercase().as_str() {
             "tcl" => Ok(Self::Tcl),
             "saif" => Ok(Self::Saif),
-            other @ _ => Err(io::Error::new(
+            "tcf" => Ok(Self::Tcf),
+            "json" => Ok(Self::Json),
+            "csv" => Ok(Self::Csv),
+            "tsv" => Ok(Self::Tsv),
+            "vcd" => Ok(Self::Vcd),
+            "power" => Ok(Self::Power),
+            "net-power" => Ok(Self::NetPower),
+            "domains" => Ok(Self::Domains),
+            "clocks" => Ok(Self::Clocks),
+            #[cfg(feature = "parquet")]
+            "parquet" => Ok(Self::Parquet),
+            other => Err(io::Error::new(
                 io::ErrorKind::InvalidInput,
                 format!(
                     "Format {} is not a valid output format forthis program",
@@ -151,13 +335,22 @@ impl FromStr for OutputFormat {
     }
 }
 
-struct Context {
+/// Trace loaded together with its accumulated activity, ready to be exported.
+/// Use [`ContextBuilder`] to create one.
+pub struct Context {
     wave: Waveform,
+    time_end: wellen::Time,
+    timescale: Timescale,
+    timescale_norm: f64,
+    clk_freq: f64,
     clk_period: f64,
+    /// Activity of each net in each iteration, of the kind given by `activity`
     stats: HashMap<HashVarRef, Vec<PackedStats>>,
+    activity: Activity,
+    /// Activity of another kind, accumulated to be exported after `stats`
+    stashed_stats: Option<(Activity, HashMap<HashVarRef, Vec<PackedStats>>)>,
     pub num_of_iterations: u64,
     lookup_point: LookupPoint,
-    output_fmt: OutputFormat,
     scope_prefix_length: usize,
     netlist: Option<Netlist>,
     top: String,
@@ -169,226 +362,311 @@ struct Context {
     export_empty: bool,
     power_scope_prefix: String,
     input_ports_activity: bool,
+    sparse: bool,
+    profile_depth: Option<usize>,
+    profile_metric: ProfileMetric,
+    tcl_dialect: TclDialect,
+    saif_dialect: SaifDialect,
+    saif_divider: Option<char>,
+    saif_escaping: Option<SaifEscaping>,
+    saif_bus: Option<SaifBusNotation>,
+    saif_module_names: bool,
+    liberty: Option<Liberty>,
+    /// Capacitance of each net (in F)
+    wire_capacitance: f64,
+    spef: Option<Spef>,
+    /// Supply voltage (in V)
+    supply_voltage: Option<f64>,
+    energy_table: Option<EnergyTable>,
+    power_domains: Vec<TraceDomain>,
+    warnings: Vec<Warning>,
+    /// Primary clock followed by the declared ones
+    clocks: Vec<ClockDomain>,
+    /// Rising edges of each clock, empty if the clock wasn't found in the trace
+    clock_edges: Vec<Vec<wellen::Time>>,
+    /// Indices of clocks of nets outside the domain of the primary clock
+    net_clocks: HashMap<HashVarRef, usize>,
+    /// Filters of pulses narrower than the inertial delay of each net
+    pulse_filters: HashMap<HashVarRef, PulseFilter>,
+    /// History of each filtered signal, shared by nets with the same signal and filter
+    filtered_changes: HashMap<(wellen::SignalRef, PulseFilter), FilteredChanges>,
+    /// Periods in which the enable condition doesn't hold
+    disabled_periods: OffPeriods,
+    /// Full names of nets with computed stats, sorted
+    net_names: Vec<(String, VarRef)>,
+    /// Nets of the SPEF file resolved in the trace, sorted by name
+    spef_nets: Vec<power::SpefTraceNet>,
+    /// Capacitance of library cell inputs connected to each net bit of each module of the netlist
+    module_loads: HashMap<String, HashMap<u64, f64>>,
 }
 
 impl Context {
-    pub fn build_from_args(args: &Args) -> Self {
-        const LOAD_OPTS: wellen::LoadOptions = wellen::LoadOptions {
-            multi_thread: true,
-            remove_scopes_with_empty_name: false,
-        };
-
-        let mut wave = wellen::simple::read_with_options(
-            args.input_file
-                .to_str()
-                .expect("Arguments should contain a path to input trace file"),
-            &LOAD_OPTS,
-        )
-        .expect("Waveform parsing should end successfully");
-
-        let wave_hierarchy = wave.hierarchy();
-
-        let clk_period = 1.0_f64 / args.clk_freq;
-        let timescale = wave_hierarchy
-            .timescale()
-            .expect("Trace file should contain a timescale");
-        let timescale_norm = (timescale.factor as f64)
-            * (10.0_f64).powf(
-                timescale
-                    .unit
-                    .to_exponent()
-                    .expect("Waveform should contain time unit") as f64,
-            );
-
-        let lookup_point = match &args.limit_scope {
-            None => LookupPoint::Top,
-            Some(scope_str) => LookupPoint::Scope(
-                get_scope_by_full_name(wave_hierarchy, scope_str)
-                    .expect("Requested scope not found"),
-            ),
-        };
-
-        let lookup_scope_name_prefix = match lookup_point {
-            LookupPoint::Top => "".to_string(),
-            LookupPoint::Scope(scope_ref) => {
-                let scope = wave_hierarchy.get(scope_ref);
-                scope.full_name(wave_hierarchy).to_string() + "."
+    pub fn builder(input_file: impl Into<path::PathBuf>) -> ContextBuilder {
+        ContextBuilder::new(input_file)
+    }
+
+    /// Export stats of a single iteration (time window) in a given format. Parquet files are
+    /// assembled in memory, use [`Context::export_parquet`] to stream them instead.
+    pub fn export(
+        &self,
+        format: OutputFormat,
+        out: impl io::Write,
+        iteration: usize,
+    ) -> Result<()> {
+        match format {
+            OutputFormat::Tcl => exporters::tcl::export(self, out, iteration),
+            OutputFormat::Saif => exporters::saif::export(self, out, iteration),
+            OutputFormat::Tcf => exporters::tcf::export(self, out, iteration),
+            OutputFormat::Json => exporters::json::export(self, out, iteration),
+            OutputFormat::Csv => exporters::csv::export(self, out, iteration..iteration + 1, ','),
+            OutputFormat::Tsv => exporters::csv::export(self, out, iteration..iteration + 1, '\t'),
+            OutputFormat::Vcd => exporters::profile::export(self, out, iteration..iteration + 1),
+            OutputFormat::Power => exporters::power::export(self, out, iteration..iteration + 1),
+            OutputFormat::NetPower => {
+                exporters::net_power::export(self, out, iteration..iteration + 1)
+            }
+            OutputFormat::Domains => {
+                exporters::domains::export(self, out, iteration..iteration + 1)
             }
-        };
-
-        let (all_vars, all_signals): (Vec<_>, Vec<_>) = match lookup_point {
-            LookupPoint::Top => wave_hierarchy
-                .var_refs_iter()
-                .map(|var_ref| (var_ref, wave_hierarchy.get(var_ref).signal_ref()))
-                .unzip(),
-            LookupPoint::Scope(_) => wave_hierarchy
-                .var_refs_iter()
-                .map(|var_ref| (var_ref, wave_hierarchy.get(var_ref)))
-                .filter(|(_, var)| {
-                    let fname = indexed_name(var.full_name(wave_hierarchy.into()), var);
-                    fname.starts_with(&lookup_scope_name_prefix)
-                })
-                .map(|(var_ref, var)| (var_ref, var.signal_ref()))
-                .unzip(),
-        };
-
-        let all_signals_power: BTreeSet<_> = match &args.limit_scope_power {
-            None => wave_hierarchy
-                .var_refs_iter()
-                .map(|var_ref| wave_hierarchy.get(var_ref))
-                .map(|var| indexed_name(var.full_name(wave_hierarchy.into()), var))
-                .collect::<BTreeSet<_>>(),
-            Some(scope_str) => wave_hierarchy
-                .var_refs_iter()
-                .map(|var_ref| wave_hierarchy.get(var_ref))
-                .filter(|var| {
-                    let fname = indexed_name(var.full_name(wave_hierarchy.into()), var);
-                    fname.starts_with(scope_str)
-                })
-                .map(|var| indexed_name(var.full_name(wave_hierarchy.into()), var))
-                .collect::<BTreeSet<_>>(),
-        };
-
-        let clk_signal: Option<SignalRef> = match &args.clock_name {
-            None => None,
-            Some(clock_name) => {
-                let mut found: Option<SignalRef> = None;
-
-                for var_ref in wave_hierarchy.var_refs_iter() {
-                    let net = wave_hierarchy.get(var_ref);
-                    let sig_ref = net.signal_ref();
-                    if net.name(wave_hierarchy) == clock_name {
-                        found = Some(sig_ref)
-                    }
-                }
-
-                found
+            OutputFormat::Clocks => exporters::clocks::export(self, out, iteration..iteration + 1),
+            #[cfg(feature = "parquet")]
+            OutputFormat::Parquet => {
+                let mut buffer = Vec::new();
+                self.export_parquet(&mut buffer, iteration..iteration + 1)?;
+                let mut out = out;
+                Ok(out.write_all(&buffer)?)
             }
-        };
-
-        // TODO load signals that are under a power scope
-        wave.load_signals_multi_threaded(&all_signals);
-
-        let last_time_stamp = *wave
-            .time_table()
-            .last()
-            .expect("Given waveform shouldn't be empty");
-        let num_of_iterations = if args.per_clock_cycle {
-            (last_time_stamp as f64 * timescale_norm / clk_period) as u64
-        } else {
-            1
-        };
-
-        // TODO: A massive optimization that can be done here is to calculate stats only
-        // for exported signals instead of all nets
-        // It's easy to do with the current implementation of DFS (see src/exporter/mod.rs).
-        // However it's single-threaded and parallelizing it efficiently is non-trivial.
-        let stats: HashMap<HashVarRef, Vec<stats::PackedStats>> = all_vars
-            .par_iter()
-            .zip(all_signals)
-            .map(|(var_ref, sig_ref)| {
-                let fname = indexed_name(
-                    wave.hierarchy()
-                        .get(*var_ref)
-                        .full_name(wave.hierarchy().into()),
-                    wave.hierarchy().get(*var_ref),
-                );
-                if args.limit_scope_power.is_none() || all_signals_power.contains(&fname) {
-                    return (
-                        HashVarRef(*var_ref),
-                        stats::calc_stats_for_each_time_span(
-                            &wave,
-                            args.only_glitches,
-                            clk_signal,
-                            sig_ref,
-                            num_of_iterations,
-                        ),
-                    );
-                }
-                (
-                    HashVarRef(*var_ref),
-                    vec![stats::empty_stats(&wave, sig_ref); num_of_iterations as usize],
-                )
-            })
-            .collect();
-
-        let top_scope = args.top_scope.as_ref().map(|s| {
-            get_scope_by_full_name(wave.hierarchy(), s)
-                .unwrap_or_else(|| panic!("Couldn't find top scope `{}`", s))
-        });
-
-        Self {
-            wave,
-            clk_period,
-            stats,
-            num_of_iterations,
-            lookup_point,
-            output_fmt: args.output_format,
-            scope_prefix_length: lookup_scope_name_prefix.len(),
-            netlist: args.netlist.as_ref().map(|path| {
-                let f = fs::File::open(path).expect("Couldn't open the netlist file");
-                let reader = io::BufReader::new(f);
-                serde_json::from_reader::<_, Netlist>(reader)
-                    .expect("Couldn't parse the netlist file")
+        }
+    }
+
+    /// Export stats of a range of iterations (time windows) as a Parquet file
+    #[cfg(feature = "parquet")]
+    pub fn export_parquet(
+        &self,
+        out: impl io::Write + Send,
+        iterations: std::ops::Range<usize>,
+    ) -> Result<()> {
+        exporters::parquet::export(self, out, iterations)
+    }
+
+    /// Export stats of a single iteration (time window) using a custom exporter
+    pub fn export_with(
+        &self,
+        exporter: &mut dyn Exporter,
+        out: impl io::Write,
+        iteration: usize,
+    ) -> Result<()> {
+        exporters::plugin::export(self, exporter, out, iteration)
+    }
+
+    /// Export stats of all iterations in a given format. With more than one iteration `output`
+    /// is expected to be a directory, unless the format is a table (CSV/TSV/Parquet/power/net
+    /// power/domains/clocks) or a VCD profile, which holds all iterations in a single file. Writes to stdout if no `output` is given.
+    pub fn export_to_path(
+        &self,
+        format: OutputFormat,
+        output: Option<path::PathBuf>,
+    ) -> Result<()> {
+        let iterations = 0..self.num_of_iterations as usize;
+        match format {
+            OutputFormat::Csv => process_single_iteration_trace(output, |out, _| {
+                exporters::csv::export(self, out, iterations.clone(), ',')
+            }),
+            OutputFormat::Tsv => process_single_iteration_trace(output, |out, _| {
+                exporters::csv::export(self, out, iterations.clone(), '\t')
+            }),
+            OutputFormat::Vcd => process_single_iteration_trace(output, |out, _| {
+                exporters::profile::export(self, out, iterations.clone())
+            }),
+            OutputFormat::Power => process_single_iteration_trace(output, |out, _| {
+                exporters::power::export(self, out, iterations.clone())
+            }),
+            OutputFormat::NetPower => process_single_iteration_trace(output, |out, _| {
+                exporters::net_power::export(self, out, iterations.clone())
+            }),
+            OutputFormat::Domains => process_single_iteration_trace(output, |out, _| {
+                exporters::domains::export(self, out, iterations.clone())
             }),
-            top: args.top.clone().unwrap_or_else(String::new),
-            top_scope,
-            blackboxes_only: args.blackboxes_only,
-            remove_virtual_pins: args.remove_virtual_pins,
-            ignore_date: args.ignore_date,
-            ignore_version: args.ignore_version,
-            export_empty: args.export_empty,
-            power_scope_prefix: args
-                .limit_scope_power
-                .clone()
-                .unwrap_or_else(|| lookup_scope_name_prefix),
-            input_ports_activity: args.input_ports_activity,
+            OutputFormat::Clocks => process_single_iteration_trace(output, |out, _| {
+                exporters::clocks::export(self, out, iterations.clone())
+            }),
+            #[cfg(feature = "parquet")]
+            OutputFormat::Parquet => process_single_iteration_trace(output, |out, _| {
+                exporters::parquet::export(self, out, iterations.clone())
+            }),
+            _ => write_iterations(self, output, |out, iteration| {
+                self.export(format, out, iteration)
+            }),
+        }
+    }
+
+    /// Same as `export_to_path`, but uses a custom exporter
+    pub fn export_to_path_with(
+        &self,
+        exporter: &mut dyn Exporter,
+        output: Option<path::PathBuf>,
+    ) -> Result<()> {
+        write_iterations(self, output, |out, iteration| {
+            self.export_with(exporter, out, iteration)
+        })
+    }
+
+    /// Clock frequency (in Hz), either given by the user or measured in the trace
+    pub fn clk_freq(&self) -> f64 {
+        self.clk_freq
+    }
+
+    /// Kind of activity which is exported
+    pub fn activity(&self) -> Activity {
+        self.activity
+    }
+
+    /// Select the kind of activity to export. Apart from the kind the context was built with,
+    /// glitches are available if they were split from functional activity.
+    pub fn set_activity(&mut self, activity: Activity) -> Result<()> {
+        if activity == self.activity {
+            return Ok(());
+        }
+        match &mut self.stashed_stats {
+            Some((stashed, stats)) if *stashed == activity => {
+                std::mem::swap(&mut self.stats, stats);
+                *stashed = std::mem::replace(&mut self.activity, activity);
+                Ok(())
+            }
+            _ => Err(error::Error::ActivityNotAccumulated { activity }),
+        }
+    }
+
+    /// Problems found while loading the trace which may make the results inaccurate
+    pub fn warnings(&self) -> &[Warning] {
+        &self.warnings
+    }
+
+    /// Scope at which the netlist is rooted in the trace: the top scope if it's given, otherwise
+    /// the lookup scope. `None` stands for the top of the hierarchy.
+    pub(crate) fn netlist_root(&self) -> Option<ScopeRef> {
+        self.top_scope.or(match self.lookup_point {
+            LookupPoint::Scope(scope_ref) => Some(scope_ref),
+            LookupPoint::Top => None,
+        })
+    }
+
+    /// Whether activity of a net, given by its full name, is accumulated. Nets outside the power
+    /// scope carry empty stats.
+    pub(crate) fn in_power_scope(&self, name: &str) -> bool {
+        name.starts_with(&self.power_scope_prefix)
+    }
+
+    /// Describe a given iteration (time window)
+    pub fn window(&self, iteration: usize) -> WindowInfo {
+        let span = self.time_end / self.num_of_iterations;
+        let start = iteration as u64 * span;
+        let end = start + span;
+        WindowInfo {
+            index: iteration,
+            count: self.num_of_iterations as usize,
+            start,
+            end,
+            active: span - self.disabled_periods.overlap(start, end),
+            timescale: self.timescale,
+            clk_period: self.clk_period,
         }
     }
 }
 
-pub fn process(args: Args) {
-    let ctx = Context::build_from_args(&args);
-    if ctx.num_of_iterations > 1 {
-        process_trace_iterations(&ctx, args.output);
-    } else {
-        process_single_iteration_trace(&ctx, args.output);
+pub fn process(args: Args) -> Result<()> {
+    process_with(args, &ExporterRegistry::new())
+}
+
+/// Same as `process`, but `args.custom_format` can select one of the formats of `registry`
+pub fn process_with(args: Args, registry: &ExporterRegistry) -> Result<()> {
+    // An unknown format is reported before the trace is loaded
+    let mut exporter = args
+        .custom_format
+        .as_deref()
+        .map(|name| registry.create(name))
+        .transpose()?;
+    let mut ctx = ContextBuilder::from(&args).build()?;
+    for warning in ctx.warnings() {
+        eprintln!("Warning: {warning}");
     }
+    let mut export = |ctx: &Context, output| match exporter.as_mut() {
+        Some(exporter) => ctx.export_to_path_with(exporter.as_mut(), output),
+        None => ctx.export_to_path(args.output_format, output),
+    };
+    if !args.split_glitches {
+        return export(&ctx, args.output);
+    }
+
+    let output = args.output.ok_or(error::Error::MissingOutput)?;
+    for activity in [Activity::Functional, Activity::Glitches] {
+        ctx.set_activity(activity)?;
+        export(&ctx, Some(activity_path(&output, activity)))?;
+    }
+    Ok(())
+}
+
+/// Path of the output holding a given kind of activity, e.g. `out.glitches.saif` for `out.saif`
+fn activity_path(output: &path::Path, activity: Activity) -> path::PathBuf {
+    let mut name = output.file_stem().unwrap_or_default().to_os_string();
+    name.push(format!(".{activity}"));
+    if let Some(extension) = output.extension() {
+        name.push(".");
+        name.push(extension);
+    }
+    output.with_file_name(name)
 }
 
-fn process_trace(ctx: &Context, out: impl io::Write, iteration: usize) {
-    match &ctx.output_fmt {
-        OutputFormat::Tcl => exporters::tcl::export(&ctx, out, iteration),
-        OutputFormat::Saif => exporters::saif::export(&ctx, out, iteration),
+fn write_iterations<F>(ctx: &Context, output_path: Option<path::PathBuf>, export: F) -> Result<()>
+where
+    F: FnMut(&mut (dyn io::Write + Send), usize) -> Result<()>,
+{
+    if ctx.num_of_iterations > 1 {
+        process_trace_iterations(ctx, output_path, export)
+    } else {
+        process_single_iteration_trace(output_path, export)
     }
-    .expect("Output format should be either 'tcl' or 'saif'")
 }
 
-fn process_trace_iterations(ctx: &Context, output_path: Option<path::PathBuf>) {
+fn process_trace_iterations<F>(
+    ctx: &Context,
+    output_path: Option<path::PathBuf>,
+    mut export: F,
+) -> Result<()>
+where
+    F: FnMut(&mut (dyn io::Write + Send), usize) -> Result<()>,
+{
     if let Some(mut path) = output_path {
         // TODO: multithreading can also be introduced here to process each iteration in parallel
         for iteration in 0..ctx.num_of_iterations as usize {
             path.push(format!("{:05}", iteration));
-            let f = fs::File::create(&path).expect("Created file should be valid");
-            let writer = io::BufWriter::new(f);
-            process_trace(&ctx, writer, iteration);
+            let f = fs::File::create(&path).map_err(error::Error::io(&path))?;
+            let mut writer = io::BufWriter::new(f);
+            export(&mut writer, iteration)?;
             path.pop();
         }
     } else {
         for iteration in 0..ctx.num_of_iterations as usize {
             println!("{1} Iteration {:05} {1}", iteration, str::repeat("-", 10));
-            process_trace(&ctx, io::stdout(), iteration);
+            export(&mut io::stdout(), iteration)?;
         }
     }
+    Ok(())
 }
 
-fn process_single_iteration_trace(ctx: &Context, output_path: Option<path::PathBuf>) {
+fn process_single_iteration_trace<F>(
+    output_path: Option<path::PathBuf>,
+    mut export: F,
+) -> Result<()>
+where
+    F: FnMut(&mut (dyn io::Write + Send), usize) -> Result<()>,
+{
     match output_path {
-        None => process_trace(&ctx, io::stdout(), 0),
+        None => export(&mut io::stdout(), 0),
         Some(ref path) => {
-            let f = fs::File::create(path).expect("Created file should be valid");
-            let writer = io::BufWriter::new(f);
-            process_trace(&ctx, writer, 0);
+            let f = fs::File::create(path).map_err(error::Error::io(path))?;
+            let mut writer = io::BufWriter::new(f);
+            export(&mut writer, 0)
         }
     }
 }
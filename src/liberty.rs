@@ -0,0 +1,493 @@
+// Copyright (c) 2024-2026 Antmicro <www.antmicro.com>
+// SPDX-License-Identifier: Apache-2.0
+
+// Minimal reader of Liberty cell libraries, extracting the data needed to estimate power
+
+use std::collections::HashMap;
+use std::fmt;
+
+/// Error encountered while parsing a Liberty file
+#[derive(Debug, Clone)]
+pub struct ParseError {
+    /// Line at which the error was found
+    pub line: usize,
+    pub message: String,
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "line {}: {}", self.line, self.message)
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum PinDirection {
+    Input,
+    Output,
+    Inout,
+    Internal,
+}
+
+/// Pin of a library cell. All values are in SI units.
+#[derive(Debug, Clone)]
+pub struct LibertyPin {
+    pub direction: PinDirection,
+    /// Capacitance of the pin (in F)
+    pub capacitance: f64,
+    /// Internal energy dissipated when the pin rises (in J), averaged over all arcs and table
+    /// entries since slews and loads aren't known
+    pub rise_energy: f64,
+    /// Internal energy dissipated when the pin falls (in J), averaged like `rise_energy`
+    pub fall_energy: f64,
+}
+
+/// Cell of a library. All values are in SI units.
+#[derive(Debug, Clone)]
+pub struct LibertyCell {
+    /// Nominal supply voltage of the library (in V)
+    pub voltage: f64,
+    /// Leakage power of the cell (in W)
+    pub leakage_power: f64,
+    pub pins: HashMap<String, LibertyPin>,
+}
+
+/// Cells of one or more Liberty libraries
+#[derive(Debug, Clone, Default)]
+pub struct Liberty {
+    pub cells: HashMap<String, LibertyCell>,
+}
+
+impl Liberty {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Parse a Liberty library and add its cells, replacing cells of the same names
+    pub fn read(&mut self, source: &str) -> Result<(), ParseError> {
+        let mut parser = Parser {
+            tokens: tokenize(source)?,
+            position: 0,
+        };
+        while parser.peek().is_some() {
+            let group = parser.statement()?;
+            if let Statement::Group(group) = group {
+                if group.kind == "library" {
+                    self.read_library(&group)?;
+                }
+            }
+        }
+        Ok(())
+    }
+
+    pub fn cell(&self, name: &str) -> Option<&LibertyCell> {
+        self.cells.get(name)
+    }
+
+    fn read_library(&mut self, library: &Group) -> Result<(), ParseError> {
+        let missing = |attribute: &str| ParseError {
+            line: library.line,
+            message: format!("library has no `{attribute}`"),
+        };
+        let voltage_unit = match library.simple("voltage_unit") {
+            Some(unit) => parse_unit(unit, "V", library.line)?,
+            None => 1.0,
+        };
+        let leakage_unit = parse_unit(
+            library
+                .simple("leakage_power_unit")
+                .ok_or_else(|| missing("leakage_power_unit"))?,
+            "W",
+            library.line,
+        )?;
+        let capacitance_unit = match library.complex("capacitive_load_unit") {
+            Some([value, unit]) => {
+                parse_number(value, library.line)?
+                    * parse_unit(&format!("1{unit}"), "F", library.line)?
+            }
+            _ => return Err(missing("capacitive_load_unit")),
+        };
+        let voltage = parse_number(
+            library
+                .simple("nom_voltage")
+                .ok_or_else(|| missing("nom_voltage"))?,
+            library.line,
+        )? * voltage_unit;
+        // Internal power tables hold energy in units of capacitance times voltage squared
+        let energy_unit = capacitance_unit * voltage_unit * voltage_unit;
+
+        for cell in library.groups.iter().filter(|group| group.kind == "cell") {
+            let Some(name) = cell.args.first() else {
+                continue;
+            };
+            let leakage_power = match cell.simple("cell_leakage_power") {
+                Some(value) => parse_number(value, cell.line)?,
+                None => {
+                    let values = cell
+                        .groups
+                        .iter()
+                        .filter(|group| group.kind == "leakage_power")
+                        .filter_map(|group| group.simple("value"))
+                        .map(|value| parse_number(value, cell.line))
+                        .collect::<Result<Vec<_>, _>>()?;
+                    average(&values)
+                }
+            } * leakage_unit;
+
+            let mut pins = HashMap::new();
+            for pin in cell.groups.iter().filter(|group| group.kind == "pin") {
+                let direction = match pin.simple("direction") {
+                    Some("input") => PinDirection::Input,
+                    Some("output") => PinDirection::Output,
+                    Some("inout") => PinDirection::Inout,
+                    _ => PinDirection::Internal,
+                };
+                let capacitance = match pin.simple("capacitance") {
+                    Some(value) => parse_number(value, pin.line)?,
+                    None => {
+                        let values = ["rise_capacitance", "fall_capacitance"]
+                            .iter()
+                            .filter_map(|name| pin.simple(name))
+                            .map(|value| parse_number(value, pin.line))
+                            .collect::<Result<Vec<_>, _>>()?;
+                        average(&values)
+                    }
+                } * capacitance_unit;
+                let (mut rise, mut fall) = (Vec::new(), Vec::new());
+                for power in pin
+                    .groups
+                    .iter()
+                    .filter(|group| group.kind == "internal_power")
+                {
+                    for table in &power.groups {
+                        let value = table_average(table)?;
+                        match table.kind.as_str() {
+                            "rise_power" => rise.push(value),
+                            "fall_power" => fall.push(value),
+                            "power" => {
+                                rise.push(value);
+                                fall.push(value);
+                            }
+                            _ => {}
+                        }
+                    }
+                }
+                let liberty_pin = LibertyPin {
+                    direction,
+                    capacitance,
+                    rise_energy: average(&rise) * energy_unit,
+                    fall_energy: average(&fall) * energy_unit,
+                };
+                // A single group may describe several pins, e.g. `pin(A B)`
+                for name in pin.args.iter().flat_map(|arg| arg.split_whitespace()) {
+                    pins.insert(name.to_string(), liberty_pin.clone());
+                }
+            }
+
+            self.cells.insert(
+                name.clone(),
+                LibertyCell {
+                    voltage,
+                    leakage_power,
+                    pins,
+                },
+            );
+        }
+        Ok(())
+    }
+}
+
+fn average(values: &[f64]) -> f64 {
+    if values.is_empty() {
+        0.0
+    } else {
+        values.iter().sum::<f64>() / values.len() as f64
+    }
+}
+
+fn parse_number(value: &str, line: usize) -> Result<f64, ParseError> {
+    value.trim().parse().map_err(|_| ParseError {
+        line,
+        message: format!("`{value}` is not a number"),
+    })
+}
+
+/// Parse a quantity such as `1nW` into a multiple of `base` (e.g. `W`)
+fn parse_unit(value: &str, base: &str, line: usize) -> Result<f64, ParseError> {
+    let invalid = || ParseError {
+        line,
+        message: format!("`{value}` is not a valid unit of {base}"),
+    };
+    let lower = value.trim().to_lowercase();
+    let scaled = lower
+        .strip_suffix(&base.to_lowercase())
+        .ok_or_else(invalid)?;
+    let (number, prefix) = scaled.split_at(
+        scaled
+            .find(|c: char| c.is_ascii_alphabetic())
+            .unwrap_or(scaled.len()),
+    );
+    let factor = match prefix {
+        "" => 1.0,
+        "m" => 1e-3,
+        "u" => 1e-6,
+        "n" => 1e-9,
+        "p" => 1e-12,
+        "f" => 1e-15,
+        _ => return Err(invalid()),
+    };
+    let number = if number.is_empty() {
+        1.0
+    } else {
+        number.parse::<f64>().map_err(|_| invalid())?
+    };
+    Ok(number * factor)
+}
+
+/// Average of all entries of a lookup table, e.g. `rise_power(template) { values("1, 2"); }`
+fn table_average(table: &Group) -> Result<f64, ParseError> {
+    let values = table
+        .complex("values")
+        .unwrap_or_default()
+        .iter()
+        .flat_map(|row| row.split([',', ' ', '\t', '\n', '\r']))
+        .filter(|value| !value.is_empty())
+        .map(|value| parse_number(value, table.line))
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(average(&values))
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    /// Identifier, number or any other bare word
+    Word(String),
+    /// Contents of a quoted string
+    Str(String),
+    Punct(char),
+}
+
+fn tokenize(source: &str) -> Result<Vec<(Token, usize)>, ParseError> {
+    let mut tokens = Vec::new();
+    let mut chars = source.chars().peekable();
+    let mut line = 1;
+    while let Some(c) = chars.next() {
+        match c {
+            '\n' => line += 1,
+            c if c.is_whitespace() => {}
+            // Line continuation
+            '\\' => {}
+            '/' if chars.peek() == Some(&'*') => {
+                chars.next();
+                let mut prev = ' ';
+                loop {
+                    match chars.next() {
+                        Some('/') if prev == '*' => break,
+                        Some(c) => {
+                            if c == '\n' {
+                                line += 1;
+                            }
+                            prev = c;
+                        }
+                        None => {
+                            return Err(ParseError {
+                                line,
+                                message: "unterminated comment".into(),
+                            });
+                        }
+                    }
+                }
+            }
+            '/' if chars.peek() == Some(&'/') => while chars.next_if(|&c| c != '\n').is_some() {},
+            '"' => {
+                let start = line;
+                let mut value = String::new();
+                loop {
+                    match chars.next() {
+                        Some('"') => break,
+                        // Line continuation within a string
+                        Some('\\') if chars.peek() == Some(&'\n') => {}
+                        Some('\\') if chars.peek() == Some(&'\r') => {}
+                        Some(c) => {
+                            if c == '\n' {
+                                line += 1;
+                            }
+                            value.push(c);
+                        }
+                        None => {
+                            return Err(ParseError {
+                                line: start,
+                                message: "unterminated string".into(),
+                            });
+                        }
+                    }
+                }
+                tokens.push((Token::Str(value), start));
+            }
+            '(' | ')' | '{' | '}' | ':' | ';' | ',' => tokens.push((Token::Punct(c), line)),
+            c => {
+                let mut word = String::from(c);
+                while let Some(c) = chars.next_if(|&c| {
+                    !c.is_whitespace()
+                        && !matches!(c, '(' | ')' | '{' | '}' | ':' | ';' | ',' | '"')
+                }) {
+                    word.push(c);
+                }
+                tokens.push((Token::Word(word), line));
+            }
+        }
+    }
+    Ok(tokens)
+}
+
+#[derive(Debug)]
+struct Group {
+    kind: String,
+    args: Vec<String>,
+    line: usize,
+    simple: Vec<(String, String)>,
+    complex: Vec<(String, Vec<String>)>,
+    groups: Vec<Group>,
+}
+
+impl Group {
+    /// Value of a simple attribute, e.g. `direction : input;`
+    fn simple(&self, name: &str) -> Option<&str> {
+        self.simple
+            .iter()
+            .find(|(attribute, _)| attribute == name)
+            .map(|(_, value)| value.as_str())
+    }
+
+    /// Values of a complex attribute, e.g. `capacitive_load_unit (1, pf);`
+    fn complex(&self, name: &str) -> Option<&[String]> {
+        self.complex
+            .iter()
+            .find(|(attribute, _)| attribute == name)
+            .map(|(_, values)| values.as_slice())
+    }
+}
+
+enum Statement {
+    Simple(String, String),
+    Complex(String, Vec<String>),
+    Group(Group),
+}
+
+struct Parser {
+    tokens: Vec<(Token, usize)>,
+    position: usize,
+}
+
+impl Parser {
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.position).map(|(token, _)| token)
+    }
+
+    fn line(&self) -> usize {
+        self.tokens
+            .get(self.position)
+            .or(self.tokens.last())
+            .map_or(1, |(_, line)| *line)
+    }
+
+    fn next(&mut self) -> Option<Token> {
+        let token = self
+            .tokens
+            .get(self.position)
+            .map(|(token, _)| token.clone());
+        self.position += 1;
+        token
+    }
+
+    fn error(&self, message: impl Into<String>) -> ParseError {
+        ParseError {
+            line: self.line(),
+            message: message.into(),
+        }
+    }
+
+    fn skip(&mut self, punct: char) -> bool {
+        if self.peek() == Some(&Token::Punct(punct)) {
+            self.position += 1;
+            true
+        } else {
+            false
+        }
+    }
+
+    fn statement(&mut self) -> Result<Statement, ParseError> {
+        let line = self.line();
+        let name = match self.next() {
+            Some(Token::Word(name)) => name,
+            other => return Err(self.error(format!("expected a name, found {other:?}"))),
+        };
+        if self.skip(':') {
+            let mut values = Vec::new();
+            while let Some(token) = self.peek() {
+                match token {
+                    Token::Punct(';') => {
+                        self.position += 1;
+                        break;
+                    }
+                    // Some libraries omit the semicolon before the end of a group
+                    Token::Punct('}') => break,
+                    Token::Word(value) | Token::Str(value) => {
+                        values.push(value.clone());
+                        self.position += 1;
+                    }
+                    Token::Punct(c) => return Err(self.error(format!("unexpected `{c}`"))),
+                }
+            }
+            return Ok(Statement::Simple(name, values.join(" ")));
+        }
+        if !self.skip('(') {
+            return Err(self.error(format!("expected `:` or `(` after `{name}`")));
+        }
+        let mut args = Vec::new();
+        let mut arg: Option<String> = None;
+        loop {
+            match self.next() {
+                Some(Token::Punct(')')) => break,
+                Some(Token::Punct(',')) => args.extend(arg.take()),
+                Some(Token::Word(value) | Token::Str(value)) => match &mut arg {
+                    Some(arg) => {
+                        arg.push(' ');
+                        arg.push_str(&value);
+                    }
+                    None => arg = Some(value),
+                },
+                Some(Token::Punct(c)) => return Err(self.error(format!("unexpected `{c}`"))),
+                None => return Err(self.error(format!("unterminated arguments of `{name}`"))),
+            }
+        }
+        args.extend(arg);
+
+        if !self.skip('{') {
+            self.skip(';');
+            return Ok(Statement::Complex(name, args));
+        }
+        let mut group = Group {
+            kind: name,
+            args,
+            line,
+            simple: Vec::new(),
+            complex: Vec::new(),
+            groups: Vec::new(),
+        };
+        loop {
+            if self.skip('}') {
+                break;
+            }
+            if self.peek().is_none() {
+                return Err(self.error(format!("unterminated group `{}`", group.kind)));
+            }
+            match self.statement()? {
+                Statement::Simple(name, value) => group.simple.push((name, value)),
+                Statement::Complex(name, values) => group.complex.push((name, values)),
+                Statement::Group(child) => group.groups.push(child),
+            }
+        }
+        Ok(Statement::Group(group))
+    }
+}
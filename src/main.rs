@@ -1,10 +1,18 @@
 // Copyright (c) 2024-2026 Antmicro <www.antmicro.com>
 // SPDX-License-Identifier: Apache-2.0
 
+use std::process::ExitCode;
+
 use libtrace2power::Args;
 use libtrace2power::process;
 
-fn main() {
+fn main() -> ExitCode {
     let args = Args::from_cli();
-    process(args);
+    match process(args) {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(err) => {
+            eprintln!("Error: {err}");
+            ExitCode::FAILURE
+        }
+    }
 }
@@ -9,12 +9,39 @@ use std::collections::HashMap;
 #[derive(Debug, Deserialize)]
 pub struct Module {
     pub cells: HashMap<String, Cell>,
+    #[serde(default)]
+    pub attributes: HashMap<String, serde_json::Value>,
+    #[serde(default)]
+    pub netnames: HashMap<String, NetName>,
+    #[serde(default)]
+    pub ports: HashMap<String, Port>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct Port {
+    pub direction: String,
+    /// Bits of the port, starting from the least significant one
+    pub bits: Vec<serde_json::Value>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct NetName {
+    /// Bits of the net, starting from the least significant one
+    pub bits: Vec<serde_json::Value>,
 }
 
 #[derive(Debug, Deserialize)]
 pub struct Cell {
     #[serde(rename = "type")]
     pub type_name: String,
+    /// Bits connected to each port of the cell. Yosys identifies nets with integers and constant
+    /// bits with strings such as `"0"` or `"x"`.
+    #[serde(default)]
+    pub connections: HashMap<String, Vec<serde_json::Value>>,
+    /// Direction of each port (`input`, `output` or `inout`), given by Yosys for cells without a
+    /// module definition
+    #[serde(default)]
+    pub port_directions: HashMap<String, String>,
 }
 
 #[derive(Deserialize)]
@@ -22,6 +49,30 @@ pub struct Netlist {
     pub modules: HashMap<String, Module>,
 }
 
+impl Netlist {
+    /// Name of the module marked by Yosys as the top of the design
+    pub fn top_module(&self) -> Option<&str> {
+        self.modules
+            .iter()
+            .find(|(_, module)| module.is_top())
+            .map(|(name, _)| name.as_str())
+    }
+
+    /// Type of the cell at a path of instance names (separated with `.`) below a top module
+    pub fn cell_type(&self, top: &str, path: &str) -> Option<&str> {
+        let mut module = self.modules.get(top)?;
+        let mut cells = path.split('.').peekable();
+        while let Some(cell_name) = cells.next() {
+            let cell = module.cells.get(cell_name)?;
+            if cells.peek().is_none() {
+                return Some(&cell.type_name);
+            }
+            module = cell.get_module(self)?;
+        }
+        None
+    }
+}
+
 impl Cell {
     pub fn get_module<'n>(&self, netlist: &'n Netlist) -> Option<&'n Module> {
         netlist.modules.get(&self.type_name)
@@ -35,6 +86,16 @@ pub enum ModuleLookupError {
 }
 
 impl Module {
+    /// Whether the module has a non-zero `top` attribute. Yosys stores integer attributes as
+    /// strings of binary digits.
+    pub fn is_top(&self) -> bool {
+        match self.attributes.get("top") {
+            Some(serde_json::Value::String(value)) => value.contains('1'),
+            Some(serde_json::Value::Number(value)) => value.as_u64().is_some_and(|v| v != 0),
+            _ => false,
+        }
+    }
+
     pub fn get_module_of_cell<'s>(
         &self,
         netlist: &'s Netlist,
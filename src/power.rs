@@ -0,0 +1,398 @@
+// Copyright (c) 2024-2026 Antmicro <www.antmicro.com>
+// SPDX-License-Identifier: Apache-2.0
+
+// Estimation of power dissipated by instances of library cells, based on their activity
+
+use std::collections::HashMap;
+
+use wellen::{GetItem, Hierarchy, VarRef};
+
+use crate::error::{Error, Result};
+use crate::liberty::{Liberty, LibertyCell, PinDirection};
+use crate::netlist::{Cell, Module, Netlist};
+use crate::spef::Spef;
+use crate::stats::SignalStats;
+use crate::util::ratio;
+use crate::{Context, HashVarRef};
+
+/// Power dissipated by a single instance of a library cell within a time window (in W)
+#[derive(Debug, Clone)]
+pub struct InstancePower {
+    /// Full hierarchical name of the instance, with `.` as a divider
+    pub instance: String,
+    /// Name of the library cell, or the module given in an energy table
+    pub cell: String,
+    /// Power dissipated within the cell when its pins toggle
+    pub internal: f64,
+    /// Power spent on charging the capacitance driven by outputs of the cell
+    pub switching: f64,
+    pub leakage: f64,
+}
+
+impl InstancePower {
+    pub fn total(&self) -> f64 {
+        self.internal + self.switching + self.leakage
+    }
+}
+
+/// Switching power of a net described in a SPEF file within a time window
+#[derive(Debug, Clone)]
+pub struct NetPower {
+    /// Full hierarchical name of the net, with `.` as a divider
+    pub name: String,
+    /// Total capacitance of the net (in F)
+    pub capacitance: f64,
+    /// Number of rises and falls of the net, `None` if the net wasn't found in the trace
+    pub toggles: Option<u32>,
+    /// Power spent on charging the capacitance of the net (in W)
+    pub switching: f64,
+}
+
+impl NetPower {
+    /// Full hierarchical name of the scope containing the net
+    pub fn scope(&self) -> &str {
+        self.name.rsplit_once('.').map_or("", |(scope, _)| scope)
+    }
+}
+
+/// Net of the SPEF file, resolved in the trace once and looked up by estimates of each window
+pub(crate) struct SpefTraceNet {
+    /// Full hierarchical name of the net, with `.` as a divider
+    name: String,
+    /// Total capacitance of the net (in F)
+    capacitance: f64,
+    /// Variable holding the net and the position of its bit within its stats, `None` if the net
+    /// wasn't found in the trace
+    var: Option<(VarRef, usize)>,
+}
+
+/// Find a bit of a net in a scope of the trace. Returns the variable and the position of the bit
+/// within its stats.
+fn find_trace_bit(
+    hier: &Hierarchy,
+    scope: &[String],
+    name: &str,
+    bit: Option<i64>,
+) -> Option<(VarRef, usize)> {
+    let scope = hier.get(hier.lookup_scope(scope)?);
+    scope.vars(hier).find_map(|var_ref| {
+        let var = hier.get(var_ref);
+        let var_name = var.name(hier);
+        if var_name.strip_prefix('\\').unwrap_or(var_name) != name {
+            return None;
+        }
+        match (bit, var.index()) {
+            (None, None) => Some((var_ref, 0)),
+            (None, Some(index)) if index.msb() == index.lsb() => Some((var_ref, 0)),
+            (Some(bit), Some(index)) => {
+                let (low, high) = (index.lsb().min(index.msb()), index.lsb().max(index.msb()));
+                (low..=high)
+                    .contains(&bit)
+                    .then(|| (var_ref, (bit - index.lsb()).unsigned_abs() as usize))
+            }
+            _ => None,
+        }
+    })
+}
+
+/// Yosys identifier of a net bit, `None` for constants
+fn net_bit(value: &serde_json::Value) -> Option<u64> {
+    value.as_u64()
+}
+
+/// Capacitance of library cell inputs connected to each net bit of a module. Loads located in
+/// other modules are not taken into account.
+fn pin_loads(liberty: &Liberty, module: &Module) -> HashMap<u64, f64> {
+    let mut loads = HashMap::new();
+    for cell in module.cells.values() {
+        let Some(lib_cell) = liberty.cell(&cell.type_name) else {
+            continue;
+        };
+        for (port, bits) in &cell.connections {
+            let Some(pin) = lib_cell.pins.get(port) else {
+                continue;
+            };
+            if let PinDirection::Input | PinDirection::Inout = pin.direction {
+                for bit in bits.iter().filter_map(net_bit) {
+                    *loads.entry(bit).or_default() += pin.capacitance;
+                }
+            }
+        }
+    }
+    loads
+}
+
+struct Estimator<'c> {
+    ctx: &'c Context,
+    netlist: &'c Netlist,
+    liberty: &'c Liberty,
+    iteration: usize,
+    /// Duration of the window (in seconds)
+    duration_s: f64,
+    instances: Vec<InstancePower>,
+}
+
+impl Estimator<'_> {
+    /// Stats of a pin of an instance, summed up over its bits
+    fn pin_stats(&self, path: &[String], pin: &String) -> SignalStats {
+        let hier = self.ctx.wave.hierarchy();
+        let mut totals = SignalStats::default();
+        let stats = hier
+            .lookup_var(path, pin)
+            .and_then(|var_ref| self.ctx.stats.get(&HashVarRef(var_ref)));
+        if let Some(stats) = stats {
+            for stat in stats[self.iteration].bits() {
+                totals.accumulate(stat);
+            }
+        }
+        totals
+    }
+
+    fn estimate(
+        &self,
+        path: &[String],
+        cell: &Cell,
+        lib_cell: &LibertyCell,
+        loads: &HashMap<u64, f64>,
+    ) -> InstancePower {
+        let mut pins: Vec<_> = lib_cell.pins.iter().collect();
+        pins.sort_by_key(|(name, _)| *name);
+
+        let (mut internal, mut switching) = (0.0, 0.0);
+        for (name, pin) in pins {
+            let stats = self.pin_stats(path, name);
+            internal += stats.rise_count as f64 * pin.rise_energy
+                + stats.fall_count as f64 * pin.fall_energy;
+            if pin.direction == PinDirection::Output {
+                let load = cell
+                    .connections
+                    .get(name)
+                    .into_iter()
+                    .flatten()
+                    .filter_map(net_bit)
+                    .map(|bit| loads.get(&bit).copied().unwrap_or_default())
+                    .sum::<f64>()
+                    + self.ctx.wire_capacitance;
+                let toggles = (stats.rise_count + stats.fall_count) as f64;
+                switching += 0.5 * load * lib_cell.voltage * lib_cell.voltage * toggles;
+            }
+        }
+
+        InstancePower {
+            instance: path.join("."),
+            cell: cell.type_name.clone(),
+            internal: ratio(internal, self.duration_s),
+            switching: ratio(switching, self.duration_s),
+            leakage: lib_cell.leakage_power,
+        }
+    }
+
+    fn visit_module(&mut self, name: &str, module: &Module, path: &mut Vec<String>) {
+        let no_loads = HashMap::new();
+        let loads = self.ctx.module_loads.get(name).unwrap_or(&no_loads);
+        let mut cells: Vec<_> = module.cells.iter().collect();
+        cells.sort_by_key(|(name, _)| *name);
+
+        for (name, cell) in cells {
+            path.push(name.clone());
+            if let Some(lib_cell) = self.liberty.cell(&cell.type_name) {
+                let instance = path.join(".") + ".";
+                if instance.contains(&self.ctx.power_scope_prefix) {
+                    let power = self.estimate(path, cell, lib_cell, loads);
+                    self.instances.push(power);
+                }
+            } else if let Some(module) = cell.get_module(self.netlist) {
+                self.visit_module(&cell.type_name, module, path);
+            }
+            path.pop();
+        }
+    }
+}
+
+impl Context {
+    /// Names of the scopes at which the netlist is rooted in the trace
+    fn netlist_root_path(&self) -> Vec<String> {
+        let hier = self.wave.hierarchy();
+        self.netlist_root()
+            .map(|scope_ref| hier.get(scope_ref).full_name(hier))
+            .map(|name| name.split('.').map(ToString::to_string).collect())
+            .unwrap_or_default()
+    }
+
+    /// Find the trace variable holding a net of the SPEF file, given the names of instances above
+    /// it and its local name. Nets missing from the trace are looked up through the netlist, which
+    /// gives other names of the same net bit and pins of cells connected to it.
+    fn find_spef_net(
+        &self,
+        spef: &Spef,
+        instances: &[String],
+        net: &str,
+    ) -> Option<(VarRef, usize)> {
+        let hier = self.wave.hierarchy();
+        let mut scope = self.netlist_root_path();
+        scope.extend(instances.iter().cloned());
+
+        let bus_bit = spef.bus_bit(net);
+        let found = find_trace_bit(hier, &scope, net, None).or_else(|| {
+            let (base, bit) = bus_bit.clone()?;
+            find_trace_bit(hier, &scope, &base, Some(bit))
+        });
+        if found.is_some() {
+            return found;
+        }
+
+        let netlist = self.netlist.as_ref()?;
+        let mut module = netlist.modules.get(&self.top)?;
+        for instance in instances {
+            module = module.cells.get(instance)?.get_module(netlist)?;
+        }
+        let bit_id = match (module.netnames.get(net), &bus_bit) {
+            (Some(netname), _) if netname.bits.len() == 1 => net_bit(&netname.bits[0]),
+            (_, Some((base, bit))) => net_bit(module.netnames.get(base)?.bits.get(*bit as usize)?),
+            _ => None,
+        }?;
+        let position_of =
+            |bits: &[serde_json::Value]| bits.iter().position(|bit| net_bit(bit) == Some(bit_id));
+
+        let mut aliases: Vec<_> = module.netnames.iter().collect();
+        aliases.sort_by_key(|(name, _)| *name);
+        for (name, netname) in aliases {
+            let Some(position) = position_of(&netname.bits) else {
+                continue;
+            };
+            let bit = (netname.bits.len() > 1).then_some(position as i64);
+            if let Some(found) = find_trace_bit(hier, &scope, name, bit) {
+                return Some(found);
+            }
+        }
+
+        let mut cells: Vec<_> = module.cells.iter().collect();
+        cells.sort_by_key(|(name, _)| *name);
+        for (cell_name, cell) in cells {
+            for (port, bits) in &cell.connections {
+                let Some(position) = position_of(bits) else {
+                    continue;
+                };
+                let mut cell_scope = scope.clone();
+                cell_scope.push(cell_name.clone());
+                let bit = (bits.len() > 1).then_some(position as i64);
+                if let Some(found) = find_trace_bit(hier, &cell_scope, port, bit) {
+                    return Some(found);
+                }
+            }
+        }
+        None
+    }
+
+    /// Resolve nets of the SPEF file in the trace and find loads of net bits of each module of the
+    /// netlist, so that power estimates of each window only look them up
+    pub(crate) fn resolve_power_nets(&mut self) {
+        if let Some(spef) = &self.spef {
+            let root = self.netlist_root_path();
+            let mut nets: Vec<_> = spef
+                .nets
+                .iter()
+                .map(|net| {
+                    let mut parts = spef.split_name(&net.name);
+                    let local = parts.pop().unwrap_or_default();
+                    let var = self.find_spef_net(spef, &parts, &local);
+                    let name = root
+                        .iter()
+                        .chain(&parts)
+                        .chain([&local])
+                        .cloned()
+                        .collect::<Vec<_>>()
+                        .join(".");
+                    SpefTraceNet {
+                        name,
+                        capacitance: net.capacitance,
+                        var,
+                    }
+                })
+                .collect();
+            nets.sort_by(|a, b| a.name.cmp(&b.name));
+            self.spef_nets = nets;
+        }
+        if let (Some(liberty), Some(netlist)) = (&self.liberty, &self.netlist) {
+            self.module_loads = netlist
+                .modules
+                .iter()
+                .map(|(name, module)| (name.clone(), pin_loads(liberty, module)))
+                .collect();
+        }
+    }
+
+    /// Compute switching power of each net of the SPEF file within a given time window, using its
+    /// total capacitance and the number of times it rose or fell
+    pub fn net_power(&self, iteration: usize) -> Result<Vec<NetPower>> {
+        self.spef.as_ref().ok_or(Error::MissingSpef)?;
+        let voltage = self
+            .supply_voltage
+            .or_else(|| {
+                let cells = self.liberty.as_ref()?.cells.values();
+                cells.map(|cell| cell.voltage).reduce(f64::max)
+            })
+            .ok_or(Error::MissingSupplyVoltage)?;
+        let window = self.window(iteration);
+        let duration_s = window.active as f64 * self.timescale_norm;
+
+        let nets = self
+            .spef_nets
+            .iter()
+            .map(|net| {
+                let toggles = net.var.and_then(|(var_ref, position)| {
+                    let stats = self.stats.get(&HashVarRef(var_ref))?;
+                    let stat = stats[iteration].bits().get(position)?;
+                    Some(stat.rise_count + stat.fall_count)
+                });
+                NetPower {
+                    name: net.name.clone(),
+                    capacitance: net.capacitance,
+                    toggles,
+                    switching: ratio(
+                        0.5 * net.capacitance
+                            * voltage
+                            * voltage
+                            * toggles.unwrap_or_default() as f64,
+                        duration_s,
+                    ),
+                }
+            })
+            .collect();
+        Ok(nets)
+    }
+
+    /// Estimate power dissipated by each instance of a library cell in the netlist within a given
+    /// time window. Internal energy of each pin toggle is averaged over the Liberty tables, and
+    /// the load of each output is the capacitance of the inputs it drives plus the wire
+    /// capacitance, so the results are suited for comparing windows and instances rather than
+    /// for signoff. If an energy table is given, it's used instead of Liberty libraries: each
+    /// scope instantiating a cell or module of the table gets the energy of its pin transitions
+    /// and its leakage.
+    pub fn power(&self, iteration: usize) -> Result<Vec<InstancePower>> {
+        if let Some(table) = &self.energy_table {
+            return crate::exporters::power::energy_table_power(self, table, iteration);
+        }
+        let liberty = self.liberty.as_ref().ok_or(Error::MissingLiberty)?;
+        let netlist = self.netlist.as_ref().ok_or(Error::MissingNetlist)?;
+        let top = netlist
+            .modules
+            .get(&self.top)
+            .ok_or_else(|| Error::TopModuleNotFound {
+                module: self.top.clone(),
+            })?;
+
+        let mut path = self.netlist_root_path();
+        let window = self.window(iteration);
+        let mut estimator = Estimator {
+            ctx: self,
+            netlist,
+            liberty,
+            iteration,
+            duration_s: window.active as f64 * self.timescale_norm,
+            instances: Vec::new(),
+        };
+        estimator.visit_module(&self.top, top, &mut path);
+        Ok(estimator.instances)
+    }
+}
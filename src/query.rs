@@ -0,0 +1,187 @@
+// Copyright (c) 2024-2026 Antmicro <www.antmicro.com>
+// SPDX-License-Identifier: Apache-2.0
+
+// Read-only queries over the activity accumulated in a `Context`
+
+use std::collections::BTreeMap;
+
+use wellen::{GetItem, Scope, ScopeRef, VarRef};
+
+use crate::stats::{PackedStats, SignalStats};
+use crate::util::glob_match;
+use crate::{Context, HashVarRef, get_scope_by_full_name};
+
+/// Activity of a single net across all time windows
+#[derive(Debug, Clone)]
+pub struct NetActivity<'c> {
+    name: String,
+    var_ref: VarRef,
+    windows: &'c [PackedStats],
+}
+
+impl<'c> NetActivity<'c> {
+    /// Full hierarchical name of the net, with `.` as a divider
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Full hierarchical name of the scope containing the net
+    pub fn scope(&self) -> &str {
+        self.name.rsplit_once('.').map_or("", |(scope, _)| scope)
+    }
+
+    pub fn var_ref(&self) -> VarRef {
+        self.var_ref
+    }
+
+    /// Stats of the net for each time window
+    pub fn windows(&self) -> &'c [PackedStats] {
+        self.windows
+    }
+
+    /// Stats of the net in a given time window
+    pub fn window(&self, iteration: usize) -> Option<&'c PackedStats> {
+        self.windows.get(iteration)
+    }
+
+    /// Stats of all bits of the net summed up for a given time window
+    pub fn totals(&self, iteration: usize) -> Option<SignalStats> {
+        self.window(iteration).map(|stats| {
+            let mut totals = SignalStats::default();
+            for stat in stats.bits() {
+                totals.accumulate(stat);
+            }
+            totals
+        })
+    }
+}
+
+/// Activity accumulated over all instances of a module
+#[derive(Debug, Clone, Default)]
+pub struct ModuleActivity {
+    pub instances: usize,
+    pub nets: usize,
+    pub stats: SignalStats,
+}
+
+impl Context {
+    fn net_activity(&self, name: &str, var_ref: VarRef) -> NetActivity<'_> {
+        NetActivity {
+            name: name.to_string(),
+            var_ref,
+            windows: &self.stats[&HashVarRef(var_ref)],
+        }
+    }
+
+    /// All nets with computed stats, sorted by their names
+    pub fn nets(&self) -> Vec<NetActivity<'_>> {
+        self.net_names
+            .iter()
+            .map(|(name, var_ref)| self.net_activity(name, *var_ref))
+            .collect()
+    }
+
+    /// Look up a net by its full hierarchical name
+    pub fn net(&self, name: &str) -> Option<NetActivity<'_>> {
+        let index = self
+            .net_names
+            .binary_search_by(|(net, _)| net.as_str().cmp(name))
+            .ok()?;
+        let (name, var_ref) = &self.net_names[index];
+        Some(self.net_activity(name, *var_ref))
+    }
+
+    /// Nets whose full hierarchical names match a glob pattern (see [`crate::util::glob_match`])
+    pub fn nets_matching(&self, pattern: &str) -> Vec<NetActivity<'_>> {
+        self.net_names
+            .iter()
+            .filter(|(name, _)| glob_match(pattern, name))
+            .map(|(name, var_ref)| self.net_activity(name, *var_ref))
+            .collect()
+    }
+
+    /// Nets located anywhere under a given scope
+    pub fn nets_under(&self, scope: &str) -> Vec<NetActivity<'_>> {
+        let prefix = format!("{scope}.");
+        // Names under the scope form a contiguous range of the sorted names
+        let start = self
+            .net_names
+            .partition_point(|(name, _)| name.as_str() < prefix.as_str());
+        self.net_names[start..]
+            .iter()
+            .take_while(|(name, _)| name.starts_with(&prefix))
+            .map(|(name, var_ref)| self.net_activity(name, *var_ref))
+            .collect()
+    }
+
+    /// Stats of all nets under a given scope summed up for a given time window
+    pub fn scope_totals(&self, scope: &str, iteration: usize) -> SignalStats {
+        let mut totals = SignalStats::default();
+        for net in self.nets_under(scope) {
+            if let Some(stats) = net.totals(iteration) {
+                totals.accumulate(&stats);
+            }
+        }
+        totals
+    }
+
+    /// Stats of nets summed up for each module (definition) for a given time window. Only nets
+    /// located directly in an instance are accounted for its module.
+    pub fn module_totals(&self, iteration: usize) -> BTreeMap<String, ModuleActivity> {
+        let hier = self.wave.hierarchy();
+        let mut scopes: BTreeMap<String, Vec<NetActivity>> = BTreeMap::new();
+        for net in self.nets() {
+            scopes.entry(net.scope().to_string()).or_default().push(net);
+        }
+
+        let mut modules: BTreeMap<String, ModuleActivity> = BTreeMap::new();
+        for (scope, nets) in scopes {
+            let Some(scope_ref) = get_scope_by_full_name(hier, &scope) else {
+                continue;
+            };
+            let Some(module_name) = self.module_name(scope_ref) else {
+                continue;
+            };
+            let module = modules.entry(module_name).or_default();
+            module.instances += 1;
+            for net in nets {
+                module.nets += 1;
+                if let Some(stats) = net.totals(iteration) {
+                    module.stats.accumulate(&stats);
+                }
+            }
+        }
+        modules
+    }
+
+    /// Name of the module instantiated by a scope. Taken from the trace if it records one,
+    /// otherwise from the type of the corresponding netlist cell.
+    pub fn module_name(&self, scope_ref: ScopeRef) -> Option<String> {
+        self.scope_module_name(self.wave.hierarchy().get(scope_ref))
+    }
+
+    pub(crate) fn scope_module_name(&self, scope: &Scope) -> Option<String> {
+        let hier = self.wave.hierarchy();
+        if let Some(component) = scope.component(hier) {
+            return Some(component.to_string());
+        }
+        self.netlist_module_name(scope)
+    }
+
+    fn netlist_module_name(&self, scope: &Scope) -> Option<String> {
+        let hier = self.wave.hierarchy();
+        let netlist = self.netlist.as_ref()?;
+        let full_name = scope.full_name(hier);
+        let path = match self.netlist_root() {
+            Some(root) => {
+                let root_name = hier.get(root).full_name(hier);
+                if full_name == root_name {
+                    return Some(self.top.clone());
+                }
+                full_name.strip_prefix(&format!("{root_name}."))?
+            }
+            None => &full_name,
+        };
+        netlist.cell_type(&self.top, path).map(String::from)
+    }
+}
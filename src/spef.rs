@@ -0,0 +1,171 @@
+// Copyright (c) 2024-2026 Antmicro <www.antmicro.com>
+// SPDX-License-Identifier: Apache-2.0
+
+// Minimal reader of Standard Parasitic Exchange Format (IEEE 1481) files, extracting total
+// capacitance of each net
+
+use std::collections::HashMap;
+use std::fmt;
+
+/// Error encountered while parsing a SPEF file
+#[derive(Debug, Clone)]
+pub struct ParseError {
+    /// Line at which the error was found
+    pub line: usize,
+    pub message: String,
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "line {}: {}", self.line, self.message)
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+/// Net described in a SPEF file
+#[derive(Debug, Clone)]
+pub struct SpefNet {
+    /// Hierarchical name of the net, with name map references resolved
+    pub name: String,
+    /// Total capacitance of the net (in F)
+    pub capacitance: f64,
+}
+
+/// Parasitics of a design
+#[derive(Debug, Clone)]
+pub struct Spef {
+    /// Divider of hierarchical names
+    pub divider: char,
+    /// Opening and closing brackets of bus bits
+    pub bus_delimiters: (char, char),
+    pub nets: Vec<SpefNet>,
+}
+
+impl Default for Spef {
+    fn default() -> Self {
+        Self {
+            divider: '/',
+            bus_delimiters: ('[', ']'),
+            nets: Vec::new(),
+        }
+    }
+}
+
+/// Value of a parameter, which can be a single number or a `min:typ:max` triplet
+fn parse_value(value: &str, line: usize) -> Result<f64, ParseError> {
+    let typical = match value.split(':').collect::<Vec<_>>().as_slice() {
+        [_, typical, _] => *typical,
+        _ => value,
+    };
+    typical.parse().map_err(|_| ParseError {
+        line,
+        message: format!("`{value}` is not a number"),
+    })
+}
+
+fn parse_capacitance_unit(value: &str, unit: &str, line: usize) -> Result<f64, ParseError> {
+    let factor = match unit.to_uppercase().as_str() {
+        "F" => 1.0,
+        "MF" => 1e-3,
+        "UF" => 1e-6,
+        "NF" => 1e-9,
+        "PF" => 1e-12,
+        "FF" => 1e-15,
+        _ => {
+            return Err(ParseError {
+                line,
+                message: format!("`{unit}` is not a unit of capacitance"),
+            });
+        }
+    };
+    Ok(parse_value(value, line)? * factor)
+}
+
+impl Spef {
+    /// Parse a SPEF file
+    pub fn parse(source: &str) -> Result<Self, ParseError> {
+        let mut spef = Self::default();
+        let mut capacitance_unit = 1e-12;
+        let mut name_map: HashMap<String, String> = HashMap::new();
+        let mut in_name_map = false;
+
+        for (index, line) in source.lines().enumerate() {
+            let line_number = index + 1;
+            let line = line.split("//").next().unwrap_or_default();
+            let fields: Vec<_> = line.split_whitespace().collect();
+            let Some(keyword) = fields.first() else {
+                continue;
+            };
+            let field = |position: usize| {
+                fields.get(position).copied().ok_or_else(|| ParseError {
+                    line: line_number,
+                    message: format!("`{keyword}` is missing a value"),
+                })
+            };
+
+            // Entries of the name map look like `*12 name`, while sections look like `*PORTS`
+            let is_reference = keyword.len() > 1
+                && keyword.starts_with('*')
+                && keyword[1..].chars().all(|c| c.is_ascii_digit());
+            if in_name_map && is_reference {
+                name_map.insert(keyword.to_string(), field(1)?.to_string());
+                continue;
+            }
+            in_name_map = false;
+
+            match *keyword {
+                "*DIVIDER" => spef.divider = field(1)?.chars().next().unwrap_or('/'),
+                "*BUS_DELIMITER" => {
+                    let open = field(1)?.chars().next().unwrap_or('[');
+                    let close = fields
+                        .get(2)
+                        .and_then(|close| close.chars().next())
+                        .unwrap_or(match open {
+                            '(' => ')',
+                            '<' => '>',
+                            '{' => '}',
+                            _ => ']',
+                        });
+                    spef.bus_delimiters = (open, close);
+                }
+                "*C_UNIT" => {
+                    capacitance_unit = parse_capacitance_unit(field(1)?, field(2)?, line_number)?
+                }
+                "*NAME_MAP" => in_name_map = true,
+                "*D_NET" | "*R_NET" => {
+                    let name = field(1)?;
+                    let name = name_map.get(name).map_or(name, String::as_str);
+                    spef.nets.push(SpefNet {
+                        name: name.to_string(),
+                        capacitance: parse_value(field(2)?, line_number)? * capacitance_unit,
+                    });
+                }
+                _ => {}
+            }
+        }
+        Ok(spef)
+    }
+
+    /// Split a hierarchical name into names of instances and the name of the net, removing
+    /// escape characters
+    pub fn split_name(&self, name: &str) -> Vec<String> {
+        let mut parts = vec![String::new()];
+        let mut chars = name.chars();
+        while let Some(c) = chars.next() {
+            match c {
+                '\\' => parts.last_mut().unwrap().extend(chars.next()),
+                c if c == self.divider => parts.push(String::new()),
+                c => parts.last_mut().unwrap().push(c),
+            }
+        }
+        parts
+    }
+
+    /// Split a net name into its base name and the index of a bus bit, if it has one
+    pub fn bus_bit(&self, name: &str) -> Option<(String, i64)> {
+        let (open, close) = self.bus_delimiters;
+        let (base, index) = name.strip_suffix(close)?.rsplit_once(open)?;
+        Some((base.to_string(), index.parse().ok()?))
+    }
+}
@@ -3,10 +3,10 @@
 
 use itertools::izip;
 use rayon::prelude::*;
-use std::fmt::Debug;
+use std::fmt::{self, Debug};
 use wellen::{Signal, SignalRef, SignalValue, TimeTableIdx, simple::Waveform};
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Default)]
 pub struct SignalStats {
     //pub name: String,
     pub trans_count_doubled: u32,
@@ -16,24 +16,68 @@ pub struct SignalStats {
     pub low_time: u32,
     pub x_time: u32,
     pub z_time: u32,
+    /// Number of glitches through X which return to the value held before the glitch
+    /// (0 -> X -> 0 and 1 -> X -> 1)
+    pub interconnect_glitch_count: u32,
+    /// Time spent in X entered from or left to Z (e.g. Z -> X -> 1 or 0 -> X -> Z), which is
+    /// caused by multiple drivers of a tristate net driving it at once. X held since the beginning
+    /// of the trace isn't counted.
+    pub bus_contention_time: u32,
+    /// Number of transitions from 0 to 1, including ones passing through X or Z (e.g. 0 -> Z -> 1)
+    pub rise_count: u32,
+    /// Number of transitions from 1 to 0, including ones passing through X or Z (e.g. 1 -> X -> 0)
+    pub fall_count: u32,
+    /// Number of pulses narrower than the inertial delay of the net. Their transitions aren't
+    /// counted, unless narrow pulses are only meant to be counted.
+    pub narrow_pulse_count: u32,
 }
 
-impl Default for SignalStats {
-    fn default() -> Self {
-        Self {
-            trans_count_doubled: 0,
-            clean_trans_count: 0,
-            glitch_trans_count: 0,
-            high_time: 0,
-            low_time: 0,
-            x_time: 0,
-            z_time: 0,
+/// Errors encountered while accumulating statistics of a single signal
+#[derive(Debug, Clone)]
+pub enum StatsError {
+    /// Signal hasn't been loaded from the trace
+    SignalNotLoaded,
+    /// No value is recorded for the signal at a time stamp it reports a change at
+    MissingValue { time: wellen::Time },
+    /// Signal holds a value which is not a bit vector
+    NotABitVector { time: wellen::Time },
+    /// Signal holds a bit that is neither of `0`, `1`, `x` or `z`
+    InvalidValue { time: wellen::Time, value: char },
+    /// Transition between two bit values can't be classified
+    UnknownTransition {
+        time: wellen::Time,
+        from: char,
+        to: char,
+    },
+}
+
+impl fmt::Display for StatsError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::SignalNotLoaded => write!(f, "signal was not loaded from the trace"),
+            Self::MissingValue { time } => write!(f, "no value recorded at time {time}"),
+            Self::NotABitVector { time } => {
+                write!(f, "value at time {time} is not a bit vector")
+            }
+            Self::InvalidValue { time, value } => {
+                write!(f, "invalid value `{value}` at time {time}")
+            }
+            Self::UnknownTransition { time, from, to } => {
+                write!(f, "unknown transition {from} -> {to} at time {time}")
+            }
         }
     }
 }
 
+impl std::error::Error for StatsError {}
+
 impl SignalStats {
-    fn modify_time_stat_of_value<'s, F>(&'s mut self, val: char, f: F)
+    fn modify_time_stat_of_value<F>(
+        &mut self,
+        val: char,
+        time: wellen::Time,
+        f: F,
+    ) -> Result<(), StatsError>
     where
         F: FnOnce(u32) -> u32,
     {
@@ -42,105 +86,510 @@ impl SignalStats {
             '0' => self.low_time = f(self.low_time),
             'x' => self.x_time = f(self.x_time),
             'z' => self.z_time = f(self.z_time),
-            _ => panic!("Invalid value"),
+            value => return Err(StatsError::InvalidValue { time, value }),
         }
+        Ok(())
     }
 }
 
 impl SignalStats {
-    fn is_glitch<'s>(&'s mut self) -> bool {
-        self.clean_trans_count >= 2 || self.glitch_trans_count >= 2 || self.trans_count_doubled > 2
+    /// Add stats of another signal to these ones
+    pub fn accumulate(&mut self, other: &SignalStats) {
+        self.trans_count_doubled += other.trans_count_doubled;
+        self.clean_trans_count += other.clean_trans_count;
+        self.glitch_trans_count += other.glitch_trans_count;
+        self.high_time += other.high_time;
+        self.low_time += other.low_time;
+        self.x_time += other.x_time;
+        self.z_time += other.z_time;
+        self.interconnect_glitch_count += other.interconnect_glitch_count;
+        self.bus_contention_time += other.bus_contention_time;
+        self.rise_count += other.rise_count;
+        self.fall_count += other.fall_count;
+        self.narrow_pulse_count += other.narrow_pulse_count;
     }
 }
 
 impl SignalStats {
-    fn clear<'s>(&'s mut self) {
-        self.trans_count_doubled = 0;
-        self.clean_trans_count = 0;
-        self.glitch_trans_count = 0;
-        self.high_time = 0;
-        self.low_time = 0;
-        self.x_time = 0;
-        self.z_time = 0;
+    /// Transitions counted in these stats beyond the ones counted in `other`, without any time
+    fn transitions_beyond(&self, other: &SignalStats) -> SignalStats {
+        SignalStats {
+            trans_count_doubled: self
+                .trans_count_doubled
+                .saturating_sub(other.trans_count_doubled),
+            clean_trans_count: self
+                .clean_trans_count
+                .saturating_sub(other.clean_trans_count),
+            glitch_trans_count: self
+                .glitch_trans_count
+                .saturating_sub(other.glitch_trans_count),
+            interconnect_glitch_count: self
+                .interconnect_glitch_count
+                .saturating_sub(other.interconnect_glitch_count),
+            rise_count: self.rise_count.saturating_sub(other.rise_count),
+            fall_count: self.fall_count.saturating_sub(other.fall_count),
+            ..Default::default()
+        }
+    }
+
+    /// These stats with transitions taken from `transitions`
+    fn with_transitions(&self, transitions: &SignalStats) -> SignalStats {
+        SignalStats {
+            trans_count_doubled: transitions.trans_count_doubled,
+            clean_trans_count: transitions.clean_trans_count,
+            glitch_trans_count: transitions.glitch_trans_count,
+            interconnect_glitch_count: transitions.interconnect_glitch_count,
+            rise_count: transitions.rise_count,
+            fall_count: transitions.fall_count,
+            ..self.clone()
+        }
+    }
+}
+
+/// Transitions counted for a net going straight from one value to another
+fn direct_transition(from: char, to: char) -> SignalStats {
+    let mut stat = SignalStats::default();
+    match (from, to) {
+        _ if from == to => {}
+        ('0', '1') | ('1', '0') => {
+            stat.clean_trans_count = 1;
+            stat.trans_count_doubled = 2;
+            if to == '1' {
+                stat.rise_count = 1;
+            } else {
+                stat.fall_count = 1;
+            }
+        }
+        (_, 'x') | ('x', _) => {
+            stat.trans_count_doubled = 1;
+            stat.glitch_trans_count = 1;
+        }
+        (other, _) => {
+            stat.trans_count_doubled = 1;
+            if other == '0' {
+                stat.clean_trans_count = 1;
+            }
+        }
     }
+    stat
 }
 
-fn val_at(ti: TimeTableIdx, sig: &Signal) -> SignalValue<'_> {
-    let offset = sig
-        .get_offset(ti)
-        .expect("Signal change timestamp should be valid");
-    return sig.get_value_at(&offset, 0);
+/// Kind of activity accumulated for nets. Telling glitches apart from functional activity requires
+/// the clock of each net.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, clap::ValueEnum)]
+pub enum Activity {
+    /// All transitions
+    #[default]
+    All,
+    /// Transitions taking a net from its value at an active edge of its clock to the one at the
+    /// next edge
+    Functional,
+    /// Transitions beyond the functional ones, between consecutive active edges of the clock
+    Glitches,
+}
+
+impl fmt::Display for Activity {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::All => write!(f, "all"),
+            Self::Functional => write!(f, "functional"),
+            Self::Glitches => write!(f, "glitches"),
+        }
+    }
+}
+
+/// Values of a signal at its changes, as bit strings
+type ValueChanges = Vec<(wellen::Time, String)>;
+
+/// Time and bit index of each narrow pulse of a signal
+type NarrowPulses = Vec<(wellen::Time, usize)>;
+
+/// Inertial delay of a net, which makes pulses narrower than a given width disappear
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct PulseFilter {
+    /// Width of the narrowest pulse which passes (in trace time units)
+    pub width: wellen::Time,
+    /// Whether narrow pulses are removed, rather than only counted
+    pub suppress: bool,
+}
+
+impl PulseFilter {
+    /// Filter values of a signal at its changes. A change of a bit is dropped if the bit changes
+    /// again within the width of the filter. Returns the filtered values together with the time
+    /// and bit index of each narrow pulse, after which the bit returns to its previous value.
+    fn apply(&self, changes: ValueChanges) -> (ValueChanges, NarrowPulses) {
+        let raw: Vec<Vec<char>> = changes.iter().map(|(_, v)| v.chars().collect()).collect();
+        let mut filtered = raw.clone();
+        let mut pulses = Vec::new();
+        for i in 0..raw.first().map_or(0, Vec::len) {
+            let mut committed = raw[0][i];
+            for k in 1..raw.len() {
+                let value = raw[k][i];
+                if value == raw[k - 1][i] {
+                    filtered[k][i] = filtered[k - 1][i];
+                    continue;
+                }
+                let next = (k + 1..raw.len()).find(|&j| raw[j][i] != value);
+                match next {
+                    Some(next) if changes[next].0 - changes[k].0 < self.width => {
+                        filtered[k][i] = committed;
+                        if raw[next][i] == committed {
+                            pulses.push((changes[k].0, i));
+                        }
+                    }
+                    _ => committed = value,
+                }
+            }
+        }
+
+        if !self.suppress {
+            return (changes, pulses);
+        }
+        let changes = changes
+            .into_iter()
+            .zip(filtered)
+            .map(|((ts, _), value)| (ts, value.into_iter().collect()))
+            .collect();
+        (changes, pulses)
+    }
+
+    /// Filter the whole history of a signal once, so that stats of each time span only look up
+    /// the filtered changes
+    pub fn filter(
+        &self,
+        wave: &Waveform,
+        sig_ref: SignalRef,
+    ) -> Result<FilteredChanges, StatsError> {
+        let sig = wave
+            .get_signal(sig_ref)
+            .ok_or(StatsError::SignalNotLoaded)?;
+        // Real numbers have no pulses to filter
+        let Some(first_time_idx) = sig.get_first_time_idx() else {
+            return Ok(FilteredChanges::default());
+        };
+        if val_at(wave, first_time_idx, sig)?.bits().is_none() {
+            return Ok(FilteredChanges::default());
+        }
+
+        let changes = sig
+            .time_indices()
+            .iter()
+            .map(|&time_idx| {
+                let ts = time_value_at(wave, time_idx);
+                Ok((ts, bit_string(&val_at(wave, time_idx, sig)?, ts)?))
+            })
+            .collect::<Result<ValueChanges, StatsError>>()?;
+        let (changes, pulses) = self.apply(changes);
+        Ok(FilteredChanges { changes, pulses })
+    }
+}
+
+/// History of a signal with narrow pulses filtered out by a [`PulseFilter`]
+#[derive(Debug, Clone, Default)]
+pub struct FilteredChanges {
+    changes: ValueChanges,
+    pulses: NarrowPulses,
+}
+
+/// How the stats of a net are accumulated
+#[derive(Debug, Clone, Copy)]
+pub struct NetSettings<'a> {
+    pub activity: Activity,
+    /// Signals of all clocks, which are exempt from glitches
+    pub clock_signals: &'a [SignalRef],
+    /// Active edges of the clock of the net, which delimit the cycles glitches are found in
+    pub clock_edges: &'a [wellen::Time],
+    /// Periods in which activity of the net isn't accumulated, as its power domain is off or the
+    /// enable condition doesn't hold
+    pub off_periods: &'a OffPeriods,
+    /// Periods in which the power domain of the net is off. Time spent off while the enable
+    /// condition holds is counted as X.
+    pub switched_off: &'a OffPeriods,
+    /// Periods in which the enable condition doesn't hold
+    pub disabled: &'a OffPeriods,
+    /// History of the signal with narrow pulses filtered out, if the net has an inertial delay
+    pub filtered: Option<&'a FilteredChanges>,
+}
+
+/// Glitches of each bit of a net, found in cycles of its clock
+struct CycleGlitches {
+    /// Value of each bit at the start of the current cycle
+    start_values: Vec<char>,
+    /// Stats of each bit at the start of the current cycle
+    start_stats: Vec<SignalStats>,
+    /// Transitions of each bit beyond the functional one in each finished cycle
+    glitches: Vec<SignalStats>,
+}
+
+impl CycleGlitches {
+    fn new(values: &str) -> Self {
+        Self {
+            start_values: values.chars().collect(),
+            start_stats: vec![Default::default(); values.len()],
+            glitches: vec![Default::default(); values.len()],
+        }
+    }
+
+    /// Finish the current cycle, at the end of which the bits hold given values
+    fn end_cycle(&mut self, ss: &[SignalStats], values: &str) {
+        for (c, i) in izip!(values.chars(), 0..) {
+            let cycle = ss[i].transitions_beyond(&self.start_stats[i]);
+            let functional = direct_transition(self.start_values[i], c);
+            self.glitches[i].accumulate(&cycle.transitions_beyond(&functional));
+            self.start_stats[i] = ss[i].clone();
+            self.start_values[i] = c;
+        }
+    }
 }
 
 fn time_value_at(wave: &Waveform, ti: TimeTableIdx) -> u64 {
-    let time_stamp = wave.time_table()[ti as usize];
-    return time_stamp;
+    wave.time_table()[ti as usize]
+}
+
+fn val_at<'s>(
+    wave: &Waveform,
+    ti: TimeTableIdx,
+    sig: &'s Signal,
+) -> Result<SignalValue<'s>, StatsError> {
+    let offset = sig.get_offset(ti).ok_or(StatsError::MissingValue {
+        time: time_value_at(wave, ti),
+    })?;
+    Ok(sig.get_value_at(&offset, 0))
+}
+
+fn bit_string(val: &SignalValue, time: wellen::Time) -> Result<String, StatsError> {
+    val.to_bit_string()
+        .ok_or(StatsError::NotABitVector { time })
+}
+
+/// Time spans in which a power domain is switched off, sorted and not overlapping. Activity of
+/// nets in the domain is not accumulated within them.
+#[derive(Debug, Clone, Default)]
+pub struct OffPeriods(Vec<(wellen::Time, wellen::Time)>);
+
+impl OffPeriods {
+    /// Find periods in which the lowest bit of a control signal holds a value other than
+    /// `on_value`. The domain is considered off until the first value of the signal.
+    pub fn from_control(
+        wave: &Waveform,
+        sig_ref: SignalRef,
+        on_value: char,
+    ) -> Result<Self, StatsError> {
+        let sig = wave
+            .get_signal(sig_ref)
+            .ok_or(StatsError::SignalNotLoaded)?;
+
+        let mut periods = Vec::new();
+        let mut off_since = Some(0);
+        for &time_idx in sig.time_indices() {
+            let ts = time_value_at(wave, time_idx);
+            let on = bit_string(&val_at(wave, time_idx, sig)?, ts)?.ends_with(on_value);
+            match (off_since, on) {
+                (Some(start), true) => {
+                    if ts > start {
+                        periods.push((start, ts));
+                    }
+                    off_since = None;
+                }
+                (None, false) => off_since = Some(ts),
+                _ => {}
+            }
+        }
+        if let Some(start) = off_since {
+            periods.push((start, wellen::Time::MAX));
+        }
+        Ok(Self(periods))
+    }
+
+    /// Wrap time spans which are already sorted and not overlapping
+    pub(crate) const fn from_spans(periods: Vec<(wellen::Time, wellen::Time)>) -> Self {
+        Self(periods)
+    }
+
+    /// Periods in which either `self` or `other` is off
+    pub fn union(&self, other: &Self) -> Self {
+        let mut spans: Vec<_> = self.0.iter().chain(&other.0).copied().collect();
+        spans.sort_unstable();
+        let mut periods: Vec<(wellen::Time, wellen::Time)> = Vec::with_capacity(spans.len());
+        for (start, end) in spans {
+            match periods.last_mut() {
+                Some(last) if start <= last.1 => last.1 = last.1.max(end),
+                _ => periods.push((start, end)),
+            }
+        }
+        Self(periods)
+    }
+
+    /// Check whether the domain is off at a given time
+    pub fn contains(&self, time: wellen::Time) -> bool {
+        let next = self.0.partition_point(|&(start, _)| start <= time);
+        next > 0 && time < self.0[next - 1].1
+    }
+
+    /// Time in which the domain is off within a time span
+    pub fn overlap(&self, start: wellen::Time, end: wellen::Time) -> wellen::Time {
+        let first = self.0.partition_point(|&(_, off_end)| off_end <= start);
+        self.0[first..]
+            .iter()
+            .take_while(|&&(off_start, _)| off_start < end)
+            .map(|&(off_start, off_end)| off_end.min(end).saturating_sub(off_start.max(start)))
+            .sum()
+    }
 }
 
 pub fn calc_stats_for_each_time_span(
     wave: &Waveform,
-    glitches_only: bool,
-    clk_signal: Option<SignalRef>,
     sig_ref: SignalRef,
     num_of_iterations: u64,
-) -> Vec<PackedStats> {
-    let mut stats = Vec::with_capacity(num_of_iterations as usize);
-    let time_span = (*wave
-        .time_table()
-        .last()
-        .expect("Waveform shouldn't be empty"))
-        / num_of_iterations;
-
-    stats.extend(
-        (0..num_of_iterations)
-            .into_par_iter()
-            .map(|index| {
-                let first_time_stamp = index * time_span;
-                let last_time_stamp = (index + 1) * time_span;
-                return calc_stats(
-                    wave,
-                    glitches_only,
-                    clk_signal,
-                    sig_ref,
-                    first_time_stamp,
-                    last_time_stamp,
-                );
-            })
-            .collect::<Vec<PackedStats>>(),
-    );
+    settings: &NetSettings,
+) -> Result<Vec<PackedStats>, StatsError> {
+    let time_span = wave.time_table().last().copied().unwrap_or(0) / num_of_iterations;
+
+    (0..num_of_iterations)
+        .into_par_iter()
+        .map(|index| {
+            let first_time_stamp = index * time_span;
+            let last_time_stamp = (index + 1) * time_span;
+            calc_stats(wave, sig_ref, first_time_stamp, last_time_stamp, settings)
+        })
+        .collect()
+}
+
+type Changes<'a> = Box<dyn Iterator<Item = Result<(wellen::Time, String), StatsError>> + 'a>;
+
+/// Values of a signal at its changes, fast forwarded to the last one at or before a given time
+/// stamp, followed by the earlier ones (latest first) and narrow pulses found by the filter
+fn value_changes<'a>(
+    wave: &'a Waveform,
+    sig: &'a Signal,
+    first_time_stamp: wellen::Time,
+    filtered: Option<&'a FilteredChanges>,
+) -> (Changes<'a>, Changes<'a>, &'a [(wellen::Time, usize)]) {
+    let value_change = |&time_idx: &TimeTableIdx| {
+        let ts = time_value_at(wave, time_idx);
+        Ok((ts, bit_string(&val_at(wave, time_idx, sig)?, ts)?))
+    };
+    match filtered {
+        None => {
+            let start = sig
+                .time_indices()
+                .partition_point(|&time_idx| time_value_at(wave, time_idx) <= first_time_stamp);
+            let (earlier, changes) = sig.time_indices().split_at(start.max(1) - 1);
+            (
+                Box::new(changes.iter().map(value_change)),
+                Box::new(earlier.iter().rev().map(value_change)),
+                &[],
+            )
+        }
+        Some(filtered) => {
+            let start = filtered
+                .changes
+                .partition_point(|&(ts, _)| ts <= first_time_stamp);
+            let (earlier, changes) = filtered.changes.split_at(start.max(1) - 1);
+            (
+                Box::new(changes.iter().cloned().map(Ok)),
+                Box::new(earlier.iter().rev().cloned().map(Ok)),
+                &filtered.pulses,
+            )
+        }
+    }
+}
 
-    return stats;
+/// Transitions of a bit between two values, doubled so that ones to and from X and Z count as
+/// halves
+fn doubled_transitions(from: char, to: char, time: wellen::Time) -> Result<u32, StatsError> {
+    match (from, to) {
+        ('0', '1') | ('1', '0') => Ok(2),
+        (_, 'x') | ('x', _) | (_, 'z') | ('z', _) => Ok(u32::from(from != to)),
+        _ if from == to => Ok(0),
+        _ => Err(StatsError::UnknownTransition { time, from, to }),
+    }
 }
 
-pub fn calc_stats(
+/// Toggles of all bits of a signal in consecutive cycles. `boundaries` holds the start of the
+/// first cycle followed by the end of each cycle, and changes at a boundary belong to the cycle it
+/// ends. Toggles are counted as by [`calc_stats`] with [`Activity::All`], in a single pass over
+/// the changes of the signal.
+pub(crate) fn cycle_toggles(
     wave: &Waveform,
-    glitches_only: bool,
-    clk_signal: Option<SignalRef>,
     sig_ref: SignalRef,
-    first_time_stamp: wellen::Time,
-    last_time_stamp: wellen::Time,
-) -> PackedStats {
-    let sig = wave.get_signal(sig_ref).unwrap();
+    boundaries: &[wellen::Time],
+    settings: &NetSettings,
+) -> Result<Vec<u32>, StatsError> {
+    let mut toggles = vec![0; boundaries.len().saturating_sub(1)];
+    let (Some(&first_time_stamp), Some(&last_time_stamp)) = (boundaries.first(), boundaries.last())
+    else {
+        return Ok(toggles);
+    };
+    let sig = wave
+        .get_signal(sig_ref)
+        .ok_or(StatsError::SignalNotLoaded)?;
+    // Real numbers have no toggles
+    let Some(first_time_idx) = sig.get_first_time_idx() else {
+        return Ok(toggles);
+    };
+    if val_at(wave, first_time_idx, sig)?.bits().is_none() {
+        return Ok(toggles);
+    }
 
-    let n = sig.time_indices().len();
-    if n == 0 {
-        return PackedStats::Vector(Vec::new());
+    let (mut changes, _, _) = value_changes(wave, sig, first_time_stamp, settings.filtered);
+    let Some(first) = changes.next() else {
+        return Ok(toggles);
+    };
+    let (_, mut prev_val) = first?;
+    let mut prev_ts = first_time_stamp;
+    for change in changes {
+        let (ts, val_str) = change?;
+        if ts > last_time_stamp {
+            break;
+        }
+        let powered =
+            settings.switched_off.overlap(prev_ts, ts) == 0 && !settings.off_periods.contains(ts);
+        if powered {
+            let cycle = boundaries.partition_point(|&boundary| boundary < ts) - 1;
+            for (c, prev_c) in val_str.chars().zip(prev_val.chars()) {
+                toggles[cycle] += doubled_transitions(prev_c, c, ts)?;
+            }
+        }
+        prev_ts = ts;
+        prev_val = val_str;
     }
+    Ok(toggles)
+}
 
-    let mut prev_val = val_at(
-        sig.get_first_time_idx()
-            .expect("Signal should have at least one value change"),
-        sig,
-    );
+/// Accumulate stats of a signal within a time span
+pub fn calc_stats(
+    wave: &Waveform,
+    sig_ref: SignalRef,
+    first_time_stamp: wellen::Time,
+    last_time_stamp: wellen::Time,
+    settings: &NetSettings,
+) -> Result<PackedStats, StatsError> {
+    let NetSettings {
+        activity,
+        clock_signals,
+        clock_edges,
+        off_periods,
+        switched_off,
+        disabled,
+        filtered,
+    } = *settings;
+    let sig = wave
+        .get_signal(sig_ref)
+        .ok_or(StatsError::SignalNotLoaded)?;
+
+    let Some(first_time_idx) = sig.get_first_time_idx() else {
+        return Ok(PackedStats::Vector(Vec::new()));
+    };
 
-    let bits = prev_val.bits();
+    let bits = val_at(wave, first_time_idx, sig)?.bits();
 
     // Check if bits are valid, otherwise value is a real number
     let bit_len = if let Some(bit_len) = bits {
         bit_len
     } else {
         // TODO: add function handling real numbers
-        return PackedStats::Vector(Vec::new());
+        return Ok(PackedStats::Vector(Vec::new()));
     };
 
     let mut ss = Vec::<SignalStats>::with_capacity(bit_len as usize);
@@ -149,87 +598,187 @@ pub fn calc_stats(
         ss.push(Default::default())
     }
 
-    let mut current_value_entry_index: usize = 1;
-
-    // Fast forward to relevant starting time stamp
-    while current_value_entry_index < sig.time_indices().len() {
-        let time_idx = sig.time_indices()[current_value_entry_index];
-
-        if time_value_at(wave, time_idx) > first_time_stamp {
+    let (mut changes, earlier, pulses) = value_changes(wave, sig, first_time_stamp, filtered);
+    let (_, mut prev_val) = changes.next().ok_or(StatsError::MissingValue {
+        time: first_time_stamp,
+    })??;
+
+    // Value each bit held before the X it is in, `None` if the bit isn't in X or has been in X
+    // since the beginning of the trace
+    let mut entered_x_from: Vec<Option<char>> = vec![None; ss.len()];
+    let mut unresolved: Vec<usize> = prev_val
+        .char_indices()
+        .filter_map(|(i, c)| (c == 'x').then_some(i))
+        .collect();
+    for change in earlier {
+        if unresolved.is_empty() {
             break;
         }
-
-        prev_val = val_at(time_idx, sig);
-
-        current_value_entry_index += 1;
+        let (_, val) = change?;
+        let val = val.as_bytes();
+        unresolved.retain(|&i| match val[i] {
+            b'x' => true,
+            c => {
+                entered_x_from[i] = Some(c as char);
+                false
+            }
+        });
     }
+    // Time spent by each bit in its current X within the time span
+    let mut current_x_time: Vec<u32> = vec![0; ss.len()];
 
     // For high time calculations to start only from specified first time stamp
     let mut prev_ts = first_time_stamp;
 
-    // Accumulate statistics over desired time span
-    while current_value_entry_index < sig.time_indices().len() {
-        let time_idx = sig.time_indices()[current_value_entry_index];
-        let val = val_at(time_idx, sig);
-        let ts = time_value_at(wave, time_idx);
-        current_value_entry_index += 1;
+    // Value held by each bit before it entered X, used to classify glitches
+    let mut value_before_x: Vec<Option<char>> = vec![None; ss.len()];
+    // Last 0 or 1 held by each bit, used to count rises and falls through X and Z
+    let mut level: Vec<Option<char>> = prev_val
+        .chars()
+        .map(|c| Some(c).filter(|&c| c == '0' || c == '1'))
+        .collect();
+    // Cycles of the clock of the net, starting with the one the time span starts in
+    let mut cycles = match activity {
+        Activity::All => None,
+        _ if clock_signals.contains(&sig_ref) => None,
+        _ => Some(CycleGlitches::new(&prev_val)),
+    };
+    let mut next_edge = clock_edges.partition_point(|&edge| edge <= first_time_stamp);
+
+    // Value following the time span, used to find out how the X bits end up
+    let mut next_val = None;
 
+    // Accumulate statistics over desired time span
+    for change in changes {
+        let (ts, val_str) = change?;
         if ts > last_time_stamp {
+            next_val = Some(val_str);
             break;
         }
 
-        let val_str = val.to_bit_string().expect("Signal's value should be valid");
-        let prev_val_str = prev_val
-            .to_bit_string()
-            .expect("Signal's previous value should be valid");
+        let prev_val_str = &prev_val;
+        // Changes at an edge of the clock belong to the cycle it starts
+        let edge = clock_edges.partition_point(|&edge| edge <= ts);
+        if let Some(cycles) = cycles.as_mut().filter(|_| edge > next_edge) {
+            cycles.end_cycle(&ss, prev_val_str);
+        }
+        next_edge = edge;
+        // Changes while the power domain is off or the enable condition doesn't hold and ones
+        // restoring values when the domain turns back on are not counted, nor is the time spent off
+        let off_time = off_periods.overlap(prev_ts, ts);
+        let unpowered_time = (off_time - disabled.overlap(prev_ts, ts)) as u32;
+        let powered = switched_off.overlap(prev_ts, ts) == 0 && !off_periods.contains(ts);
         for (c, prev_c, i) in izip!(val_str.chars(), prev_val_str.chars(), 0..) {
+            if !powered {
+                level[i] = Some(c).filter(|&c| c == '0' || c == '1');
+                value_before_x[i] = None;
+                entered_x_from[i] = None;
+                current_x_time[i] = 0;
+                ss[i].modify_time_stat_of_value(prev_c, ts, |v| {
+                    v + (ts - prev_ts - off_time) as u32
+                })?;
+                ss[i].x_time += unpowered_time;
+                continue;
+            }
+
+            ss[i].trans_count_doubled += doubled_transitions(prev_c, c, ts)?;
             match (prev_c, c) {
                 ('0', '1') | ('1', '0') => {
                     ss[i].clean_trans_count += 1;
-                    ss[i].trans_count_doubled += 2;
                 }
-                (other @ _, 'x') | ('x', other @ _) => {
-                    if other != 'x' {
-                        ss[i].trans_count_doubled += 1;
-                        ss[i].glitch_trans_count += 1;
+                (other, 'x') | ('x', other) if other != 'x' => {
+                    ss[i].glitch_trans_count += 1;
+                    if c == 'x' {
+                        value_before_x[i] = Some(other).filter(|&v| v == '0' || v == '1');
+                    } else if value_before_x[i].take() == Some(other) {
+                        ss[i].interconnect_glitch_count += 1;
                     }
                 }
-                (other @ _, 'z') | ('z', other @ _) => {
-                    if other != 'z' {
-                        ss[i].trans_count_doubled += 1;
-                        if other == '0' {
-                            ss[i].clean_trans_count += 1;
-                        }
-                    }
+                ('0', 'z') | ('z', '0') => {
+                    ss[i].clean_trans_count += 1;
                 }
-                _ => {
-                    if prev_c != c {
-                        panic!("Unknown transition {prev_c} -> {c}")
+                _ => {}
+            }
+
+            if c == '0' || c == '1' {
+                match (level[i].replace(c), c) {
+                    (Some('0'), '1') => ss[i].rise_count += 1,
+                    (Some('1'), '0') => ss[i].fall_count += 1,
+                    _ => {}
+                }
+            }
+
+            if prev_c == 'x' {
+                current_x_time[i] += (ts - prev_ts - off_time) as u32;
+            }
+            match (prev_c, c) {
+                ('x', 'x') => {}
+                (_, 'x') => {
+                    entered_x_from[i] = Some(prev_c);
+                    current_x_time[i] = 0;
+                }
+                ('x', _) => {
+                    if entered_x_from[i]
+                        .take()
+                        .is_some_and(|from| from == 'z' || c == 'z')
+                    {
+                        ss[i].bus_contention_time += current_x_time[i];
                     }
+                    current_x_time[i] = 0;
                 }
+                _ => {}
             }
 
-            ss[i].modify_time_stat_of_value(prev_c, |v| v + (ts - prev_ts) as u32);
+            ss[i]
+                .modify_time_stat_of_value(prev_c, ts, |v| v + (ts - prev_ts - off_time) as u32)?;
+            ss[i].x_time += unpowered_time;
         }
         prev_ts = ts;
-        prev_val = val;
+        prev_val = val_str;
+    }
+
+    let off_time = off_periods.overlap(prev_ts, last_time_stamp);
+    let unpowered_time = (off_time - disabled.overlap(prev_ts, last_time_stamp)) as u32;
+    let next_val = next_val.unwrap_or_default();
+    for (prev_c, i) in izip!(prev_val.chars(), 0..) {
+        ss[i].modify_time_stat_of_value(prev_c, prev_ts, |v| {
+            v + (last_time_stamp - prev_ts - off_time) as u32
+        })?;
+        ss[i].x_time += unpowered_time;
+        if prev_c == 'x' {
+            current_x_time[i] += (last_time_stamp - prev_ts - off_time) as u32;
+            let next_c = next_val.as_bytes().get(i).copied();
+            if entered_x_from[i].is_some_and(|from| from == 'z' || next_c == Some(b'z')) {
+                ss[i].bus_contention_time += current_x_time[i];
+            }
+        }
     }
 
-    for (prev_c, i) in izip!(
-        prev_val
-            .to_bit_string()
-            .expect("Signal's previous value should be valid")
-            .chars(),
-        0..
-    ) {
-        ss[i]
-            .modify_time_stat_of_value(prev_c, |v| v + (last_time_stamp - (prev_ts as u64)) as u32);
+    for &(ts, i) in pulses {
+        if ts > first_time_stamp && ts <= last_time_stamp && !off_periods.contains(ts) {
+            ss[i].narrow_pulse_count += 1;
+        }
     }
 
-    if glitches_only {
-        for stat in ss.iter_mut() {
-            if !stat.is_glitch() || (clk_signal != None && sig_ref == clk_signal.unwrap()) {
-                stat.clear();
+    match (activity, cycles) {
+        (Activity::All, _) => {}
+        (Activity::Functional, Some(mut cycles)) => {
+            cycles.end_cycle(&ss, &prev_val);
+            for (stat, glitches) in ss.iter_mut().zip(&cycles.glitches) {
+                *stat = stat.with_transitions(&stat.transitions_beyond(glitches));
+            }
+        }
+        (Activity::Glitches, Some(mut cycles)) => {
+            cycles.end_cycle(&ss, &prev_val);
+            for (stat, glitches) in ss.iter_mut().zip(&cycles.glitches) {
+                *stat = stat.with_transitions(glitches);
+            }
+        }
+        // Transitions of clocks are all functional
+        (Activity::Functional, None) => {}
+        (Activity::Glitches, None) => {
+            for stat in ss.iter_mut() {
+                *stat = stat.with_transitions(&SignalStats::default());
             }
         }
     }
@@ -237,54 +786,51 @@ pub fn calc_stats(
     // TODO: Figure out how the indexing direction is denoted
     ss.reverse();
 
-    return if ss.len() == 1 {
-        PackedStats::OneBit(
-            ss.into_iter()
-                .next()
-                .expect("Signal's value should be valid"),
-        )
-    } else {
-        PackedStats::Vector(ss)
-    };
+    Ok(pack(ss))
 }
 
-pub fn empty_stats(wave: &Waveform, sig_ref: SignalRef) -> PackedStats {
-    let sig = wave.get_signal(sig_ref).unwrap();
+pub fn empty_stats(wave: &Waveform, sig_ref: SignalRef) -> Result<PackedStats, StatsError> {
+    let sig = wave
+        .get_signal(sig_ref)
+        .ok_or(StatsError::SignalNotLoaded)?;
 
-    if sig.time_indices().len() == 0 {
-        return PackedStats::Vector(Vec::new());
-    }
+    let Some(first_time_idx) = sig.get_first_time_idx() else {
+        return Ok(PackedStats::Vector(Vec::new()));
+    };
 
-    let bits = val_at(
-        sig.get_first_time_idx()
-            .expect("Signal should have at least one value change"),
-        sig,
-    )
-    .bits();
+    let bits = val_at(wave, first_time_idx, sig)?.bits();
 
     // Check if bits are valid, otherwise value is a real number
     let bit_len: usize = if let Some(bit_len) = bits {
         bit_len as usize
     } else {
         // TODO: add function handling real numbers
-        return PackedStats::Vector(Vec::new());
+        return Ok(PackedStats::Vector(Vec::new()));
     };
 
-    let ss = vec![Default::default(); bit_len];
+    Ok(pack(vec![Default::default(); bit_len]))
+}
 
-    return if ss.len() == 1 {
-        PackedStats::OneBit(
-            ss.into_iter()
-                .next()
-                .expect("Signal's value should be valid"),
-        )
+fn pack(mut ss: Vec<SignalStats>) -> PackedStats {
+    if ss.len() == 1 {
+        PackedStats::OneBit(ss.remove(0))
     } else {
         PackedStats::Vector(ss)
-    };
+    }
 }
 
-#[derive(Clone)]
+#[derive(Debug, Clone)]
 pub enum PackedStats {
     OneBit(SignalStats),
     Vector(Vec<SignalStats>),
 }
+
+impl PackedStats {
+    /// Stats of each bit of the signal
+    pub fn bits(&self) -> &[SignalStats] {
+        match self {
+            Self::OneBit(stat) => std::slice::from_ref(stat),
+            Self::Vector(stats) => stats,
+        }
+    }
+}
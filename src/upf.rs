@@ -0,0 +1,332 @@
+// Copyright (c) 2024-2026 Antmicro <www.antmicro.com>
+// SPDX-License-Identifier: Apache-2.0
+
+// Minimal reader of Unified Power Format (IEEE 1801) files, extracting power domains and the
+// control signals of their power switches
+
+use std::fmt;
+
+use wellen::GetItem;
+
+use crate::stats::OffPeriods;
+use crate::{Context, indexed_name};
+
+/// Error encountered while parsing a UPF file
+#[derive(Debug, Clone)]
+pub struct ParseError {
+    /// Line at which the error was found
+    pub line: usize,
+    pub message: String,
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "line {}: {}", self.line, self.message)
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+/// Power switch turning a domain on and off
+#[derive(Debug, Clone)]
+pub struct PowerSwitch {
+    pub name: String,
+    /// Hierarchical name of the net controlling the switch, relative to the top of the design
+    pub control: String,
+    /// Value of the control net which turns the domain on
+    pub on_value: char,
+}
+
+/// Power domain with the instances it contains
+#[derive(Debug, Clone)]
+pub struct PowerDomain {
+    pub name: String,
+    /// Hierarchical names of instances in the domain, relative to the top of the design. An
+    /// empty list denotes the whole design.
+    pub elements: Vec<String>,
+    /// Switch of the domain, `None` for domains which are always on
+    pub switch: Option<PowerSwitch>,
+}
+
+/// Power intent of a design
+#[derive(Debug, Clone, Default)]
+pub struct Upf {
+    pub domains: Vec<PowerDomain>,
+}
+
+/// Words of a Tcl command, with braced lists kept as single words
+fn split_words(command: &str) -> Vec<String> {
+    let mut words = Vec::new();
+    let mut word = String::new();
+    let mut depth = 0;
+    for c in command.chars() {
+        match c {
+            '{' => {
+                if depth > 0 {
+                    word.push(c);
+                }
+                depth += 1;
+            }
+            '}' if depth > 0 => {
+                depth -= 1;
+                if depth > 0 {
+                    word.push(c);
+                }
+            }
+            c if c.is_whitespace() && depth == 0 => {
+                if !word.is_empty() {
+                    words.push(std::mem::take(&mut word));
+                }
+            }
+            c => word.push(c),
+        }
+    }
+    if !word.is_empty() {
+        words.push(word);
+    }
+    words
+}
+
+/// Commands separated with `;` outside of braces
+fn split_commands(line: &str) -> Vec<&str> {
+    let mut commands = Vec::new();
+    let mut depth = 0usize;
+    let mut start = 0;
+    for (position, c) in line.char_indices() {
+        match c {
+            '{' => depth += 1,
+            '}' => depth = depth.saturating_sub(1),
+            ';' if depth == 0 => {
+                commands.push(&line[start..position]);
+                start = position + 1;
+            }
+            _ => {}
+        }
+    }
+    commands.push(&line[start..]);
+    commands
+}
+
+/// Value of an option (`-name value`) of a command
+fn option<'a>(words: &'a [String], name: &str) -> Option<&'a str> {
+    let position = words.iter().position(|word| word == name)?;
+    words.get(position + 1).map(String::as_str)
+}
+
+/// Convert a UPF hierarchical name into the notation used by traces
+fn trace_name(name: &str) -> String {
+    name.trim_start_matches('/').replace('/', ".")
+}
+
+impl Upf {
+    /// Parse a UPF file. Only `create_power_domain` and `create_power_switch` commands are
+    /// interpreted, others are skipped.
+    pub fn parse(source: &str) -> Result<Self, ParseError> {
+        let mut upf = Self::default();
+
+        // Join lines continued with a backslash, remembering where each command starts
+        let mut commands = Vec::new();
+        let mut command = String::new();
+        let mut start = 0;
+        for (index, line) in source.lines().enumerate() {
+            if command.is_empty() {
+                start = index + 1;
+            }
+            let line = line.trim();
+            if command.is_empty() && line.starts_with('#') {
+                continue;
+            }
+            match line.strip_suffix('\\') {
+                Some(line) => {
+                    command.push_str(line);
+                    command.push(' ');
+                }
+                None => {
+                    command.push_str(line);
+                    commands.push((start, std::mem::take(&mut command)));
+                }
+            }
+        }
+        commands.push((start, command));
+
+        let commands = commands.iter().flat_map(|(line, command)| {
+            split_commands(command)
+                .into_iter()
+                .map(move |command| (*line, command))
+        });
+        for (line, command) in commands {
+            let words = split_words(command);
+            let error = |message: String| ParseError { line, message };
+            match words.first().map(String::as_str) {
+                Some("create_power_domain") => {
+                    let name = words
+                        .get(1)
+                        .ok_or_else(|| error("power domain has no name".to_string()))?;
+                    let elements = option(&words, "-elements")
+                        .map(|elements| {
+                            elements
+                                .split_whitespace()
+                                .filter(|element| *element != ".")
+                                .map(trace_name)
+                                .collect()
+                        })
+                        .unwrap_or_default();
+                    upf.domains.push(PowerDomain {
+                        name: name.clone(),
+                        elements,
+                        switch: None,
+                    });
+                }
+                Some("create_power_switch") => {
+                    let name = words
+                        .get(1)
+                        .ok_or_else(|| error("power switch has no name".to_string()))?;
+                    let domain = option(&words, "-domain")
+                        .ok_or_else(|| error(format!("power switch `{name}` has no `-domain`")))?;
+                    let control_port = option(&words, "-control_port").ok_or_else(|| {
+                        error(format!("power switch `{name}` has no `-control_port`"))
+                    })?;
+                    let (port, control) = match split_words(control_port).as_slice() {
+                        [port, net] => (port.clone(), trace_name(net)),
+                        [port] => (port.clone(), trace_name(port)),
+                        _ => return Err(error(format!("invalid control port `{control_port}`"))),
+                    };
+
+                    // The on state is `{state supply {expression}}`, where the expression is the
+                    // control port, optionally negated
+                    let on_value = match option(&words, "-on_state") {
+                        None => '1',
+                        Some(on_state) => {
+                            let on_state = split_words(on_state);
+                            match on_state.last().map(|expression| expression.trim()) {
+                                Some(expression) if expression == port => '1',
+                                Some(expression)
+                                    if expression
+                                        .strip_prefix(['!', '~'])
+                                        .is_some_and(|negated| negated.trim() == port) =>
+                                {
+                                    '0'
+                                }
+                                _ => {
+                                    return Err(error(format!(
+                                        "unsupported on state of power switch `{name}`"
+                                    )));
+                                }
+                            }
+                        }
+                    };
+
+                    let domain = upf
+                        .domains
+                        .iter_mut()
+                        .find(|candidate| candidate.name == domain)
+                        .ok_or_else(|| error(format!("power domain `{domain}` is not defined")))?;
+                    domain.switch = Some(PowerSwitch {
+                        name: name.clone(),
+                        control,
+                        on_value,
+                    });
+                }
+                _ => {}
+            }
+        }
+        Ok(upf)
+    }
+}
+
+/// Power domain resolved against a trace
+#[derive(Debug, Clone)]
+pub(crate) struct TraceDomain {
+    pub name: String,
+    /// Full names of scopes in the domain, each followed by a `.`
+    pub scopes: Vec<String>,
+    pub off_periods: OffPeriods,
+    /// Periods in which the domain is off or the enable condition doesn't hold
+    pub gated_periods: OffPeriods,
+}
+
+static NO_OFF_PERIODS: OffPeriods = OffPeriods::from_spans(Vec::new());
+
+/// Periods in which activity of a net with a given full name isn't accumulated, followed by the
+/// ones in which its domain is off
+pub(crate) fn gating<'a>(
+    domains: &'a [TraceDomain],
+    disabled_periods: &'a OffPeriods,
+    net: &str,
+) -> (&'a OffPeriods, &'a OffPeriods) {
+    match domain_of(domains, net) {
+        Some(index) => (&domains[index].gated_periods, &domains[index].off_periods),
+        None => (disabled_periods, &NO_OFF_PERIODS),
+    }
+}
+
+/// Index of the innermost domain containing a net with a given full name
+pub(crate) fn domain_of(domains: &[TraceDomain], net: &str) -> Option<usize> {
+    domains
+        .iter()
+        .enumerate()
+        .filter_map(|(index, domain)| {
+            let depth = domain
+                .scopes
+                .iter()
+                .filter(|scope| net.starts_with(scope.as_str()))
+                .map(String::len)
+                .max()?;
+            Some((depth, index))
+        })
+        .max_by_key(|&(depth, _)| depth)
+        .map(|(_, index)| index)
+}
+
+/// Activity of nets in a power domain within a time window
+#[derive(Debug, Clone)]
+pub struct DomainActivity {
+    pub domain: String,
+    /// Time in which the domain is on (in trace time units)
+    pub on_time: wellen::Time,
+    /// Number of nets (bits) in the domain
+    pub nets: usize,
+    /// Toggles of all nets, counted while the domain is on
+    pub toggles: f64,
+    /// Transitions through X of all nets, counted while the domain is on
+    pub glitches: u64,
+}
+
+impl Context {
+    /// Activity of each power domain in a given iteration (time window)
+    pub fn domain_activity(&self, iteration: usize) -> Vec<DomainActivity> {
+        let window = self.window(iteration);
+        let mut activity: Vec<_> = self
+            .power_domains
+            .iter()
+            .map(|domain| DomainActivity {
+                domain: domain.name.clone(),
+                on_time: window.end
+                    - window.start
+                    - domain.gated_periods.overlap(window.start, window.end),
+                nets: 0,
+                toggles: 0.0,
+                glitches: 0,
+            })
+            .collect();
+
+        let hier = self.wave.hierarchy();
+        for (var_ref, stats) in &self.stats {
+            let var = hier.get(var_ref.0);
+            let name = indexed_name(var.full_name(hier), var);
+            let Some(index) = domain_of(&self.power_domains, &name) else {
+                continue;
+            };
+            if !self.in_power_scope(&name) {
+                continue;
+            }
+            let domain = &mut activity[index];
+            for stat in stats[iteration].bits() {
+                domain.nets += 1;
+                domain.toggles += stat.trans_count_doubled as f64 / 2.0;
+                domain.glitches += stat.glitch_trans_count as u64;
+            }
+        }
+        activity
+    }
+}
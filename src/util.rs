@@ -53,7 +53,7 @@ pub trait VarRefsIter {
 impl VarRefsIter for Hierarchy {
     fn var_refs_iter<'s>(&'s self) -> impl Iterator<Item = VarRef> + 's {
         VarRefIterator {
-            hierarchy: &self,
+            hierarchy: self,
             scopes: self.scopes().map(|s| self.get(s)).collect(),
             get_iter_of_scope: |s: &Scope| s.vars(self),
             get_siter_of_scope: |s: &Scope| s.scopes(self),
@@ -62,3 +62,52 @@ impl VarRefsIter for Hierarchy {
         }
     }
 }
+
+/// Matches a hierarchical name against a glob `pattern`. `?` matches any single character and
+/// `*` any sequence of characters within a single hierarchy level, while `**` matches any
+/// sequence of characters, including the `.` separators.
+pub fn glob_match(pattern: &str, name: &str) -> bool {
+    let pattern: Vec<_> = pattern.chars().collect();
+    let name: Vec<_> = name.chars().collect();
+    let (mut p, mut n) = (0, 0);
+    // Positions in the pattern after the last `*` and `**`, and in the name where they resume
+    let mut star: Option<(usize, usize)> = None;
+    let mut double_star: Option<(usize, usize)> = None;
+    while n < name.len() {
+        match pattern.get(p) {
+            Some('*') if pattern.get(p + 1) == Some(&'*') => {
+                p += 2;
+                double_star = Some((p, n));
+                star = None;
+            }
+            Some('*') => {
+                p += 1;
+                star = Some((p, n));
+            }
+            Some('?') if name[n] != '.' => (p, n) = (p + 1, n + 1),
+            Some(&c) if c == name[n] => (p, n) = (p + 1, n + 1),
+            // Let the last star consume one more character. A `*` can't consume a separator,
+            // and the segments since the last `**` are pinned by their separators, so in that
+            // case only the last `**` can.
+            _ => match (star, double_star) {
+                (Some((star_p, star_n)), _) if name[star_n] != '.' => {
+                    star = Some((star_p, star_n + 1));
+                    (p, n) = (star_p, star_n + 1);
+                }
+                (_, Some((star_p, star_n))) => {
+                    star = None;
+                    double_star = Some((star_p, star_n + 1));
+                    (p, n) = (star_p, star_n + 1);
+                }
+                _ => return false,
+            },
+        }
+    }
+    pattern[p..].iter().all(|&c| c == '*')
+}
+
+/// Quotient of two values, or 0 if the divisor is 0, e.g. for rates in a window in which the
+/// enable condition never holds
+pub fn ratio(value: f64, total: f64) -> f64 {
+    if total == 0.0 { 0.0 } else { value / total }
+}
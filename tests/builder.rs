@@ -0,0 +1,93 @@
+// Copyright (c) 2024-2026 Antmicro <www.antmicro.com>
+// SPDX-License-Identifier: Apache-2.0
+
+use libtrace2power::error::Error;
+use libtrace2power::{ContextBuilder, OutputFormat, SaifDialect};
+
+fn synth_builder() -> ContextBuilder {
+    ContextBuilder::new("tests/synth/counter.vcd")
+        .clk_freq(500000000.0)
+        .limit_scope("counter_tb.counter0")
+        .netlist_file("tests/synth/counter.json")
+        .remove_virtual_pins(true)
+}
+
+#[test]
+fn test_builder_saif() {
+    let ctx = synth_builder()
+        .ignore_date(true)
+        .ignore_version(true)
+        .build()
+        .expect("Context should be built");
+
+    let mut actual = Vec::new();
+    ctx.export(OutputFormat::Saif, &mut actual, 0)
+        .expect("Export should succeed");
+
+    let golden = include_str!("synth/synth.saif");
+    assert_eq!(String::from_utf8(actual).unwrap(), golden);
+}
+
+#[test]
+fn test_builder_tcl() {
+    let ctx = synth_builder().build().expect("Context should be built");
+
+    let mut actual = Vec::new();
+    ctx.export(OutputFormat::Tcl, &mut actual, 0)
+        .expect("Export should succeed");
+
+    let golden = include_str!("synth/synth.tcl");
+    assert_eq!(String::from_utf8(actual).unwrap(), golden);
+}
+
+#[test]
+fn test_builder_windows() {
+    let ctx = synth_builder()
+        .windows(4)
+        .build()
+        .expect("Context should be built");
+    assert_eq!(ctx.num_of_iterations, 4);
+}
+
+#[test]
+fn test_builder_missing_clk_freq() {
+    let result = ContextBuilder::new("tests/synth/counter.vcd").build();
+    assert!(matches!(result, Err(Error::MissingClockFrequency)));
+}
+
+#[test]
+fn test_builder_saif_xilinx_dut_subtree() {
+    let ctx = ContextBuilder::new("tests/limit_scope_power/hierarchical.vcd")
+        .clk_freq(500000000.0)
+        .saif_dialect(SaifDialect::Xilinx)
+        .top_scope("hierarchical_tb.dut.adder1")
+        .ignore_date(true)
+        .ignore_version(true)
+        .build()
+        .expect("Context should be built");
+
+    let mut actual = Vec::new();
+    ctx.export(OutputFormat::Saif, &mut actual, 0)
+        .expect("Export should succeed");
+    let actual = String::from_utf8(actual).unwrap();
+
+    // Only the DUT and its nets are exported, without nets of the instances around it or
+    // instances next to it
+    assert!(actual.contains("(INSTANCE adder1\n"), "{actual}");
+    assert!(actual.contains("(r "), "{actual}");
+    assert!(!actual.contains("multiplier1"), "{actual}");
+    assert!(!actual.contains("mul_res"), "{actual}");
+    assert!(!actual.contains("INSTANCE dut"), "{actual}");
+}
+
+#[test]
+fn test_builder_saif_xilinx_missing_top_scope() {
+    let ctx = ContextBuilder::new("tests/limit_scope_power/hierarchical.vcd")
+        .clk_freq(500000000.0)
+        .saif_dialect(SaifDialect::Xilinx)
+        .build()
+        .expect("Context should be built");
+
+    let result = ctx.export(OutputFormat::Saif, std::io::sink(), 0);
+    assert!(matches!(result, Err(Error::MissingTopScope)));
+}
@@ -0,0 +1,111 @@
+// Copyright (c) 2024-2026 Antmicro <www.antmicro.com>
+// SPDX-License-Identifier: Apache-2.0
+
+use libtrace2power::{ContextBuilder, OutputFormat, TclDialect};
+
+// Bits of `hi[15:8]` and `asc[0:3]` are named after their declared indices. Only the LSB of `hi`
+// (`hi[8]`) and the MSB of `asc` (`asc[0]`) toggle.
+
+fn export(format: OutputFormat, dialect: TclDialect) -> String {
+    let ctx = ContextBuilder::new("tests/bus/bus.vcd")
+        .clk_freq(500e6)
+        .limit_scope("tb")
+        .tcl_dialect(dialect)
+        .build()
+        .expect("Context should be built");
+    let mut out = Vec::new();
+    ctx.export(format, &mut out, 0)
+        .expect("Export should succeed");
+    String::from_utf8(out).unwrap()
+}
+
+#[test]
+fn test_json_bus_bits() {
+    let json = export(OutputFormat::Json, TclDialect::default());
+    let json: serde_json::Value = serde_json::from_str(&json).expect("Output should be valid JSON");
+
+    let nets = json["hierarchy"][0]["nets"]
+        .as_array()
+        .expect("Scope should have nets");
+    let toggles = |name: &str| {
+        nets.iter()
+            .find(|net| net["name"] == name)
+            .and_then(|net| net["TC"].as_u64())
+            .unwrap_or_else(|| panic!("Net {name} should be exported"))
+    };
+    assert!(toggles("hi[8]") > 0);
+    assert_eq!(toggles("hi[15]"), 0);
+    assert!(toggles("asc[0]") > 0);
+    assert_eq!(toggles("asc[3]"), 0);
+    assert!(!nets.iter().any(|net| net["name"] == "hi[0]"));
+}
+
+#[test]
+fn test_csv_bus_bits() {
+    let csv = export(OutputFormat::Csv, TclDialect::default());
+    let rows: Vec<Vec<_>> = csv
+        .lines()
+        .skip(1)
+        .map(|line| line.split(',').collect())
+        .collect();
+    let toggles = |net: &str, bit: &str| {
+        rows.iter()
+            .find(|row| row[3] == net && row[4] == bit)
+            .map(|row| row[9].parse::<u32>().unwrap())
+            .unwrap_or_else(|| panic!("Bit {bit} of {net} should be exported"))
+    };
+    assert!(toggles("tb.hi", "8") > 0);
+    assert_eq!(toggles("tb.hi", "15"), 0);
+    assert!(toggles("tb.asc", "0") > 0);
+    assert_eq!(toggles("tb.asc", "3"), 0);
+    assert!(!rows.iter().any(|row| row[3] == "tb.hi" && row[4] == "0"));
+}
+
+#[test]
+fn test_tcf_bus_bits() {
+    let tcf = export(OutputFormat::Tcf, TclDialect::default());
+    let toggles = |net: &str| {
+        tcf.lines()
+            .find_map(|line| line.trim().strip_prefix(&format!("\"{net}\" : \"")))
+            .and_then(|stats| stats.trim_end_matches("\";").split(' ').nth(1))
+            .map(|toggles| toggles.parse::<u32>().unwrap())
+            .unwrap_or_else(|| panic!("Net {net} should be exported: {tcf}"))
+    };
+    assert!(toggles("hi[8]") > 0);
+    assert_eq!(toggles("hi[15]"), 0);
+    assert!(toggles("asc[0]") > 0);
+    assert_eq!(toggles("asc[3]"), 0);
+    assert!(!tcf.contains("\"hi[0]\""));
+}
+
+#[test]
+fn test_tcl_bus_bits_voltus() {
+    let tcl = export(OutputFormat::Tcl, TclDialect::Voltus);
+
+    for bit in 8..16 {
+        assert!(tcl.contains(&format!("-pin {{hi[{bit}]}}")), "{tcl}");
+    }
+    for bit in 0..4 {
+        assert!(tcl.contains(&format!("-pin {{asc[{bit}]}}")), "{tcl}");
+    }
+    assert!(!tcl.contains("{hi[0]}"));
+
+    let toggling = |pin: &str| {
+        tcl.lines()
+            .any(|line| line.contains(&format!("{{{pin}}}")) && !line.contains("-activity 0 "))
+    };
+    assert!(toggling("hi[8]"));
+    assert!(!toggling("hi[15]"));
+    assert!(toggling("asc[0]"));
+    assert!(!toggling("asc[3]"));
+}
+
+#[test]
+fn test_tcl_bus_bits_primetime() {
+    let tcl = export(OutputFormat::Tcl, TclDialect::Primetime);
+
+    assert!(tcl.contains("hi[8]"), "{tcl}");
+    assert!(tcl.contains("asc[0]"), "{tcl}");
+    assert!(!tcl.contains("hi[0]"));
+    assert!(!tcl.contains("asc[4]"));
+}
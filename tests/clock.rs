@@ -0,0 +1,478 @@
+// Copyright (c) 2024-2026 Antmicro <www.antmicro.com>
+// SPDX-License-Identifier: Apache-2.0
+
+use clap::Parser;
+use libtrace2power::clock::ClockSpec;
+use libtrace2power::error::{Error, Warning};
+use libtrace2power::stats::Activity;
+use libtrace2power::{Args, Context, ContextBuilder, OutputFormat, TclDialect};
+
+fn synth_builder() -> ContextBuilder {
+    ContextBuilder::new("tests/synth/counter.vcd")
+        .clock_name("clk")
+        .limit_scope("counter_tb.counter0")
+        .netlist_file("tests/synth/counter.json")
+        .remove_virtual_pins(true)
+}
+
+#[test]
+fn test_clock_measured_frequency() {
+    let ctx = synth_builder().build().expect("Context should be built");
+    assert_eq!(ctx.clk_freq(), 500000000.0);
+    assert!(ctx.warnings().is_empty());
+
+    // The measured frequency normalizes activity the same way as the given one
+    let mut actual = Vec::new();
+    ctx.export(OutputFormat::Tcl, &mut actual, 0)
+        .expect("Export should succeed");
+    assert_eq!(
+        String::from_utf8(actual).unwrap(),
+        include_str!("synth/synth.tcl")
+    );
+}
+
+#[test]
+fn test_clock_frequency_mismatch() {
+    let ctx = synth_builder()
+        .clk_freq(1e9)
+        .build()
+        .expect("Context should be built");
+
+    // The given frequency takes precedence over the measured one
+    assert_eq!(ctx.clk_freq(), 1e9);
+    match ctx.warnings() {
+        [
+            Warning::ClockFrequencyMismatch {
+                clock,
+                given,
+                measured,
+            },
+        ] => {
+            assert_eq!(clock, "clk");
+            assert_eq!((*given, *measured), (1e9, 500000000.0));
+        }
+        warnings => panic!("Unexpected warnings: {warnings:?}"),
+    }
+
+    let ctx = synth_builder()
+        .clk_freq(500100000.0)
+        .build()
+        .expect("Context should be built");
+    assert!(ctx.warnings().is_empty());
+}
+
+#[test]
+fn test_clock_jitter_and_gaps() {
+    let ctx = ContextBuilder::new("tests/clock/jitter.vcd")
+        .clock_name("clk")
+        .build()
+        .expect("Context should be built");
+
+    // A single longer period and a gap don't affect the median period
+    assert_eq!(ctx.clk_freq(), 100000000.0);
+    let warnings: Vec<_> = ctx.warnings().iter().map(|w| w.to_string()).collect();
+    assert_eq!(
+        warnings,
+        [
+            "Periods of clock `clk` differ from its median period by over 5% (1 times)",
+            "Clock `clk` stops in the trace (1 times)",
+        ]
+    );
+}
+
+fn two_clocks_builder(slow: &str) -> ContextBuilder {
+    ContextBuilder::new("tests/clock/two_clocks.vcd")
+        .clock_name("clk_fast")
+        .limit_scope("tb")
+        .clock(slow.parse().expect("Clock should be valid"))
+}
+
+/// Activity of each net in the first window of a CSV export
+fn csv_activity(ctx: &Context) -> Vec<(String, String)> {
+    let mut actual = Vec::new();
+    ctx.export(OutputFormat::Csv, &mut actual, 0)
+        .expect("Export should succeed");
+    String::from_utf8(actual)
+        .unwrap()
+        .lines()
+        .skip(1)
+        .map(|row| {
+            let fields: Vec<_> = row.split(',').collect();
+            (fields[3].to_string(), fields[14].to_string())
+        })
+        .collect()
+}
+
+#[test]
+fn test_clock_domains_by_pattern() {
+    let ctx = two_clocks_builder("clk_slow@tb.dut.sq,tb.dut.ff_slow")
+        .build()
+        .expect("Context should be built");
+    assert_eq!(ctx.clocks().len(), 2);
+    assert_eq!(ctx.clocks()[1].freq, 25000000.0);
+
+    let mut actual = Vec::new();
+    ctx.export(OutputFormat::Tcl, &mut actual, 0)
+        .expect("Export should succeed");
+    let actual = String::from_utf8(actual).unwrap();
+    // 3 toggles in 4 cycles of the slow clock, 15 toggles in 16 cycles of the fast one
+    assert!(actual.contains("-pins \"dut/sq dut/ff_slow/Q\" -activity 0.75 "));
+    assert!(actual.contains("-pins \"dut/fq dut/ff_fast/Q\" -activity 0.9375 "));
+    // The slow clock net isn't matched by the patterns
+    assert!(actual.contains("-pins \"dut/clk_slow\" -activity 0.4375 "));
+
+    let ctx = two_clocks_builder("clk_slow@tb.dut.sq")
+        .tcl_dialect(TclDialect::Primetime)
+        .build()
+        .expect("Context should be built");
+    let mut actual = Vec::new();
+    ctx.export(OutputFormat::Tcl, &mut actual, 0)
+        .expect("Export should succeed");
+    let actual = String::from_utf8(actual).unwrap();
+    assert!(actual.contains(
+        "-toggle_rate 0.75 -static_probability 0.375 -base_clock {clk_slow} [get_pins {dut/sq}]"
+    ));
+    assert!(actual.contains("-base_clock {clk_fast} [get_pins {dut/fq"));
+}
+
+#[test]
+fn test_clock_domains_base_clocks() {
+    // Without a primary clock, nets outside the patterns belong to the first declared clock
+    let ctx = ContextBuilder::new("tests/clock/two_clocks.vcd")
+        .limit_scope("tb")
+        .clock("dut.clk_fast".parse().expect("Clock should be valid"))
+        .clock(
+            "tb.dut.clk_slow@tb.dut.sq"
+                .parse()
+                .expect("Clock should be valid"),
+        )
+        .tcl_dialect(TclDialect::Primetime)
+        .build()
+        .expect("Context should be built");
+    let mut actual = Vec::new();
+    ctx.export(OutputFormat::Tcl, &mut actual, 0)
+        .expect("Export should succeed");
+    let actual = String::from_utf8(actual).unwrap();
+
+    // Base clocks are named after the clock nets, without their paths
+    assert!(actual.contains(
+        "-toggle_rate 0.75 -static_probability 0.375 -base_clock {clk_slow} [get_pins {dut/sq}]"
+    ));
+    assert!(
+        actual.contains("-base_clock {clk_fast} [get_pins {dut/fq"),
+        "{actual}"
+    );
+    assert!(
+        actual
+            .lines()
+            .filter(|line| line.contains("set_switching_activity"))
+            .all(|line| line.contains("-base_clock")),
+        "{actual}"
+    );
+}
+
+#[test]
+fn test_clock_domains_by_netlist() {
+    let ctx = two_clocks_builder("clk_slow")
+        .netlist_file("tests/clock/two_clocks.json")
+        .top("dut")
+        .top_scope("tb.dut")
+        .build()
+        .expect("Context should be built");
+
+    let activity = csv_activity(&ctx);
+    let expected = [
+        ("tb.dut.clk_fast", "1.9375"),
+        ("tb.dut.clk_slow", "1.75"),
+        ("tb.dut.fq", "0.9375"),
+        ("tb.dut.sq", "0.75"),
+        ("tb.dut.ff_fast.CLK", "1.9375"),
+        ("tb.dut.ff_fast.Q", "0.9375"),
+        ("tb.dut.ff_slow.CLK", "1.75"),
+        ("tb.dut.ff_slow.Q", "0.75"),
+    ];
+    assert_eq!(
+        activity,
+        expected.map(|(net, activity)| (net.to_string(), activity.to_string()))
+    );
+}
+
+#[test]
+fn test_clock_cycles() {
+    let ctx = two_clocks_builder("clk_slow@tb.dut.sq,tb.dut.ff_slow,tb.dut.clk_slow")
+        .build()
+        .expect("Context should be built");
+
+    let cycles = ctx.clock_activity(0).expect("Activity should be computed");
+    let fast: Vec<_> = cycles.iter().filter(|cycle| cycle.clock == 0).collect();
+    let slow: Vec<_> = cycles.iter().filter(|cycle| cycle.clock == 1).collect();
+    // Cycles start at rising edges of the clock, with partial ones at both ends of the window
+    assert_eq!(fast.len(), 17);
+    let starts: Vec<_> = slow.iter().map(|cycle| cycle.start).collect();
+    assert_eq!(starts, [0, 20, 60, 100, 140]);
+    assert_eq!((slow[1].start, slow[1].end, slow[1].nets), (20, 60, 4));
+    assert_eq!((slow[4].start, slow[4].end), (140, 160));
+
+    // Toggles of all cycles sum up to the toggles of the whole window
+    let toggles: f64 = slow.iter().map(|cycle| cycle.toggles).sum();
+    assert_eq!(toggles, 3.0 + 3.0 + 7.0 + 7.0);
+
+    let mut actual = Vec::new();
+    ctx.export(OutputFormat::Clocks, &mut actual, 0)
+        .expect("Export should succeed");
+    let actual = String::from_utf8(actual).unwrap();
+    assert_eq!(actual.lines().count(), 1 + 17 + 5);
+    assert!(actual.contains("\n0,clk_slow,1,20,60,4,"));
+
+    // Only nets within the power scope count
+    let ctx = two_clocks_builder("clk_slow@tb.dut.sq,tb.dut.ff_slow,tb.dut.clk_slow")
+        .limit_scope_power("tb.dut.ff_slow")
+        .build()
+        .expect("Context should be built");
+    let cycles = ctx.clock_activity(0).expect("Activity should be computed");
+    let (fast, slow): (Vec<_>, Vec<_>) = cycles.iter().partition(|cycle| cycle.clock == 0);
+    assert!(fast.iter().all(|cycle| cycle.nets == 0));
+    assert!(slow.iter().all(|cycle| cycle.nets == 2));
+    let toggles: f64 = slow.iter().map(|cycle| cycle.toggles).sum();
+    assert_eq!(toggles, 3.0 + 7.0);
+}
+
+#[test]
+fn test_clock_spec() {
+    let spec: ClockSpec = "clk_slow=25e6@tb.dut.sq,tb.*.ff_*"
+        .parse()
+        .expect("Clock should be valid");
+    assert_eq!(spec.name, "clk_slow");
+    assert_eq!(spec.freq, Some(25e6));
+    assert!(spec.matches("tb.dut.sq"));
+    assert!(spec.matches("tb.dut.ff_slow"));
+    assert!(!spec.matches("tb.dut.sq2"));
+
+    let spec: ClockSpec = "clk_slow@tb.dut".parse().unwrap();
+    assert!(spec.matches("tb.dut.ff_slow.Q"));
+    assert!(!spec.matches("tb.dut2.q"));
+
+    assert!("clk=fast".parse::<ClockSpec>().is_err());
+    assert!("=1e6".parse::<ClockSpec>().is_err());
+
+    match two_clocks_builder("missing@tb.dut.sq").build() {
+        Err(Error::ClockNotMeasured { clock }) => assert_eq!(clock, "missing"),
+        Err(err) => panic!("Unexpected error: {err}"),
+        Ok(_) => panic!("Building should fail"),
+    }
+}
+
+fn resolve_clock(limit_scope: &str, clock: &str) -> Result<Context, Error> {
+    ContextBuilder::new("tests/clock/two_clocks.vcd")
+        .limit_scope(limit_scope)
+        .clock_name(clock)
+        .build()
+}
+
+#[test]
+fn test_clock_name_resolution() {
+    // Paths are relative to the lookup scope, absolute or globs
+    for clock in [
+        "dut.clk_slow",
+        "tb.dut.clk_slow",
+        "dut.clk_s*",
+        "**.ff_slow.CLK",
+    ] {
+        let ctx = resolve_clock("tb", clock).expect("Context should be built");
+        assert_eq!(ctx.clk_freq(), 25000000.0, "{clock}");
+    }
+
+    // Absolute paths, including globs, aren't prefixed with the lookup scope
+    for clock in ["tb.dut.clk_s*", "tb.**.clk_slow"] {
+        let ctx = resolve_clock("tb.dut.ff_slow", clock).expect("Context should be built");
+        assert_eq!(ctx.clk_freq(), 25000000.0, "{clock}");
+    }
+
+    // Names without a path are looked up under the lookup scope only
+    let ctx = resolve_clock("tb", "clk_slow").expect("Context should be built");
+    assert_eq!(ctx.clk_freq(), 25000000.0);
+    for clock in ["clk_fast", "missing", "tb.dut.missing*"] {
+        match resolve_clock("tb.dut.ff_slow", clock) {
+            Err(Error::ClockNotFound { clock: name }) => assert_eq!(name, clock),
+            Err(err) => panic!("Unexpected error: {err}"),
+            Ok(_) => panic!("Clock `{clock}` shouldn't be found"),
+        }
+    }
+
+    // Aliases of the same clock aren't ambiguous
+    let ctx = resolve_clock("tb", "**.clk_fast").expect("Context should be built");
+    assert_eq!(ctx.clk_freq(), 100000000.0);
+}
+
+#[test]
+fn test_clock_name_ambiguous() {
+    for (clock, candidates) in [
+        ("dut.clk_*", ["tb.dut.clk_fast", "tb.dut.clk_slow"]),
+        ("CLK", ["tb.dut.ff_fast.CLK", "tb.dut.ff_slow.CLK"]),
+    ] {
+        match resolve_clock("tb", clock) {
+            Err(Error::AmbiguousClock {
+                clock: name,
+                candidates: actual,
+            }) => {
+                assert_eq!(name, clock);
+                assert_eq!(actual, candidates);
+            }
+            Err(err) => panic!("Unexpected error: {err}"),
+            Ok(_) => panic!("Clock `{clock}` should be ambiguous"),
+        }
+    }
+}
+
+#[test]
+fn test_clock_aliases_exempt_from_glitches() {
+    let builder = || {
+        ContextBuilder::new("tests/clock/two_clocks.vcd")
+            .limit_scope("tb")
+            .clock_name("dut.ff_fast.CLK")
+            .clock("dut.clk_slow@tb.dut.sq".parse().unwrap())
+    };
+    let all = builder().build().expect("Context should be built");
+    let glitches = builder()
+        .only_glitches(true)
+        .build()
+        .expect("Context should be built");
+
+    // Nets carrying either clock only have functional transitions, as do the registers they clock
+    for (net, all) in glitches.nets().iter().zip(all.nets()) {
+        assert_eq!(net.name(), all.name());
+        assert_eq!(
+            net.totals(0).unwrap().trans_count_doubled,
+            0,
+            "{}",
+            net.name()
+        );
+        assert_eq!(
+            net.totals(0).unwrap().high_time,
+            all.totals(0).unwrap().high_time
+        );
+    }
+}
+
+fn glitches_builder() -> ContextBuilder {
+    ContextBuilder::new("tests/clock/glitches.vcd").clock_name("clk")
+}
+
+/// Doubled transitions, rises and falls of each net
+fn transitions(ctx: &Context) -> Vec<(String, u32, u32, u32)> {
+    ctx.nets()
+        .iter()
+        .map(|net| {
+            let totals = net.totals(0).unwrap();
+            (
+                net.name().to_string(),
+                totals.trans_count_doubled,
+                totals.rise_count,
+                totals.fall_count,
+            )
+        })
+        .collect()
+}
+
+#[test]
+fn test_clock_glitches() {
+    let all = glitches_builder().build().expect("Context should be built");
+    let functional = glitches_builder()
+        .activity(Activity::Functional)
+        .build()
+        .expect("Context should be built");
+    let glitches = glitches_builder()
+        .activity(Activity::Glitches)
+        .build()
+        .expect("Context should be built");
+
+    let net = |name: &str, transitions: u32, rises: u32, falls: u32| {
+        (format!("tb.{name}"), transitions, rises, falls)
+    };
+    assert_eq!(
+        transitions(&all),
+        [net("clk", 16, 4, 4), net("d", 12, 3, 3), net("q", 6, 2, 1)]
+    );
+    // `d` settles once in the first and the last cycle, and returns to its value in the second
+    assert_eq!(
+        transitions(&functional),
+        [net("clk", 16, 4, 4), net("d", 4, 1, 1), net("q", 6, 2, 1)]
+    );
+    assert_eq!(
+        transitions(&glitches),
+        [net("clk", 0, 0, 0), net("d", 8, 2, 2), net("q", 0, 0, 0)]
+    );
+
+    // Times spent in each value don't depend on the kind of activity
+    for ctx in [&functional, &glitches] {
+        let d = &ctx.nets_matching("tb.d")[0];
+        assert_eq!(d.totals(0).unwrap().high_time, 18);
+    }
+}
+
+#[test]
+fn test_clock_glitches_split() {
+    let mut ctx = glitches_builder()
+        .split_glitches(true)
+        .build()
+        .expect("Context should be built");
+
+    assert_eq!(ctx.activity(), Activity::Functional);
+    let d = |ctx: &Context| ctx.nets_matching("tb.d")[0].totals(0).unwrap();
+    assert_eq!(d(&ctx).trans_count_doubled, 4);
+
+    ctx.set_activity(Activity::Glitches)
+        .expect("Glitches should be accumulated");
+    assert_eq!(d(&ctx).trans_count_doubled, 8);
+    ctx.set_activity(Activity::Functional)
+        .expect("Functional activity should be accumulated");
+    assert_eq!(d(&ctx).trans_count_doubled, 4);
+
+    match ctx.set_activity(Activity::All) {
+        Err(Error::ActivityNotAccumulated {
+            activity: Activity::All,
+        }) => {}
+        Err(err) => panic!("Unexpected error: {err}"),
+        Ok(_) => panic!("All activity shouldn't be accumulated"),
+    }
+}
+
+#[test]
+fn test_clock_glitches_split_output() {
+    let dir = tempfile::tempdir().unwrap();
+    let args = Args::parse_from([
+        "trace2power",
+        "tests/clock/glitches.vcd",
+        "--clock-name",
+        "clk",
+        "--output-format",
+        "tcl",
+        "--split-glitches",
+        "--output",
+        dir.path().join("activity.tcl").to_str().unwrap(),
+    ]);
+    libtrace2power::process(args).expect("Processing should succeed");
+
+    let functional = std::fs::read_to_string(dir.path().join("activity.functional.tcl")).unwrap();
+    let glitches = std::fs::read_to_string(dir.path().join("activity.glitches.tcl")).unwrap();
+    assert!(!dir.path().join("activity.tcl").exists());
+    assert!(functional.contains(r#"-pins "tb/d" -activity 0.5 -duty 0.45"#));
+    assert!(glitches.contains(r#"-pins "tb/d" -activity 1 -duty 0.45"#));
+    assert!(glitches.contains(r#"-pins "tb/q" -activity 0 -duty 0.625"#));
+}
+
+#[test]
+fn test_clock_glitches_without_clock() {
+    let result = ContextBuilder::new("tests/clock/glitches.vcd")
+        .clk_freq(1e8)
+        .only_glitches(true)
+        .build();
+    match result {
+        Err(err @ Error::ClockEdgesUnknown { .. }) => assert_eq!(
+            err.to_string(),
+            "Glitches can't be told apart from functional activity without `--clock-name`"
+        ),
+        Err(err) => panic!("Unexpected error: {err}"),
+        Ok(_) => panic!("Glitches shouldn't be found without a clock"),
+    }
+}
@@ -0,0 +1,173 @@
+// Copyright (c) 2024-2026 Antmicro <www.antmicro.com>
+// SPDX-License-Identifier: Apache-2.0
+
+use libtrace2power::error::Error;
+use libtrace2power::upf::Upf;
+use libtrace2power::{Context, ContextBuilder, OutputFormat};
+
+fn domains_context(upf: bool) -> Context {
+    let builder = ContextBuilder::new("tests/domains/domains.vcd")
+        .clk_freq(100000000.0)
+        .limit_scope("tb");
+    let builder = if upf {
+        builder.upf_file("tests/domains/domains.upf")
+    } else {
+        builder
+    };
+    builder.build().expect("Context should be built")
+}
+
+#[test]
+fn test_domains_table() {
+    let ctx = domains_context(true);
+
+    let mut actual = Vec::new();
+    ctx.export(OutputFormat::Domains, &mut actual, 0)
+        .expect("Export should succeed");
+    assert_eq!(
+        String::from_utf8(actual).unwrap(),
+        include_str!("domains/domains.csv")
+    );
+}
+
+#[test]
+fn test_domains_power_scope() {
+    let ctx = ContextBuilder::new("tests/domains/domains.vcd")
+        .clk_freq(100000000.0)
+        .limit_scope("tb")
+        .limit_scope_power("tb.core.inner")
+        .upf_file("tests/domains/domains.upf")
+        .build()
+        .expect("Context should be built");
+
+    // Only bits of `d` are within the power scope
+    let activity = ctx.domain_activity(0);
+    assert_eq!((activity[0].nets, activity[1].nets), (0, 2));
+}
+
+#[test]
+fn test_domains_gate_activity() {
+    let gated = domains_context(true);
+    let b = gated
+        .net("tb.core.b")
+        .and_then(|net| net.totals(0))
+        .expect("Net should be found");
+
+    // Activity of the core domain is only counted while `pwr_en` is high, and the time it's off
+    // is spent in X, so that the times add up to the duration of the window
+    assert_eq!(b.clean_trans_count, 4);
+    assert_eq!(b.glitch_trans_count, 0);
+    assert_eq!((b.low_time, b.high_time, b.x_time), (50, 20, 30));
+    assert_eq!(
+        (b.low_time + b.high_time + b.x_time + b.z_time) as u64,
+        gated.window(0).active
+    );
+    assert_eq!((b.rise_count, b.fall_count), (2, 2));
+
+    let ungated = domains_context(false);
+    let b = ungated
+        .net("tb.core.b")
+        .and_then(|net| net.totals(0))
+        .expect("Net should be found");
+    assert_eq!(b.x_time, 20);
+    assert_eq!(b.glitch_trans_count, 2);
+
+    // Nets of always-on domains aren't affected
+    let a = |ctx: &Context| ctx.net("tb.aon.a").and_then(|net| net.totals(0)).unwrap();
+    assert_eq!(a(&gated).clean_trans_count, a(&ungated).clean_trans_count);
+    assert_eq!(a(&gated).low_time, 70);
+}
+
+#[test]
+fn test_domains_with_enable() {
+    let ctx = ContextBuilder::new("tests/domains/domains.vcd")
+        .clk_freq(100000000.0)
+        .limit_scope("tb")
+        .upf_file("tests/domains/domains.upf")
+        .enable("aon.a".parse().expect("Condition should be parsed"))
+        .build()
+        .expect("Context should be built");
+    let b = ctx
+        .net("tb.core.b")
+        .and_then(|net| net.totals(0))
+        .expect("Net should be found");
+
+    // `a` holds within 10-40 ns and the core domain is off from 30 ns
+    assert_eq!(ctx.window(0).active, 30);
+    assert_eq!(
+        (b.low_time, b.high_time, b.x_time, b.z_time),
+        (10, 10, 10, 0)
+    );
+}
+
+#[test]
+fn test_domains_active_low_switch() {
+    let upf = Upf::parse(
+        "create_power_domain PD -elements {core}\n\
+         create_power_switch sw -domain PD -control_port {sleep pwr_en} \\\n\
+         \x20   -on_state {on vin {!sleep}}\n",
+    )
+    .expect("Parsing should succeed");
+    assert_eq!(upf.domains[0].elements, ["core"]);
+    let switch = upf.domains[0].switch.as_ref().unwrap();
+    assert_eq!((switch.control.as_str(), switch.on_value), ("pwr_en", '0'));
+
+    let ctx = ContextBuilder::new("tests/domains/domains.vcd")
+        .clk_freq(100000000.0)
+        .limit_scope("tb")
+        .upf(upf)
+        .build()
+        .expect("Context should be built");
+    let activity = ctx.domain_activity(0);
+    assert_eq!(activity[0].on_time, 30);
+    // Only nets leaving X at 50 ns toggle while `pwr_en` is low
+    assert_eq!(activity[0].toggles, 1.5);
+    assert_eq!(activity[0].glitches, 3);
+}
+
+#[test]
+fn test_domains_upf_semicolons() {
+    let upf = Upf::parse(
+        "create_power_domain PD_TOP; create_power_domain PD -elements {core}\n\
+         create_power_switch sw -domain PD -control_port {ctrl pwr_en} \\\n\
+         \x20   -on_state {on vin {ctrl}}; # switch of the core domain\n",
+    )
+    .expect("Parsing should succeed");
+    let names: Vec<_> = upf
+        .domains
+        .iter()
+        .map(|domain| domain.name.as_str())
+        .collect();
+    assert_eq!(names, ["PD_TOP", "PD"]);
+    assert_eq!(upf.domains[1].elements, ["core"]);
+    let switch = upf.domains[1].switch.as_ref().unwrap();
+    assert_eq!((switch.control.as_str(), switch.on_value), ("pwr_en", '1'));
+
+    let err = Upf::parse("create_power_domain PD\ncreate_power_domain A; create_power_switch sw")
+        .expect_err("Parsing should fail");
+    assert_eq!(err.line, 2);
+}
+
+#[test]
+fn test_domains_errors() {
+    let err = Upf::parse("create_power_switch sw -domain PD -control_port {ctrl en}\n")
+        .expect_err("Parsing should fail");
+    assert_eq!(err.line, 1);
+    assert_eq!(err.message, "power domain `PD` is not defined");
+
+    let upf = Upf::parse(
+        "create_power_domain PD -elements {core}\n\
+         create_power_switch sw -domain PD -control_port {ctrl missing}\n",
+    )
+    .unwrap();
+    let result = ContextBuilder::new("tests/domains/domains.vcd")
+        .clk_freq(100000000.0)
+        .limit_scope("tb")
+        .upf(upf)
+        .build();
+    match result {
+        Err(Error::NetNotFound { net }) => assert_eq!(net, "tb.missing"),
+        Err(err) => panic!("Unexpected error: {err}"),
+        Ok(_) => panic!("Building should fail"),
+    }
+}
@@ -0,0 +1,191 @@
+// Copyright (c) 2024-2026 Antmicro <www.antmicro.com>
+// SPDX-License-Identifier: Apache-2.0
+
+use libtrace2power::condition::Condition;
+use libtrace2power::error::{Error, Warning};
+use libtrace2power::stats::SignalStats;
+use libtrace2power::{Context, ContextBuilder, OutputFormat, ProfileMetric};
+
+fn enable_builder() -> ContextBuilder {
+    ContextBuilder::new("tests/enable/enable.vcd").clk_freq(1e9)
+}
+
+fn condition(source: &str) -> Condition {
+    source.parse().expect("Condition should be parsed")
+}
+
+fn totals(ctx: &Context) -> SignalStats {
+    ctx.net("tb.d")
+        .and_then(|net| net.totals(0))
+        .expect("Net should be found")
+}
+
+#[test]
+fn test_enable_unqualified() {
+    let ctx = enable_builder().build().expect("Context should be built");
+
+    let d = totals(&ctx);
+    assert_eq!((d.trans_count_doubled, d.high_time), (12, 550));
+    assert_eq!(ctx.window(0).active, ctx.window(0).duration());
+}
+
+#[test]
+fn test_enable_signal() {
+    let ctx = enable_builder()
+        .enable(condition("tb.busy"))
+        .build()
+        .expect("Context should be built");
+
+    // Only changes and time within 200-600 and 800-1000 count
+    let d = totals(&ctx);
+    assert_eq!((d.trans_count_doubled, d.high_time), (8, 350));
+    assert_eq!(ctx.window(0).active, 600);
+
+    // Duty cycles are relative to the qualified time
+    let mut csv = Vec::new();
+    ctx.export(OutputFormat::Csv, &mut csv, 0)
+        .expect("Export should succeed");
+    let csv = String::from_utf8(csv).unwrap();
+    let duty = format!(",{},", 350.0 / 600.0);
+    assert!(
+        csv.lines()
+            .any(|line| line.contains(",tb.d,") && line.contains(&duty))
+    );
+
+    let mut json = Vec::new();
+    ctx.export(OutputFormat::Json, &mut json, 0)
+        .expect("Export should succeed");
+    let json = String::from_utf8(json).unwrap();
+    assert!(json.contains(r#""duration": 600"#));
+}
+
+#[test]
+fn test_enable_bus_equality() {
+    let ctx = enable_builder()
+        .limit_scope("tb")
+        .enable(condition("busy && state == 2'b01"))
+        .build()
+        .expect("Context should be built");
+
+    let d = totals(&ctx);
+    assert_eq!((d.trans_count_doubled, d.high_time), (2, 100));
+    assert_eq!(ctx.window(0).active, 200);
+}
+
+#[test]
+fn test_enable_or_not() {
+    let ctx = enable_builder()
+        .limit_scope("tb")
+        .enable(condition("!busy || state == 0x3"))
+        .build()
+        .expect("Context should be built");
+
+    // Qualified in 0-200, 600-800 and 900-1000
+    let d = totals(&ctx);
+    assert_eq!((d.trans_count_doubled, d.high_time), (6, 250));
+    assert_eq!(ctx.window(0).active, 500);
+}
+
+#[test]
+fn test_enable_never_holds() {
+    let ctx = enable_builder()
+        .profile_metric(ProfileMetric::ToggleRate)
+        .enable(condition("tb.state == 2'b01 && ~tb.busy"))
+        .build()
+        .expect("Context should be built");
+
+    assert!(matches!(ctx.warnings(), [Warning::EnableNeverHolds { .. }]));
+    assert_eq!(totals(&ctx).trans_count_doubled, 0);
+
+    // Ratios over a window without any qualified time are 0
+    for format in [OutputFormat::Json, OutputFormat::Tcf, OutputFormat::Vcd] {
+        let mut out = Vec::new();
+        ctx.export(format, &mut out, 0)
+            .expect("Export should succeed");
+        let out = String::from_utf8(out).unwrap();
+        assert!(!out.contains("NaN") && !out.contains("inf") && !out.contains("null"));
+    }
+}
+
+#[test]
+fn test_enable_parse() {
+    assert_eq!(
+        condition("a || b && !(c == 'hf)"),
+        Condition::Or(
+            Box::new(Condition::Signal("a".into())),
+            Box::new(Condition::And(
+                Box::new(Condition::Signal("b".into())),
+                Box::new(Condition::Not(Box::new(Condition::Equal("c".into(), 15)))),
+            )),
+        )
+    );
+    assert_eq!(condition("x != 4'b10_10").to_string(), "x != 10");
+    assert_eq!(
+        condition("u.valid && u.ready").signals(),
+        ["u.valid", "u.ready"]
+    );
+
+    let err = "busy &&".parse::<Condition>().unwrap_err();
+    assert_eq!(err.position, 7);
+    let err = "(busy".parse::<Condition>().unwrap_err();
+    assert_eq!(err.position, 5);
+    let err = "state == 2'q1".parse::<Condition>().unwrap_err();
+    assert_eq!(err.position, 9);
+    assert!("busy state".parse::<Condition>().is_err());
+    assert!("busy & ready".parse::<Condition>().is_err());
+}
+
+#[test]
+fn test_enable_unknown_signal() {
+    let result = enable_builder().enable(condition("tb.valid")).build();
+    match result {
+        Err(Error::NetNotFound { net }) => assert_eq!(net, "tb.valid"),
+        Err(err) => panic!("Unexpected error: {err}"),
+        Ok(_) => panic!("Building should fail"),
+    }
+}
+
+fn unknown_builder(source: &str) -> ContextBuilder {
+    ContextBuilder::new("tests/enable/unknown.vcd")
+        .clk_freq(1e9)
+        .limit_scope("tb")
+        .enable(condition(source))
+}
+
+#[test]
+fn test_enable_negated_unknown() {
+    // `en` has no value before 200 and is X in 400-600, so `!en` holds only in 200-400 and
+    // 600-800
+    let ctx = unknown_builder("!en")
+        .build()
+        .expect("Context should be built");
+    assert_eq!(ctx.window(0).active, 400);
+    assert_eq!(totals(&ctx).high_time, 200);
+
+    // `mode` is partially X in 400-600
+    let ctx = unknown_builder("mode != 2'b01")
+        .build()
+        .expect("Context should be built");
+    assert_eq!(ctx.window(0).active, 400);
+    assert_eq!(totals(&ctx).high_time, 200);
+}
+
+#[test]
+fn test_enable_negated_before_first_value() {
+    // `late` has no value before 600, so `!late` is unknown until then
+    let ctx = unknown_builder("en || !late")
+        .build()
+        .expect("Context should be built");
+    assert_eq!(ctx.window(0).active, 400);
+
+    // Known values still decide the condition while `late` is unknown
+    let ctx = unknown_builder("mode == 2'b00 || late != 1'b1")
+        .build()
+        .expect("Context should be built");
+    assert_eq!(ctx.window(0).active, 200 + 300);
+
+    let ctx = unknown_builder("mode != 2'b11 && late != 1'b1")
+        .build()
+        .expect("Context should be built");
+    assert_eq!(ctx.window(0).active, 300);
+}
@@ -0,0 +1,102 @@
+// Copyright (c) 2024-2026 Antmicro <www.antmicro.com>
+// SPDX-License-Identifier: Apache-2.0
+
+use tempfile::NamedTempFile;
+
+use libtrace2power::Args;
+use libtrace2power::OutputFormat;
+use libtrace2power::error::Error;
+use libtrace2power::process;
+use std::path::PathBuf;
+
+fn common_args(output_file: &NamedTempFile) -> Args {
+    Args {
+        input_file: PathBuf::from(r"tests/synth/counter.vcd"),
+        clk_freq: Some(500000000.0),
+        output_format: OutputFormat::Saif,
+        limit_scope: Some(String::from("counter_tb.counter0")),
+        netlist: Some(PathBuf::from(r"tests/synth/counter.json")),
+        remove_virtual_pins: true,
+        output: Some(output_file.path().to_path_buf()),
+        ignore_date: true,
+        ignore_version: true,
+        ..Default::default()
+    }
+}
+
+#[test]
+fn test_missing_input_file() {
+    let output_file = NamedTempFile::new().expect("Failed to allocate temp file");
+    let mut args = common_args(&output_file);
+    args.input_file = PathBuf::from(r"tests/synth/missing.vcd");
+
+    let err = process(args).expect_err("Processing should fail");
+    assert!(err.to_string().contains("tests/synth/missing.vcd"), "{err}");
+    match err {
+        Error::Io { path, .. } => assert_eq!(path, PathBuf::from("tests/synth/missing.vcd")),
+        err => panic!("Unexpected error: {err}"),
+    }
+}
+
+#[test]
+fn test_missing_limit_scope() {
+    let output_file = NamedTempFile::new().expect("Failed to allocate temp file");
+    let mut args = common_args(&output_file);
+    args.limit_scope = Some(String::from("counter_tb.missing"));
+
+    match process(args) {
+        Err(Error::ScopeNotFound { scope }) => assert_eq!(scope, "counter_tb.missing"),
+        other => panic!("Unexpected result: {other:?}"),
+    }
+}
+
+#[test]
+fn test_missing_top_module() {
+    let output_file = NamedTempFile::new().expect("Failed to allocate temp file");
+    let mut args = common_args(&output_file);
+    args.top = Some(String::from("missing"));
+    args.top_scope = Some(String::from("counter_tb.counter0"));
+
+    match process(args) {
+        Err(Error::TopModuleNotFound { module }) => assert_eq!(module, "missing"),
+        other => panic!("Unexpected result: {other:?}"),
+    }
+}
+
+#[test]
+fn test_missing_netlist_file() {
+    let output_file = NamedTempFile::new().expect("Failed to allocate temp file");
+    let mut args = common_args(&output_file);
+    args.netlist = Some(PathBuf::from(r"tests/synth/missing.json"));
+
+    match process(args) {
+        Err(Error::Io { path, .. }) => assert_eq!(path, PathBuf::from("tests/synth/missing.json")),
+        other => panic!("Unexpected result: {other:?}"),
+    }
+}
+
+#[test]
+fn test_invalid_netlist_file() {
+    let output_file = NamedTempFile::new().expect("Failed to allocate temp file");
+    let mut args = common_args(&output_file);
+    args.netlist = Some(PathBuf::from(r"tests/synth/counter.vcd"));
+
+    match process(args) {
+        Err(Error::Netlist { path, .. }) => {
+            assert_eq!(path, PathBuf::from("tests/synth/counter.vcd"))
+        }
+        other => panic!("Unexpected result: {other:?}"),
+    }
+}
+
+#[test]
+fn test_per_clock_cycle_shorter_than_period() {
+    let output_file = NamedTempFile::new().expect("Failed to allocate temp file");
+    let mut args = common_args(&output_file);
+    args.per_clock_cycle = true;
+    args.clk_freq = Some(1.0);
+
+    process(args).expect("Processing should succeed");
+    let saif = std::fs::read_to_string(output_file.path()).expect("Output should be written");
+    assert!(saif.contains("(DURATION 300)"), "{saif}");
+}
@@ -0,0 +1,107 @@
+// Copyright (c) 2024-2026 Antmicro <www.antmicro.com>
+// SPDX-License-Identifier: Apache-2.0
+
+use std::io::Write;
+use std::path::PathBuf;
+
+use tempfile::NamedTempFile;
+
+use libtrace2power::error::{Error, Result};
+use libtrace2power::exporters::{Exporter, ExporterRegistry, NetInfo, ScopeInfo, WindowInfo};
+use libtrace2power::{Args, ContextBuilder, process_with};
+
+/// Writes one line per scope and per net with the number of clean transitions
+#[derive(Default)]
+struct ToggleListExporter {
+    depth: usize,
+}
+
+impl Exporter for ToggleListExporter {
+    fn begin(&mut self, out: &mut dyn Write, window: &WindowInfo) -> Result<()> {
+        writeln!(
+            out,
+            "window {} {}..{}",
+            window.index, window.start, window.end
+        )?;
+        Ok(())
+    }
+
+    fn enter_scope(&mut self, out: &mut dyn Write, scope: &ScopeInfo) -> Result<()> {
+        writeln!(out, "{}{}", "  ".repeat(self.depth), scope.name)?;
+        self.depth += 1;
+        Ok(())
+    }
+
+    fn exit_scope(&mut self, _out: &mut dyn Write, _scope: &ScopeInfo) -> Result<()> {
+        self.depth -= 1;
+        Ok(())
+    }
+
+    fn net(&mut self, out: &mut dyn Write, net: &NetInfo) -> Result<()> {
+        let toggles: u32 = net.stats.iter().map(|stat| stat.clean_trans_count).sum();
+        writeln!(out, "{}{} {}", "  ".repeat(self.depth), net.name, toggles)?;
+        Ok(())
+    }
+}
+
+fn registry() -> ExporterRegistry {
+    let mut registry = ExporterRegistry::new();
+    registry.register("toggles", || Box::new(ToggleListExporter::default()));
+    registry
+}
+
+#[test]
+fn test_custom_exporter() {
+    let ctx = ContextBuilder::new("tests/synth/counter.vcd")
+        .clk_freq(500000000.0)
+        .limit_scope("counter_tb.counter0")
+        .build()
+        .expect("Context should be built");
+
+    let mut exporter = registry().create("toggles").expect("Exporter should exist");
+    let mut actual = Vec::new();
+    ctx.export_with(exporter.as_mut(), &mut actual, 0)
+        .expect("Export should succeed");
+    let actual = String::from_utf8(actual).unwrap();
+
+    let mut lines = actual.lines();
+    assert_eq!(lines.next(), Some("window 0 0..300"));
+    assert_eq!(lines.next(), Some("counter0"));
+    assert_eq!(lines.next(), Some("  clk 300"));
+    assert!(actual.contains("\n  _06_\n    VGND 0\n"));
+}
+
+#[test]
+fn test_unknown_exporter() {
+    let registry = registry();
+    assert_eq!(registry.names().collect::<Vec<_>>(), ["toggles"]);
+    assert!(matches!(
+        registry.create("missing"),
+        Err(Error::UnknownExporter { .. })
+    ));
+}
+
+#[test]
+fn test_custom_format_selection() {
+    let output_file = NamedTempFile::new().expect("Failed to allocate temp file");
+    let args = |custom_format: &str| Args {
+        input_file: PathBuf::from("tests/synth/counter.vcd"),
+        clk_freq: Some(500000000.0),
+        limit_scope: Some(String::from("counter_tb.counter0")),
+        custom_format: Some(String::from(custom_format)),
+        output: Some(output_file.path().to_path_buf()),
+        ..Default::default()
+    };
+
+    process_with(args("toggles"), &registry()).expect("Processing should succeed");
+    let actual = std::fs::read_to_string(output_file.path()).expect("Output should be written");
+    assert!(
+        actual.starts_with("window 0 0..300\ncounter0\n  clk 300\n"),
+        "{actual}"
+    );
+
+    match process_with(args("missing"), &registry()) {
+        Err(Error::UnknownExporter { name }) => assert_eq!(name, "missing"),
+        other => panic!("Unexpected result: {other:?}"),
+    }
+}
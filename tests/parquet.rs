@@ -0,0 +1,103 @@
+// Copyright (c) 2024-2026 Antmicro <www.antmicro.com>
+// SPDX-License-Identifier: Apache-2.0
+
+#![cfg(feature = "parquet")]
+
+use std::fs::File;
+
+use arrow_array::cast::AsArray;
+use arrow_array::types::{Int32Type, Int64Type, UInt32Type};
+use parquet::arrow::arrow_reader::ParquetRecordBatchReaderBuilder;
+use tempfile::NamedTempFile;
+
+use libtrace2power::{ContextBuilder, OutputFormat};
+
+#[test]
+fn test_parquet_per_clock_cycle() {
+    let output_file = NamedTempFile::new().expect("Failed to allocate temp file");
+    let ctx = ContextBuilder::new("tests/synth/counter.vcd")
+        .clk_freq(500000000.0)
+        .limit_scope("counter_tb.counter0")
+        .per_clock_cycle(true)
+        .sparse(true)
+        .build()
+        .expect("Context should be built");
+    ctx.export_to_path(
+        OutputFormat::Parquet,
+        Some(output_file.path().to_path_buf()),
+    )
+    .expect("Export should succeed");
+
+    let reader = ParquetRecordBatchReaderBuilder::try_new(File::open(output_file.path()).unwrap())
+        .expect("Output should be a valid Parquet file")
+        .build()
+        .unwrap();
+
+    let mut clk_windows = Vec::new();
+    for batch in reader {
+        let batch = batch.unwrap();
+        let windows = batch["window"].as_primitive::<UInt32Type>();
+        let scopes = batch["scope"].as_dictionary::<Int32Type>();
+        let nets = batch["net"].as_dictionary::<Int32Type>();
+        let tc = batch["TC"].as_primitive::<UInt32Type>();
+        let scope_values = scopes.values().as_string::<i32>();
+        let net_values = nets.values().as_string::<i32>();
+        for row in 0..batch.num_rows() {
+            let scope = scope_values.value(scopes.keys().value(row) as usize);
+            let net = net_values.value(nets.keys().value(row) as usize);
+            if scope == "counter_tb.counter0" && net == "clk" {
+                clk_windows.push((windows.value(row), tc.value(row)));
+            }
+        }
+    }
+
+    assert_eq!(clk_windows.len(), 150);
+    assert_eq!(clk_windows[0], (0, 2));
+    assert_eq!(clk_windows[149].0, 149);
+}
+
+#[test]
+fn test_parquet_bus_bits() {
+    let output_file = NamedTempFile::new().expect("Failed to allocate temp file");
+    let ctx = ContextBuilder::new("tests/bus/bus.vcd")
+        .clk_freq(500e6)
+        .limit_scope("tb")
+        .build()
+        .expect("Context should be built");
+    ctx.export_to_path(
+        OutputFormat::Parquet,
+        Some(output_file.path().to_path_buf()),
+    )
+    .expect("Export should succeed");
+
+    let reader = ParquetRecordBatchReaderBuilder::try_new(File::open(output_file.path()).unwrap())
+        .expect("Output should be a valid Parquet file")
+        .build()
+        .unwrap();
+
+    // Bits of `hi[15:8]` and `asc[0:3]` are numbered after their declared indices
+    let mut toggles = Vec::new();
+    for batch in reader {
+        let batch = batch.unwrap();
+        let nets = batch["net"].as_dictionary::<Int32Type>();
+        let bits = batch["bit"].as_primitive::<Int64Type>();
+        let tc = batch["TC"].as_primitive::<UInt32Type>();
+        let net_values = nets.values().as_string::<i32>();
+        for row in 0..batch.num_rows() {
+            let net = net_values.value(nets.keys().value(row) as usize);
+            toggles.push((net.to_string(), bits.value(row), tc.value(row)));
+        }
+    }
+
+    let toggles = |net: &str, bit: i64| {
+        toggles
+            .iter()
+            .find(|(name, index, _)| name == net && *index == bit)
+            .map(|&(_, _, tc)| tc)
+            .unwrap_or_else(|| panic!("Bit {bit} of {net} should be exported"))
+    };
+    assert!(toggles("hi", 8) > 0);
+    assert_eq!(toggles("hi", 15), 0);
+    assert!(toggles("asc", 0) > 0);
+    assert_eq!(toggles("asc", 3), 0);
+}
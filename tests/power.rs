@@ -0,0 +1,299 @@
+// Copyright (c) 2024-2026 Antmicro <www.antmicro.com>
+// SPDX-License-Identifier: Apache-2.0
+
+use libtrace2power::error::Error;
+use libtrace2power::liberty::Liberty;
+use libtrace2power::netlist::Netlist;
+use libtrace2power::spef::Spef;
+use libtrace2power::{ContextBuilder, OutputFormat, ProfileMetric};
+use tempfile::NamedTempFile;
+
+fn synth_builder() -> ContextBuilder {
+    ContextBuilder::new("tests/synth/counter.vcd")
+        .clk_freq(500000000.0)
+        .limit_scope("counter_tb.counter0")
+        .netlist_file("tests/synth/counter.json")
+        .top("counter")
+        .top_scope("counter_tb.counter0")
+        .liberty_file("tests/synth/counter.lib")
+}
+
+#[test]
+fn test_power_table() {
+    let ctx = synth_builder().build().expect("Context should be built");
+
+    let mut actual = Vec::new();
+    ctx.export(OutputFormat::Power, &mut actual, 0)
+        .expect("Export should succeed");
+    assert_eq!(
+        String::from_utf8(actual).unwrap(),
+        include_str!("synth/synth_power.csv")
+    );
+}
+
+#[test]
+fn test_power_windows() {
+    let ctx = synth_builder()
+        .windows(2)
+        .build()
+        .expect("Context should be built");
+    let whole = synth_builder().build().expect("Context should be built");
+
+    let total = |ctx: &libtrace2power::Context, iteration| -> f64 {
+        ctx.power(iteration)
+            .expect("Estimation should succeed")
+            .iter()
+            .map(|power| power.internal + power.switching)
+            .sum()
+    };
+    // Dynamic power of the whole trace is the average over both halves
+    let halves = (total(&ctx, 0) + total(&ctx, 1)) / 2.0;
+    assert!((halves - total(&whole, 0)).abs() < 1e-12);
+
+    let instances = ctx.power(0).unwrap();
+    assert_eq!(instances.len(), 11);
+    let flop = instances
+        .iter()
+        .find(|power| power.instance == "counter_tb.counter0.cnt[0]$_DFF_PP0_")
+        .expect("Flip-flop should be estimated");
+    assert_eq!(flop.cell, "sky130_fd_sc_hd__dfrtp_1");
+    assert!((flop.leakage - 8.7e-12).abs() < 1e-20);
+}
+
+#[test]
+fn test_power_wire_capacitance() {
+    let switching = |builder: ContextBuilder| -> f64 {
+        let ctx = builder.build().expect("Context should be built");
+        let power = ctx.power(0).expect("Estimation should succeed");
+        power.iter().map(|power| power.switching).sum()
+    };
+    let without_wires = switching(synth_builder());
+    let with_wires = switching(synth_builder().wire_capacitance(0.01));
+    assert!(with_wires > without_wires);
+}
+
+#[test]
+fn test_power_missing_liberty() {
+    let ctx = ContextBuilder::new("tests/synth/counter.vcd")
+        .clk_freq(500000000.0)
+        .netlist_file("tests/synth/counter.json")
+        .top("counter")
+        .build()
+        .expect("Context should be built");
+    assert!(matches!(ctx.power(0), Err(Error::MissingLiberty)));
+}
+
+#[test]
+fn test_power_top_from_netlist() {
+    let ctx = ContextBuilder::new("tests/synth/counter.vcd")
+        .clk_freq(500000000.0)
+        .limit_scope("counter_tb.counter0")
+        .netlist_file("tests/synth/counter.json")
+        .top_scope("counter_tb.counter0")
+        .liberty_file("tests/synth/counter.lib")
+        .build()
+        .expect("Context should be built");
+
+    // The top module is marked in the netlist
+    let mut actual = Vec::new();
+    ctx.export(OutputFormat::Power, &mut actual, 0)
+        .expect("Export should succeed");
+    assert_eq!(
+        String::from_utf8(actual).unwrap(),
+        include_str!("synth/synth_power.csv")
+    );
+}
+
+#[test]
+fn test_power_missing_liberty_file() {
+    let result = synth_builder()
+        .liberty_file("tests/synth/missing.lib")
+        .build();
+    match result {
+        Err(Error::Io { path, .. }) => assert_eq!(path.to_str(), Some("tests/synth/missing.lib")),
+        Err(err) => panic!("Unexpected error: {err}"),
+        Ok(_) => panic!("Building should fail"),
+    }
+}
+
+#[test]
+fn test_liberty_parse_error() {
+    let mut liberty = Liberty::new();
+    let err = liberty
+        .read("library (broken) {\n  nom_voltage : 1.8;\n  cell (x) {\n")
+        .expect_err("Parsing should fail");
+    assert_eq!(err.line, 3);
+
+    let err = liberty
+        .read("library (units) {\n  nom_voltage : 1.8;\n}\n")
+        .expect_err("Missing units should be reported");
+    assert_eq!(err.message, "library has no `leakage_power_unit`");
+}
+
+#[test]
+fn test_net_power_table() {
+    let ctx = synth_builder()
+        .spef_file("tests/synth/counter.spef")
+        .supply_voltage(1.8)
+        .build()
+        .expect("Context should be built");
+
+    let mut actual = Vec::new();
+    ctx.export(OutputFormat::NetPower, &mut actual, 0)
+        .expect("Export should succeed");
+    assert_eq!(
+        String::from_utf8(actual).unwrap(),
+        include_str!("synth/synth_net_power.csv")
+    );
+}
+
+#[test]
+fn test_net_power_liberty_voltage() {
+    let net_power = |builder: ContextBuilder| {
+        let ctx = builder
+            .spef_file("tests/synth/counter.spef")
+            .build()
+            .expect("Context should be built");
+        ctx.net_power(0)
+    };
+
+    // The Liberty library has a nominal voltage of 1.8V
+    let from_liberty = net_power(synth_builder()).expect("Computation should succeed");
+    let explicit = net_power(synth_builder().supply_voltage(1.8)).unwrap();
+    for (a, b) in from_liberty.iter().zip(&explicit) {
+        assert_eq!(a.switching, b.switching);
+    }
+
+    let builder = ContextBuilder::new("tests/synth/counter.vcd").clk_freq(500000000.0);
+    assert!(matches!(
+        net_power(builder),
+        Err(Error::MissingSupplyVoltage)
+    ));
+}
+
+#[test]
+fn test_net_power_netlist_alias() {
+    // `pulse2_alias` isn't in the trace, but the netlist tells it's the same net as `pulse2`
+    let mut json: serde_json::Value =
+        serde_json::from_str(include_str!("synth/counter.json")).unwrap();
+    json["modules"]["counter"]["netnames"]["pulse2_alias"] = serde_json::json!({ "bits": [4] });
+    let netlist: Netlist = serde_json::from_value(json).unwrap();
+    let spef = Spef::parse("*C_UNIT 1 PF\n*D_NET pulse2_alias 0.01\n*D_NET missing 0.01\n")
+        .expect("Parsing should succeed");
+
+    let ctx = ContextBuilder::new("tests/synth/counter.vcd")
+        .clk_freq(500000000.0)
+        .netlist(netlist)
+        .top("counter")
+        .top_scope("counter_tb.counter0")
+        .spef(spef)
+        .supply_voltage(1.0)
+        .build()
+        .expect("Context should be built");
+    let nets = ctx.net_power(0).expect("Computation should succeed");
+
+    assert_eq!(nets[0].name, "counter_tb.counter0.missing");
+    assert_eq!(nets[0].toggles, None);
+    assert_eq!(nets[1].name, "counter_tb.counter0.pulse2_alias");
+    assert_eq!(nets[1].toggles, Some(149));
+    assert!((nets[1].capacitance - 1e-14).abs() < 1e-24);
+}
+
+fn energy_table_builder() -> ContextBuilder {
+    ContextBuilder::new("tests/synth/counter.vcd")
+        .clk_freq(500000000.0)
+        .limit_scope("counter_tb.counter0")
+        .netlist_file("tests/synth/counter.json")
+        .top("counter")
+        .top_scope("counter_tb.counter0")
+        .remove_virtual_pins(true)
+        .energy_table_file("tests/synth/counter_energy.csv")
+}
+
+#[test]
+fn test_energy_table_power() {
+    let ctx = energy_table_builder()
+        .build()
+        .expect("Context should be built");
+
+    let mut actual = Vec::new();
+    ctx.export(OutputFormat::Power, &mut actual, 0)
+        .expect("Export should succeed");
+    assert_eq!(
+        String::from_utf8(actual).unwrap(),
+        include_str!("synth/synth_energy_power.csv")
+    );
+}
+
+#[test]
+fn test_energy_table_without_top_scope() {
+    // The netlist is rooted at the lookup scope
+    let ctx = ContextBuilder::new("tests/synth/counter.vcd")
+        .clk_freq(500000000.0)
+        .limit_scope("counter_tb.counter0")
+        .netlist_file("tests/synth/counter.json")
+        .remove_virtual_pins(true)
+        .energy_table_file("tests/synth/counter_energy.csv")
+        .build()
+        .expect("Context should be built");
+
+    let mut actual = Vec::new();
+    ctx.export(OutputFormat::Power, &mut actual, 0)
+        .expect("Export should succeed");
+    assert_eq!(
+        String::from_utf8(actual).unwrap(),
+        include_str!("synth/synth_energy_power.csv")
+    );
+}
+
+#[test]
+fn test_energy_table_profile() {
+    let ctx = energy_table_builder()
+        .windows(3)
+        .profile_depth(1)
+        .profile_metric(ProfileMetric::Power)
+        .build()
+        .expect("Context should be built");
+
+    let output_file = NamedTempFile::new().expect("Failed to allocate temp file");
+    ctx.export_to_path(OutputFormat::Vcd, Some(output_file.path().to_path_buf()))
+        .expect("Export should succeed");
+    let actual = std::fs::read_to_string(output_file.path()).expect("Actual file should exist");
+    let (header, changes) = actual
+        .split_once("$enddefinitions $end\n")
+        .expect("Profile should have a header");
+    assert!(header.contains("$var real 64 ! power $end\n"));
+
+    // The signal of the top scope carries the total power of all instances in a window (in mW)
+    let values: Vec<f64> = changes
+        .lines()
+        .filter_map(|line| line.strip_prefix('r'))
+        .map(|change| change.trim_end_matches(" !").parse().unwrap())
+        .collect();
+    assert_eq!(values.len(), 3);
+    for (iteration, value) in values.into_iter().enumerate() {
+        let total: f64 = ctx
+            .power(iteration)
+            .expect("Power should be estimated")
+            .iter()
+            .map(|power| power.total())
+            .sum();
+        assert!((value - total * 1e3).abs() < 1e-9);
+    }
+}
+
+#[test]
+fn test_energy_table_parse_error() {
+    use libtrace2power::energy::EnergyTable;
+
+    let table = EnergyTable::parse("cell,pin,rise,fall,leakage\ninv,A,1,,2\ninv,,,,0.5\n")
+        .expect("Parsing should succeed");
+    let inv = table.cell("inv").unwrap();
+    assert!((inv.leakage - 2.5e-9).abs() < 1e-20);
+    assert!((inv.pins["A"].rise - 1e-12).abs() < 1e-24);
+
+    let err = EnergyTable::parse("cell,pin,rise,fall,leakage\ninv,A,x,,\n")
+        .expect_err("Parsing should fail");
+    assert_eq!(err.line, 2);
+    assert!(EnergyTable::parse("cell,pin\n").is_err());
+}
@@ -0,0 +1,83 @@
+// Copyright (c) 2024-2026 Antmicro <www.antmicro.com>
+// SPDX-License-Identifier: Apache-2.0
+
+use libtrace2power::stats::SignalStats;
+use libtrace2power::{Context, ContextBuilder, OutputFormat};
+
+fn pulses_builder() -> ContextBuilder {
+    ContextBuilder::new("tests/pulses/pulses.vcd")
+        .clk_freq(1e9)
+        .top("top")
+        .top_scope("tb")
+        .netlist_file("tests/pulses/pulses.json")
+}
+
+fn totals(ctx: &Context, net: &str) -> SignalStats {
+    ctx.net(net)
+        .and_then(|net| net.totals(0))
+        .expect("Net should be found")
+}
+
+#[test]
+fn test_pulses_unfiltered() {
+    let ctx = pulses_builder().build().expect("Context should be built");
+
+    let y = totals(&ctx, "tb.u1.Y");
+    assert_eq!((y.trans_count_doubled, y.high_time), (8, 130));
+    assert_eq!(y.narrow_pulse_count, 0);
+}
+
+#[test]
+fn test_pulses_filtered() {
+    let ctx = pulses_builder()
+        .min_pulse_width(50e-12)
+        .build()
+        .expect("Context should be built");
+
+    // The 30 ps pulse is filtered out, together with the time it spent high
+    let y = totals(&ctx, "tb.u1.Y");
+    assert_eq!((y.trans_count_doubled, y.high_time), (4, 100));
+    assert_eq!((y.rise_count, y.fall_count), (1, 1));
+    assert_eq!(y.narrow_pulse_count, 1);
+
+    // A narrow pulse through X leaves the net at its previous value
+    let a = totals(&ctx, "tb.a");
+    assert_eq!((a.trans_count_doubled, a.glitch_trans_count), (4, 0));
+    assert_eq!((a.high_time, a.x_time), (500, 0));
+    assert_eq!(a.narrow_pulse_count, 1);
+}
+
+#[test]
+fn test_pulses_per_cell() {
+    let ctx = pulses_builder()
+        .min_pulse_width(50e-12)
+        .cell_pulse_width("sky130_fd_sc_hd__buf_1", 10e-12)
+        .build()
+        .expect("Context should be built");
+
+    // Cells found in the netlist use their own width, others the global one
+    let buf = totals(&ctx, "tb.u2.Y");
+    assert_eq!((buf.trans_count_doubled, buf.narrow_pulse_count), (8, 0));
+    let inv = totals(&ctx, "tb.u1.Y");
+    assert_eq!((inv.trans_count_doubled, inv.narrow_pulse_count), (4, 1));
+}
+
+#[test]
+fn test_pulses_kept() {
+    let ctx = pulses_builder()
+        .min_pulse_width(50e-12)
+        .keep_narrow_pulses(true)
+        .build()
+        .expect("Context should be built");
+
+    // Narrow pulses are counted without changing the activity
+    let y = totals(&ctx, "tb.u1.Y");
+    assert_eq!((y.trans_count_doubled, y.high_time), (8, 130));
+    assert_eq!(y.narrow_pulse_count, 1);
+
+    let mut json = Vec::new();
+    ctx.export(OutputFormat::Json, &mut json, 0)
+        .expect("Export should succeed");
+    let json = String::from_utf8(json).unwrap();
+    assert!(json.contains(r#""narrow_pulses": 1"#));
+}
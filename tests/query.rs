@@ -0,0 +1,145 @@
+// Copyright (c) 2024-2026 Antmicro <www.antmicro.com>
+// SPDX-License-Identifier: Apache-2.0
+
+use libtrace2power::util::glob_match;
+use libtrace2power::{Context, ContextBuilder};
+
+fn synth_context() -> Context {
+    ContextBuilder::new("tests/synth/counter.vcd")
+        .clk_freq(500000000.0)
+        .limit_scope("counter_tb.counter0")
+        .netlist_file("tests/synth/counter.json")
+        .top("counter")
+        .top_scope("counter_tb.counter0")
+        .windows(2)
+        .build()
+        .expect("Context should be built")
+}
+
+#[test]
+fn test_query_net() {
+    let ctx = synth_context();
+    let clk = ctx
+        .net("counter_tb.counter0.clk")
+        .expect("Clock net should be found");
+    assert_eq!(clk.scope(), "counter_tb.counter0");
+    assert_eq!(clk.windows().len(), 2);
+
+    let first = clk.totals(0).unwrap();
+    let second = clk.totals(1).unwrap();
+    assert_eq!(first.clean_trans_count + second.clean_trans_count, 300);
+    assert!(clk.window(2).is_none());
+
+    assert!(ctx.net("counter_tb.counter0.missing").is_none());
+}
+
+#[test]
+fn test_query_glob() {
+    let ctx = synth_context();
+    let pulses: Vec<_> = ctx
+        .nets_matching("counter_tb.counter0.pulse*")
+        .into_iter()
+        .map(|net| net.name().to_string())
+        .collect();
+    assert_eq!(
+        pulses,
+        [
+            "counter_tb.counter0.pulse16",
+            "counter_tb.counter0.pulse2",
+            "counter_tb.counter0.pulse4",
+            "counter_tb.counter0.pulse8",
+        ]
+    );
+
+    // A single `*` doesn't cross hierarchy levels
+    assert!(ctx.nets_matching("counter_tb.*.Y").is_empty());
+    assert_eq!(ctx.nets_matching("counter_tb.**._06_.Y").len(), 1);
+}
+
+#[test]
+fn test_query_glob_match() {
+    assert!(glob_match("a*.**.c?", "ab.x.y.cd"));
+    assert!(!glob_match("a?b", "a.b"));
+    assert!(!glob_match("a*c", "ab.c"));
+    assert!(glob_match("**a*b", "xa.ab"));
+
+    // Many stars on a name that doesn't match don't take exponential time
+    let name = "a".repeat(100);
+    assert!(!glob_match(&format!("{}b", "*a".repeat(30)), &name));
+    assert!(!glob_match(&format!("{}b", "**a".repeat(30)), &name));
+}
+
+#[test]
+fn test_query_scope() {
+    let ctx = synth_context();
+    let names: Vec<_> = ctx
+        .nets_under("counter_tb.counter0._06_")
+        .into_iter()
+        .map(|net| net.name().to_string())
+        .collect();
+    assert!(names.contains(&String::from("counter_tb.counter0._06_.base.not0_out_Y")));
+    assert!(
+        names
+            .iter()
+            .all(|name| name.starts_with("counter_tb.counter0._06_."))
+    );
+
+    let totals = ctx.scope_totals("counter_tb.counter0._06_", 0);
+    assert!(totals.clean_trans_count > 0);
+}
+
+#[test]
+fn test_query_modules() {
+    let ctx = synth_context();
+    let modules = ctx.module_totals(0);
+    assert_eq!(modules["counter"].instances, 1);
+    assert_eq!(modules["sky130_fd_sc_hd__dfrtp_1"].instances, 4);
+    assert_eq!(modules["sky130_fd_sc_hd__inv_1"].instances, 2);
+}
+
+#[test]
+fn test_query_bus() {
+    let ctx = ContextBuilder::new("tests/limit_scope_power/hierarchical.vcd")
+        .clk_freq(500000000.0)
+        .build()
+        .expect("Context should be built");
+
+    // Vectors are named without the index of any of their bits
+    let a = ctx
+        .net("hierarchical_tb.dut.a")
+        .expect("Bus should be found");
+    assert_eq!(a.window(0).unwrap().bits().len(), 4);
+    assert!(ctx.net("hierarchical_tb.dut.a[0]").is_none());
+
+    let names: Vec<_> = ctx
+        .nets_under("hierarchical_tb.dut.adder1")
+        .into_iter()
+        .map(|net| net.name().to_string())
+        .collect();
+    assert!(names.contains(&String::from("hierarchical_tb.dut.adder1.result")));
+    assert!(names.iter().all(|name| !name.ends_with(']')));
+}
+
+#[test]
+fn test_query_rise_fall() {
+    let ctx = ContextBuilder::new("tests/saif_options/contention.vcd")
+        .clk_freq(100000000.0)
+        .limit_scope("contention_tb")
+        .build()
+        .expect("Context should be built");
+
+    // 1 -> X -> Z -> 0 is a single fall
+    let bus = ctx.net("contention_tb.bus").unwrap().totals(0).unwrap();
+    assert_eq!((bus.rise_count, bus.fall_count), (0, 1));
+
+    // 0 -> X -> 0 neither rises nor falls
+    let net = ctx.net("contention_tb.net").unwrap().totals(0).unwrap();
+    assert_eq!((net.rise_count, net.fall_count), (0, 0));
+
+    let clk = synth_context()
+        .net("counter_tb.counter0.clk")
+        .unwrap()
+        .totals(0)
+        .unwrap();
+    assert_eq!(clk.rise_count + clk.fall_count, clk.clean_trans_count);
+}
@@ -0,0 +1,102 @@
+// Copyright (c) 2024-2026 Antmicro <www.antmicro.com>
+// SPDX-License-Identifier: Apache-2.0
+
+use libtrace2power::{ContextBuilder, OutputFormat, SaifBusNotation, SaifEscaping};
+
+fn synth_builder() -> ContextBuilder {
+    ContextBuilder::new("tests/synth/counter.vcd")
+        .clk_freq(500000000.0)
+        .limit_scope("counter_tb.counter0")
+        .netlist_file("tests/synth/counter.json")
+        .remove_virtual_pins(true)
+        .ignore_date(true)
+        .ignore_version(true)
+}
+
+fn export_saif(builder: ContextBuilder) -> String {
+    let ctx = builder.build().expect("Context should be built");
+
+    let mut actual = Vec::new();
+    ctx.export(OutputFormat::Saif, &mut actual, 0)
+        .expect("Export should succeed");
+    String::from_utf8(actual).unwrap()
+}
+
+#[test]
+fn test_saif_escaping_all() {
+    let actual = export_saif(synth_builder().saif_escaping(SaifEscaping::All));
+    assert_eq!(actual, include_str!("saif_options/escaping_all.saif"));
+}
+
+#[test]
+fn test_saif_escaping_none() {
+    let actual = export_saif(synth_builder().saif_escaping(SaifEscaping::None));
+    assert_eq!(actual, include_str!("saif_options/escaping_none.saif"));
+}
+
+#[test]
+fn test_saif_divider_dot() {
+    let actual = export_saif(synth_builder().saif_divider('.'));
+    assert_eq!(actual, include_str!("saif_options/divider_dot.saif"));
+}
+
+#[test]
+fn test_saif_bus_range() {
+    let builder = ContextBuilder::new("tests/limit_scope_power/hierarchical.vcd")
+        .clk_freq(500000000.0)
+        .limit_scope("hierarchical_tb")
+        .ignore_date(true)
+        .ignore_version(true)
+        .saif_bus(SaifBusNotation::Range);
+    let actual = export_saif(builder);
+    assert_eq!(actual, include_str!("saif_options/bus_range.saif"));
+}
+
+#[test]
+fn test_saif_module_names() {
+    let builder = synth_builder()
+        .top("counter")
+        .top_scope("counter_tb.counter0")
+        .saif_module_names(true);
+    let actual = export_saif(builder);
+    assert_eq!(actual, include_str!("saif_options/module_names.saif"));
+}
+
+#[test]
+fn test_saif_glitch_and_contention() {
+    let builder = ContextBuilder::new("tests/saif_options/contention.vcd")
+        .clk_freq(100000000.0)
+        .limit_scope("contention_tb")
+        .ignore_date(true)
+        .ignore_version(true);
+    let actual = export_saif(builder);
+    assert_eq!(actual, include_str!("saif_options/contention.saif"));
+}
+
+#[test]
+fn test_saif_bus_contention_time() {
+    let ctx = ContextBuilder::new("tests/saif_options/bus_contention.vcd")
+        .clk_freq(100000000.0)
+        .windows(2)
+        .build()
+        .expect("Context should be built");
+    let contention = |net: &str, iteration: usize| {
+        ctx.net(net)
+            .and_then(|net| net.totals(iteration))
+            .expect("Net should be found")
+            .bus_contention_time
+    };
+
+    // X held since the beginning of the trace and X between driven values aren't contention
+    assert_eq!((contention("tb.init", 0), contention("tb.init", 1)), (0, 0));
+    assert_eq!(
+        (contention("tb.driven", 0), contention("tb.driven", 1)),
+        (0, 0)
+    );
+    // X entered from Z or left to Z counts in each window it spans
+    assert_eq!(
+        (contention("tb.fight", 0), contention("tb.fight", 1)),
+        (10, 10)
+    );
+    assert_eq!((contention("tb.late", 0), contention("tb.late", 1)), (5, 5));
+}
@@ -6,29 +6,21 @@ use tempfile::NamedTempFile;
 
 use libtrace2power::Args;
 use libtrace2power::OutputFormat;
+use libtrace2power::SaifDialect;
+use libtrace2power::TclDialect;
 use libtrace2power::process;
 use std::path::PathBuf;
 
 fn common_args(output_file: &NamedTempFile) -> Args {
     Args {
         input_file: PathBuf::from(r"tests/synth/counter.vcd"),
-        clk_freq: 500000000.0,
-        clock_name: None,
+        clk_freq: Some(500000000.0),
         output_format: OutputFormat::Tcl,
         limit_scope: Some(String::from("counter_tb.counter0")),
         netlist: Some(PathBuf::from(r"tests/synth/counter.json")),
-        top: None,
-        top_scope: None,
-        blackboxes_only: false,
         remove_virtual_pins: true,
         output: Some(output_file.path().to_path_buf()),
-        ignore_date: false,
-        ignore_version: false,
-        per_clock_cycle: false,
-        only_glitches: false,
-        export_empty: false,
-        limit_scope_power: None,
-        input_ports_activity: false,
+        ..Default::default()
     }
 }
 
@@ -40,7 +32,7 @@ fn test_synth_saif() {
     args.ignore_date = true;
     args.ignore_version = true;
 
-    process(args);
+    process(args).expect("Processing should succeed");
 
     let golden = include_str!("synth/synth.saif");
     let mut actual = String::new();
@@ -55,7 +47,7 @@ fn test_synth_tcl() {
     let mut output_file = NamedTempFile::new().expect("Failed to allocate temp file");
     let args = common_args(&output_file);
 
-    process(args);
+    process(args).expect("Processing should succeed");
 
     let golden = include_str!("synth/synth.tcl");
     let mut actual = String::new();
@@ -64,3 +56,234 @@ fn test_synth_tcl() {
         .expect("Actual file should exist");
     assert_eq!(actual, String::from(golden));
 }
+
+#[test]
+fn test_synth_tcl_primetime() {
+    let mut output_file = NamedTempFile::new().expect("Failed to allocate temp file");
+    let mut args = common_args(&output_file);
+    args.tcl_dialect = TclDialect::Primetime;
+    args.base_clock = Some(String::from("clk"));
+
+    process(args).expect("Processing should succeed");
+
+    let golden = include_str!("synth/synth_primetime.tcl");
+    let mut actual = String::new();
+    output_file
+        .read_to_string(&mut actual)
+        .expect("Actual file should exist");
+    assert_eq!(actual, golden);
+}
+
+#[test]
+fn test_synth_tcl_primetime_default_base_clock() {
+    let mut output_file = NamedTempFile::new().expect("Failed to allocate temp file");
+    let mut args = common_args(&output_file);
+    args.tcl_dialect = TclDialect::Primetime;
+    // The base clock is named after the clock net, without its path
+    args.clock_name = Some(String::from("counter_tb.counter0.clk"));
+
+    process(args).expect("Processing should succeed");
+
+    let golden = include_str!("synth/synth_primetime.tcl");
+    let mut actual = String::new();
+    output_file
+        .read_to_string(&mut actual)
+        .expect("Actual file should exist");
+    assert_eq!(actual, golden);
+}
+
+#[test]
+fn test_synth_tcl_voltus() {
+    let mut output_file = NamedTempFile::new().expect("Failed to allocate temp file");
+    let mut args = common_args(&output_file);
+    args.tcl_dialect = TclDialect::Voltus;
+
+    process(args).expect("Processing should succeed");
+
+    let golden = include_str!("synth/synth_voltus.tcl");
+    let mut actual = String::new();
+    output_file
+        .read_to_string(&mut actual)
+        .expect("Actual file should exist");
+    assert_eq!(actual, golden);
+}
+
+#[test]
+fn test_synth_tcl_vivado() {
+    let mut output_file = NamedTempFile::new().expect("Failed to allocate temp file");
+    let mut args = common_args(&output_file);
+    args.tcl_dialect = TclDialect::Vivado;
+
+    process(args).expect("Processing should succeed");
+
+    let golden = include_str!("synth/synth_vivado.tcl");
+    let mut actual = String::new();
+    output_file
+        .read_to_string(&mut actual)
+        .expect("Actual file should exist");
+    assert_eq!(actual, golden);
+}
+
+#[test]
+fn test_synth_saif_xilinx() {
+    let mut output_file = NamedTempFile::new().expect("Failed to allocate temp file");
+    let mut args = common_args(&output_file);
+    args.output_format = OutputFormat::Saif;
+    args.saif_dialect = SaifDialect::Xilinx;
+    args.limit_scope = None;
+    args.top = Some(String::from("counter"));
+    args.top_scope = Some(String::from("counter_tb.counter0"));
+    args.ignore_date = true;
+    args.ignore_version = true;
+
+    process(args).expect("Processing should succeed");
+
+    let golden = include_str!("synth/synth_xilinx.saif");
+    let mut actual = String::new();
+    output_file
+        .read_to_string(&mut actual)
+        .expect("Actual file should exist");
+    assert_eq!(actual, golden);
+}
+
+#[test]
+fn test_synth_tcf() {
+    let mut output_file = NamedTempFile::new().expect("Failed to allocate temp file");
+    let mut args = common_args(&output_file);
+    args.output_format = OutputFormat::Tcf;
+    args.ignore_date = true;
+    args.ignore_version = true;
+
+    process(args).expect("Processing should succeed");
+
+    let golden = include_str!("synth/synth.tcf");
+    let mut actual = String::new();
+    output_file
+        .read_to_string(&mut actual)
+        .expect("Actual file should exist");
+    assert_eq!(actual, golden);
+}
+
+#[test]
+fn test_synth_json() {
+    let mut output_file = NamedTempFile::new().expect("Failed to allocate temp file");
+    let mut args = common_args(&output_file);
+    args.output_format = OutputFormat::Json;
+    args.ignore_date = true;
+    args.ignore_version = true;
+
+    process(args).expect("Processing should succeed");
+
+    let golden = include_str!("synth/synth.json");
+    let mut actual = String::new();
+    output_file
+        .read_to_string(&mut actual)
+        .expect("Actual file should exist");
+    assert_eq!(actual, golden);
+}
+
+#[test]
+fn test_synth_csv() {
+    let mut output_file = NamedTempFile::new().expect("Failed to allocate temp file");
+    let mut args = common_args(&output_file);
+    args.output_format = OutputFormat::Csv;
+
+    process(args).expect("Processing should succeed");
+
+    let golden = include_str!("synth/synth.csv");
+    let mut actual = String::new();
+    output_file
+        .read_to_string(&mut actual)
+        .expect("Actual file should exist");
+    assert_eq!(actual, golden);
+}
+
+#[test]
+fn test_synth_csv_per_clock_cycle() {
+    let mut output_file = NamedTempFile::new().expect("Failed to allocate temp file");
+    let mut args = common_args(&output_file);
+    args.output_format = OutputFormat::Tsv;
+    args.per_clock_cycle = true;
+    args.sparse = true;
+
+    process(args).expect("Processing should succeed");
+
+    let mut actual = String::new();
+    output_file
+        .read_to_string(&mut actual)
+        .expect("Actual file should exist");
+    let mut lines = actual.lines();
+    assert_eq!(
+        lines.next(),
+        Some(
+            "window\tstart_time\tend_time\tnet\tbit\tT0\tT1\tTX\tTZ\tTC\tIG\trise\tfall\tduty\tactivity"
+        )
+    );
+    let clk_rows: Vec<_> = lines
+        .map(|line| line.split('\t').collect::<Vec<_>>())
+        .filter(|row| row[3] == "counter_tb.counter0.clk")
+        .collect();
+    assert_eq!(clk_rows.len(), 150);
+    assert_eq!(
+        clk_rows[149][..5],
+        ["149", "298", "300", "counter_tb.counter0.clk", ""]
+    );
+}
+
+#[test]
+fn test_synth_vcd_profile() {
+    let profile = |per_clock_cycle: bool| {
+        let mut output_file = NamedTempFile::new().expect("Failed to allocate temp file");
+        let mut args = common_args(&output_file);
+        args.output_format = OutputFormat::Vcd;
+        args.per_clock_cycle = per_clock_cycle;
+        args.profile_depth = Some(1);
+
+        process(args).expect("Processing should succeed");
+
+        let mut actual = String::new();
+        output_file
+            .read_to_string(&mut actual)
+            .expect("Actual file should exist");
+        actual
+    };
+
+    let whole = profile(false);
+    let (header, changes) = whole
+        .split_once("$enddefinitions $end\n")
+        .expect("Profile should have a header");
+    assert!(
+        header
+            .ends_with("$scope module counter0 $end\n$var real 64 ! toggles $end\n$upscope $end\n")
+    );
+    let changes: Vec<_> = changes.lines().collect();
+    assert_eq!(changes.len(), 3);
+    assert_eq!(changes[0], "#0");
+    assert_eq!(changes[2], "#300");
+    let total: f64 = changes[1]
+        .strip_prefix('r')
+        .and_then(|change| change.strip_suffix(" !"))
+        .expect("Value change should be valid")
+        .parse()
+        .expect("Value should be a number");
+
+    // Toggles of consecutive windows sum up to the toggles of the whole trace
+    let per_cycle = profile(true);
+    assert_eq!(
+        per_cycle
+            .lines()
+            .filter(|line| line.starts_with('#'))
+            .count(),
+        151
+    );
+    let mut value = None;
+    let mut sum = 0.0;
+    for line in per_cycle.lines() {
+        if line.starts_with('#') {
+            sum += value.unwrap_or(0.0);
+        } else if let Some(change) = line.strip_prefix('r') {
+            value = Some(change.trim_end_matches(" !").parse().unwrap());
+        }
+    }
+    assert_eq!(sum, total);
+}
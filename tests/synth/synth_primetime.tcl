@@ -0,0 +1,18 @@
+proc set_pin_activity_and_duty {} {
+  set_switching_activity -toggle_rate 0 -static_probability 0 -base_clock {clk} [get_pins {_06_/VGND _06_/VNB _07_/VGND _07_/VNB _08_/VGND _08_/VNB _09_/VGND _09_/VNB _10_/VGND _10_/VNB _11_/VGND _11_/VNB _12_/VGND _12_/VNB cnt\[0\]$_DFF_PP0_/VGND cnt\[0\]$_DFF_PP0_/VNB cnt\[1\]$_DFF_PP0_/VGND cnt\[1\]$_DFF_PP0_/VNB cnt\[2\]$_DFF_PP0_/VGND cnt\[2\]$_DFF_PP0_/VNB cnt\[3\]$_DFF_PP0_/VGND cnt\[3\]$_DFF_PP0_/VNB}]
+  set_switching_activity -toggle_rate 0.006666666666666667 -static_probability 0.006666666666666667 -base_clock {clk} [get_pins {rst _09_/A _09_/base/A cnt\[0\]$_DFF_PP0_/base/RESET cnt\[0\]$_DFF_PP0_/base/dff0/RESET cnt\[1\]$_DFF_PP0_/base/RESET cnt\[1\]$_DFF_PP0_/base/dff0/RESET cnt\[2\]$_DFF_PP0_/base/RESET cnt\[2\]$_DFF_PP0_/base/dff0/RESET cnt\[3\]$_DFF_PP0_/base/RESET cnt\[3\]$_DFF_PP0_/base/dff0/RESET}]
+  set_switching_activity -toggle_rate 0.12 -static_probability 0.06 -base_clock {clk} [get_pins {pulse16 _10_/X _10_/base/X _10_/base/and0_out_X}]
+  set_switching_activity -toggle_rate 0.24 -static_probability 0.12 -base_clock {clk} [get_pins {pulse8 _12_/COUT _12_/base/COUT _12_/base/and0_out_COUT}]
+  set_switching_activity -toggle_rate 0.49333333333333335 -static_probability 0.24666666666666667 -base_clock {clk} [get_pins {pulse4 _10_/C _10_/base/C _11_/COUT _11_/base/COUT _11_/base/and0_out_COUT _12_/B _12_/base/B}]
+  set_switching_activity -toggle_rate 0.12 -static_probability 0.48 -base_clock {clk} [get_pins {cnt[3] _01_ _08_/Y _08_/A _08_/base/Y _08_/base/xnor0_out_Y _08_/base/A _10_/A _10_/base/A cnt\[3\]$_DFF_PP0_/D cnt\[3\]$_DFF_PP0_/Q cnt\[3\]$_DFF_PP0_/base/D cnt\[3\]$_DFF_PP0_/base/Q cnt\[3\]$_DFF_PP0_/base/buf_Q cnt\[3\]$_DFF_PP0_/base/dff0/D cnt\[3\]$_DFF_PP0_/base/dff0/Q}]
+  set_switching_activity -toggle_rate 0.24666666666666667 -static_probability 0.49 -base_clock {clk} [get_pins {cnt[2] _07_/B _07_/base/B _10_/B _10_/base/B _12_/A _12_/base/A cnt\[2\]$_DFF_PP0_/Q cnt\[2\]$_DFF_PP0_/base/Q cnt\[2\]$_DFF_PP0_/base/buf_Q cnt\[2\]$_DFF_PP0_/base/dff0/Q}]
+  set_switching_activity -toggle_rate 0.49333333333333335 -static_probability 0.49333333333333335 -base_clock {clk} [get_pins {cnt[1] _07_/C _07_/base/C _11_/A _11_/base/A cnt\[1\]$_DFF_PP0_/Q cnt\[1\]$_DFF_PP0_/base/Q cnt\[1\]$_DFF_PP0_/base/buf_Q cnt\[1\]$_DFF_PP0_/base/dff0/Q}]
+  set_switching_activity -toggle_rate 0.24666666666666667 -static_probability 0.49666666666666665 -base_clock {clk} [get_pins {_05_ _12_/SUM _12_/base/SUM _12_/base/xor0_out_SUM cnt\[2\]$_DFF_PP0_/D cnt\[2\]$_DFF_PP0_/base/D cnt\[2\]$_DFF_PP0_/base/dff0/D}]
+  set_switching_activity -toggle_rate 0.5 -static_probability 0.49666666666666665 -base_clock {clk} [get_pins {_04_ _11_/SUM _11_/base/SUM _11_/base/xor0_out_SUM cnt\[1\]$_DFF_PP0_/D cnt\[1\]$_DFF_PP0_/base/D cnt\[1\]$_DFF_PP0_/base/dff0/D}]
+  set_switching_activity -toggle_rate 0.9933333333333333 -static_probability 0.49666666666666665 -base_clock {clk} [get_pins {pulse2 _06_/A _06_/base/A _07_/A _07_/base/A _11_/B _11_/base/B cnt\[0\]$_DFF_PP0_/Q cnt\[0\]$_DFF_PP0_/base/Q cnt\[0\]$_DFF_PP0_/base/buf_Q cnt\[0\]$_DFF_PP0_/base/dff0/Q}]
+  set_switching_activity -toggle_rate 2 -static_probability 0.5 -base_clock {clk} [get_pins {clk cnt\[0\]$_DFF_PP0_/CLK cnt\[0\]$_DFF_PP0_/base/CLK cnt\[0\]$_DFF_PP0_/base/dff0/CLK cnt\[1\]$_DFF_PP0_/CLK cnt\[1\]$_DFF_PP0_/base/CLK cnt\[1\]$_DFF_PP0_/base/dff0/CLK cnt\[2\]$_DFF_PP0_/CLK cnt\[2\]$_DFF_PP0_/base/CLK cnt\[2\]$_DFF_PP0_/base/dff0/CLK cnt\[3\]$_DFF_PP0_/CLK cnt\[3\]$_DFF_PP0_/base/CLK cnt\[3\]$_DFF_PP0_/base/dff0/CLK}]
+  set_switching_activity -toggle_rate 0.9933333333333333 -static_probability 0.5033333333333333 -base_clock {clk} [get_pins {_00_ _06_/Y _06_/base/Y _06_/base/not0_out_Y cnt\[0\]$_DFF_PP0_/D cnt\[0\]$_DFF_PP0_/base/D cnt\[0\]$_DFF_PP0_/base/dff0/D}]
+  set_switching_activity -toggle_rate 0.24 -static_probability 0.88 -base_clock {clk} [get_pins {_03_ _07_/Y _07_/base/Y _07_/base/nand0_out_Y _08_/B _08_/base/B}]
+  set_switching_activity -toggle_rate 0.006666666666666667 -static_probability 0.9933333333333333 -base_clock {clk} [get_pins {_02_ _09_/Y _09_/base/Y _09_/base/not0_out_Y cnt\[0\]$_DFF_PP0_/RESET_B cnt\[0\]$_DFF_PP0_/base/RESET_B cnt\[1\]$_DFF_PP0_/RESET_B cnt\[1\]$_DFF_PP0_/base/RESET_B cnt\[2\]$_DFF_PP0_/RESET_B cnt\[2\]$_DFF_PP0_/base/RESET_B cnt\[3\]$_DFF_PP0_/RESET_B cnt\[3\]$_DFF_PP0_/base/RESET_B}]
+  set_switching_activity -toggle_rate 0 -static_probability 1 -base_clock {clk} [get_pins {_06_/VPB _06_/VPWR _07_/VPB _07_/VPWR _08_/VPB _08_/VPWR _09_/VPB _09_/VPWR _10_/VPB _10_/VPWR _11_/VPB _11_/VPWR _12_/VPB _12_/VPWR cnt\[0\]$_DFF_PP0_/VPB cnt\[0\]$_DFF_PP0_/VPWR cnt\[1\]$_DFF_PP0_/VPB cnt\[1\]$_DFF_PP0_/VPWR cnt\[2\]$_DFF_PP0_/VPB cnt\[2\]$_DFF_PP0_/VPWR cnt\[3\]$_DFF_PP0_/VPB cnt\[3\]$_DFF_PP0_/VPWR}]
+}
@@ -0,0 +1,172 @@
+proc set_pin_activity_and_duty {} {
+  set_switching_activity -pin {_06_/VGND} -activity 0 -duty 0 -period 2
+  set_switching_activity -pin {_06_/VNB} -activity 0 -duty 0 -period 2
+  set_switching_activity -pin {_07_/VGND} -activity 0 -duty 0 -period 2
+  set_switching_activity -pin {_07_/VNB} -activity 0 -duty 0 -period 2
+  set_switching_activity -pin {_08_/VGND} -activity 0 -duty 0 -period 2
+  set_switching_activity -pin {_08_/VNB} -activity 0 -duty 0 -period 2
+  set_switching_activity -pin {_09_/VGND} -activity 0 -duty 0 -period 2
+  set_switching_activity -pin {_09_/VNB} -activity 0 -duty 0 -period 2
+  set_switching_activity -pin {_10_/VGND} -activity 0 -duty 0 -period 2
+  set_switching_activity -pin {_10_/VNB} -activity 0 -duty 0 -period 2
+  set_switching_activity -pin {_11_/VGND} -activity 0 -duty 0 -period 2
+  set_switching_activity -pin {_11_/VNB} -activity 0 -duty 0 -period 2
+  set_switching_activity -pin {_12_/VGND} -activity 0 -duty 0 -period 2
+  set_switching_activity -pin {_12_/VNB} -activity 0 -duty 0 -period 2
+  set_switching_activity -pin {cnt\[0\]$_DFF_PP0_/VGND} -activity 0 -duty 0 -period 2
+  set_switching_activity -pin {cnt\[0\]$_DFF_PP0_/VNB} -activity 0 -duty 0 -period 2
+  set_switching_activity -pin {cnt\[1\]$_DFF_PP0_/VGND} -activity 0 -duty 0 -period 2
+  set_switching_activity -pin {cnt\[1\]$_DFF_PP0_/VNB} -activity 0 -duty 0 -period 2
+  set_switching_activity -pin {cnt\[2\]$_DFF_PP0_/VGND} -activity 0 -duty 0 -period 2
+  set_switching_activity -pin {cnt\[2\]$_DFF_PP0_/VNB} -activity 0 -duty 0 -period 2
+  set_switching_activity -pin {cnt\[3\]$_DFF_PP0_/VGND} -activity 0 -duty 0 -period 2
+  set_switching_activity -pin {cnt\[3\]$_DFF_PP0_/VNB} -activity 0 -duty 0 -period 2
+  set_switching_activity -pin {rst} -activity 0.006666666666666667 -duty 0.006666666666666667 -period 2
+  set_switching_activity -pin {_09_/A} -activity 0.006666666666666667 -duty 0.006666666666666667 -period 2
+  set_switching_activity -pin {_09_/base/A} -activity 0.006666666666666667 -duty 0.006666666666666667 -period 2
+  set_switching_activity -pin {cnt\[0\]$_DFF_PP0_/base/RESET} -activity 0.006666666666666667 -duty 0.006666666666666667 -period 2
+  set_switching_activity -pin {cnt\[0\]$_DFF_PP0_/base/dff0/RESET} -activity 0.006666666666666667 -duty 0.006666666666666667 -period 2
+  set_switching_activity -pin {cnt\[1\]$_DFF_PP0_/base/RESET} -activity 0.006666666666666667 -duty 0.006666666666666667 -period 2
+  set_switching_activity -pin {cnt\[1\]$_DFF_PP0_/base/dff0/RESET} -activity 0.006666666666666667 -duty 0.006666666666666667 -period 2
+  set_switching_activity -pin {cnt\[2\]$_DFF_PP0_/base/RESET} -activity 0.006666666666666667 -duty 0.006666666666666667 -period 2
+  set_switching_activity -pin {cnt\[2\]$_DFF_PP0_/base/dff0/RESET} -activity 0.006666666666666667 -duty 0.006666666666666667 -period 2
+  set_switching_activity -pin {cnt\[3\]$_DFF_PP0_/base/RESET} -activity 0.006666666666666667 -duty 0.006666666666666667 -period 2
+  set_switching_activity -pin {cnt\[3\]$_DFF_PP0_/base/dff0/RESET} -activity 0.006666666666666667 -duty 0.006666666666666667 -period 2
+  set_switching_activity -pin {pulse16} -activity 0.12 -duty 0.06 -period 2
+  set_switching_activity -pin {_10_/X} -activity 0.12 -duty 0.06 -period 2
+  set_switching_activity -pin {_10_/base/X} -activity 0.12 -duty 0.06 -period 2
+  set_switching_activity -pin {_10_/base/and0_out_X} -activity 0.12 -duty 0.06 -period 2
+  set_switching_activity -pin {pulse8} -activity 0.24 -duty 0.12 -period 2
+  set_switching_activity -pin {_12_/COUT} -activity 0.24 -duty 0.12 -period 2
+  set_switching_activity -pin {_12_/base/COUT} -activity 0.24 -duty 0.12 -period 2
+  set_switching_activity -pin {_12_/base/and0_out_COUT} -activity 0.24 -duty 0.12 -period 2
+  set_switching_activity -pin {pulse4} -activity 0.49333333333333335 -duty 0.24666666666666667 -period 2
+  set_switching_activity -pin {_10_/C} -activity 0.49333333333333335 -duty 0.24666666666666667 -period 2
+  set_switching_activity -pin {_10_/base/C} -activity 0.49333333333333335 -duty 0.24666666666666667 -period 2
+  set_switching_activity -pin {_11_/COUT} -activity 0.49333333333333335 -duty 0.24666666666666667 -period 2
+  set_switching_activity -pin {_11_/base/COUT} -activity 0.49333333333333335 -duty 0.24666666666666667 -period 2
+  set_switching_activity -pin {_11_/base/and0_out_COUT} -activity 0.49333333333333335 -duty 0.24666666666666667 -period 2
+  set_switching_activity -pin {_12_/B} -activity 0.49333333333333335 -duty 0.24666666666666667 -period 2
+  set_switching_activity -pin {_12_/base/B} -activity 0.49333333333333335 -duty 0.24666666666666667 -period 2
+  set_switching_activity -pin {cnt[3]} -activity 0.12 -duty 0.48 -period 2
+  set_switching_activity -pin {_01_} -activity 0.12 -duty 0.48 -period 2
+  set_switching_activity -pin {_08_/Y} -activity 0.12 -duty 0.48 -period 2
+  set_switching_activity -pin {_08_/A} -activity 0.12 -duty 0.48 -period 2
+  set_switching_activity -pin {_08_/base/Y} -activity 0.12 -duty 0.48 -period 2
+  set_switching_activity -pin {_08_/base/xnor0_out_Y} -activity 0.12 -duty 0.48 -period 2
+  set_switching_activity -pin {_08_/base/A} -activity 0.12 -duty 0.48 -period 2
+  set_switching_activity -pin {_10_/A} -activity 0.12 -duty 0.48 -period 2
+  set_switching_activity -pin {_10_/base/A} -activity 0.12 -duty 0.48 -period 2
+  set_switching_activity -pin {cnt\[3\]$_DFF_PP0_/D} -activity 0.12 -duty 0.48 -period 2
+  set_switching_activity -pin {cnt\[3\]$_DFF_PP0_/Q} -activity 0.12 -duty 0.48 -period 2
+  set_switching_activity -pin {cnt\[3\]$_DFF_PP0_/base/D} -activity 0.12 -duty 0.48 -period 2
+  set_switching_activity -pin {cnt\[3\]$_DFF_PP0_/base/Q} -activity 0.12 -duty 0.48 -period 2
+  set_switching_activity -pin {cnt\[3\]$_DFF_PP0_/base/buf_Q} -activity 0.12 -duty 0.48 -period 2
+  set_switching_activity -pin {cnt\[3\]$_DFF_PP0_/base/dff0/D} -activity 0.12 -duty 0.48 -period 2
+  set_switching_activity -pin {cnt\[3\]$_DFF_PP0_/base/dff0/Q} -activity 0.12 -duty 0.48 -period 2
+  set_switching_activity -pin {cnt[2]} -activity 0.24666666666666667 -duty 0.49 -period 2
+  set_switching_activity -pin {_07_/B} -activity 0.24666666666666667 -duty 0.49 -period 2
+  set_switching_activity -pin {_07_/base/B} -activity 0.24666666666666667 -duty 0.49 -period 2
+  set_switching_activity -pin {_10_/B} -activity 0.24666666666666667 -duty 0.49 -period 2
+  set_switching_activity -pin {_10_/base/B} -activity 0.24666666666666667 -duty 0.49 -period 2
+  set_switching_activity -pin {_12_/A} -activity 0.24666666666666667 -duty 0.49 -period 2
+  set_switching_activity -pin {_12_/base/A} -activity 0.24666666666666667 -duty 0.49 -period 2
+  set_switching_activity -pin {cnt\[2\]$_DFF_PP0_/Q} -activity 0.24666666666666667 -duty 0.49 -period 2
+  set_switching_activity -pin {cnt\[2\]$_DFF_PP0_/base/Q} -activity 0.24666666666666667 -duty 0.49 -period 2
+  set_switching_activity -pin {cnt\[2\]$_DFF_PP0_/base/buf_Q} -activity 0.24666666666666667 -duty 0.49 -period 2
+  set_switching_activity -pin {cnt\[2\]$_DFF_PP0_/base/dff0/Q} -activity 0.24666666666666667 -duty 0.49 -period 2
+  set_switching_activity -pin {cnt[1]} -activity 0.49333333333333335 -duty 0.49333333333333335 -period 2
+  set_switching_activity -pin {_07_/C} -activity 0.49333333333333335 -duty 0.49333333333333335 -period 2
+  set_switching_activity -pin {_07_/base/C} -activity 0.49333333333333335 -duty 0.49333333333333335 -period 2
+  set_switching_activity -pin {_11_/A} -activity 0.49333333333333335 -duty 0.49333333333333335 -period 2
+  set_switching_activity -pin {_11_/base/A} -activity 0.49333333333333335 -duty 0.49333333333333335 -period 2
+  set_switching_activity -pin {cnt\[1\]$_DFF_PP0_/Q} -activity 0.49333333333333335 -duty 0.49333333333333335 -period 2
+  set_switching_activity -pin {cnt\[1\]$_DFF_PP0_/base/Q} -activity 0.49333333333333335 -duty 0.49333333333333335 -period 2
+  set_switching_activity -pin {cnt\[1\]$_DFF_PP0_/base/buf_Q} -activity 0.49333333333333335 -duty 0.49333333333333335 -period 2
+  set_switching_activity -pin {cnt\[1\]$_DFF_PP0_/base/dff0/Q} -activity 0.49333333333333335 -duty 0.49333333333333335 -period 2
+  set_switching_activity -pin {_05_} -activity 0.24666666666666667 -duty 0.49666666666666665 -period 2
+  set_switching_activity -pin {_12_/SUM} -activity 0.24666666666666667 -duty 0.49666666666666665 -period 2
+  set_switching_activity -pin {_12_/base/SUM} -activity 0.24666666666666667 -duty 0.49666666666666665 -period 2
+  set_switching_activity -pin {_12_/base/xor0_out_SUM} -activity 0.24666666666666667 -duty 0.49666666666666665 -period 2
+  set_switching_activity -pin {cnt\[2\]$_DFF_PP0_/D} -activity 0.24666666666666667 -duty 0.49666666666666665 -period 2
+  set_switching_activity -pin {cnt\[2\]$_DFF_PP0_/base/D} -activity 0.24666666666666667 -duty 0.49666666666666665 -period 2
+  set_switching_activity -pin {cnt\[2\]$_DFF_PP0_/base/dff0/D} -activity 0.24666666666666667 -duty 0.49666666666666665 -period 2
+  set_switching_activity -pin {_04_} -activity 0.5 -duty 0.49666666666666665 -period 2
+  set_switching_activity -pin {_11_/SUM} -activity 0.5 -duty 0.49666666666666665 -period 2
+  set_switching_activity -pin {_11_/base/SUM} -activity 0.5 -duty 0.49666666666666665 -period 2
+  set_switching_activity -pin {_11_/base/xor0_out_SUM} -activity 0.5 -duty 0.49666666666666665 -period 2
+  set_switching_activity -pin {cnt\[1\]$_DFF_PP0_/D} -activity 0.5 -duty 0.49666666666666665 -period 2
+  set_switching_activity -pin {cnt\[1\]$_DFF_PP0_/base/D} -activity 0.5 -duty 0.49666666666666665 -period 2
+  set_switching_activity -pin {cnt\[1\]$_DFF_PP0_/base/dff0/D} -activity 0.5 -duty 0.49666666666666665 -period 2
+  set_switching_activity -pin {pulse2} -activity 0.9933333333333333 -duty 0.49666666666666665 -period 2
+  set_switching_activity -pin {_06_/A} -activity 0.9933333333333333 -duty 0.49666666666666665 -period 2
+  set_switching_activity -pin {_06_/base/A} -activity 0.9933333333333333 -duty 0.49666666666666665 -period 2
+  set_switching_activity -pin {_07_/A} -activity 0.9933333333333333 -duty 0.49666666666666665 -period 2
+  set_switching_activity -pin {_07_/base/A} -activity 0.9933333333333333 -duty 0.49666666666666665 -period 2
+  set_switching_activity -pin {_11_/B} -activity 0.9933333333333333 -duty 0.49666666666666665 -period 2
+  set_switching_activity -pin {_11_/base/B} -activity 0.9933333333333333 -duty 0.49666666666666665 -period 2
+  set_switching_activity -pin {cnt\[0\]$_DFF_PP0_/Q} -activity 0.9933333333333333 -duty 0.49666666666666665 -period 2
+  set_switching_activity -pin {cnt\[0\]$_DFF_PP0_/base/Q} -activity 0.9933333333333333 -duty 0.49666666666666665 -period 2
+  set_switching_activity -pin {cnt\[0\]$_DFF_PP0_/base/buf_Q} -activity 0.9933333333333333 -duty 0.49666666666666665 -period 2
+  set_switching_activity -pin {cnt\[0\]$_DFF_PP0_/base/dff0/Q} -activity 0.9933333333333333 -duty 0.49666666666666665 -period 2
+  set_switching_activity -pin {clk} -activity 2 -duty 0.5 -period 2
+  set_switching_activity -pin {cnt\[0\]$_DFF_PP0_/CLK} -activity 2 -duty 0.5 -period 2
+  set_switching_activity -pin {cnt\[0\]$_DFF_PP0_/base/CLK} -activity 2 -duty 0.5 -period 2
+  set_switching_activity -pin {cnt\[0\]$_DFF_PP0_/base/dff0/CLK} -activity 2 -duty 0.5 -period 2
+  set_switching_activity -pin {cnt\[1\]$_DFF_PP0_/CLK} -activity 2 -duty 0.5 -period 2
+  set_switching_activity -pin {cnt\[1\]$_DFF_PP0_/base/CLK} -activity 2 -duty 0.5 -period 2
+  set_switching_activity -pin {cnt\[1\]$_DFF_PP0_/base/dff0/CLK} -activity 2 -duty 0.5 -period 2
+  set_switching_activity -pin {cnt\[2\]$_DFF_PP0_/CLK} -activity 2 -duty 0.5 -period 2
+  set_switching_activity -pin {cnt\[2\]$_DFF_PP0_/base/CLK} -activity 2 -duty 0.5 -period 2
+  set_switching_activity -pin {cnt\[2\]$_DFF_PP0_/base/dff0/CLK} -activity 2 -duty 0.5 -period 2
+  set_switching_activity -pin {cnt\[3\]$_DFF_PP0_/CLK} -activity 2 -duty 0.5 -period 2
+  set_switching_activity -pin {cnt\[3\]$_DFF_PP0_/base/CLK} -activity 2 -duty 0.5 -period 2
+  set_switching_activity -pin {cnt\[3\]$_DFF_PP0_/base/dff0/CLK} -activity 2 -duty 0.5 -period 2
+  set_switching_activity -pin {_00_} -activity 0.9933333333333333 -duty 0.5033333333333333 -period 2
+  set_switching_activity -pin {_06_/Y} -activity 0.9933333333333333 -duty 0.5033333333333333 -period 2
+  set_switching_activity -pin {_06_/base/Y} -activity 0.9933333333333333 -duty 0.5033333333333333 -period 2
+  set_switching_activity -pin {_06_/base/not0_out_Y} -activity 0.9933333333333333 -duty 0.5033333333333333 -period 2
+  set_switching_activity -pin {cnt\[0\]$_DFF_PP0_/D} -activity 0.9933333333333333 -duty 0.5033333333333333 -period 2
+  set_switching_activity -pin {cnt\[0\]$_DFF_PP0_/base/D} -activity 0.9933333333333333 -duty 0.5033333333333333 -period 2
+  set_switching_activity -pin {cnt\[0\]$_DFF_PP0_/base/dff0/D} -activity 0.9933333333333333 -duty 0.5033333333333333 -period 2
+  set_switching_activity -pin {_03_} -activity 0.24 -duty 0.88 -period 2
+  set_switching_activity -pin {_07_/Y} -activity 0.24 -duty 0.88 -period 2
+  set_switching_activity -pin {_07_/base/Y} -activity 0.24 -duty 0.88 -period 2
+  set_switching_activity -pin {_07_/base/nand0_out_Y} -activity 0.24 -duty 0.88 -period 2
+  set_switching_activity -pin {_08_/B} -activity 0.24 -duty 0.88 -period 2
+  set_switching_activity -pin {_08_/base/B} -activity 0.24 -duty 0.88 -period 2
+  set_switching_activity -pin {_02_} -activity 0.006666666666666667 -duty 0.9933333333333333 -period 2
+  set_switching_activity -pin {_09_/Y} -activity 0.006666666666666667 -duty 0.9933333333333333 -period 2
+  set_switching_activity -pin {_09_/base/Y} -activity 0.006666666666666667 -duty 0.9933333333333333 -period 2
+  set_switching_activity -pin {_09_/base/not0_out_Y} -activity 0.006666666666666667 -duty 0.9933333333333333 -period 2
+  set_switching_activity -pin {cnt\[0\]$_DFF_PP0_/RESET_B} -activity 0.006666666666666667 -duty 0.9933333333333333 -period 2
+  set_switching_activity -pin {cnt\[0\]$_DFF_PP0_/base/RESET_B} -activity 0.006666666666666667 -duty 0.9933333333333333 -period 2
+  set_switching_activity -pin {cnt\[1\]$_DFF_PP0_/RESET_B} -activity 0.006666666666666667 -duty 0.9933333333333333 -period 2
+  set_switching_activity -pin {cnt\[1\]$_DFF_PP0_/base/RESET_B} -activity 0.006666666666666667 -duty 0.9933333333333333 -period 2
+  set_switching_activity -pin {cnt\[2\]$_DFF_PP0_/RESET_B} -activity 0.006666666666666667 -duty 0.9933333333333333 -period 2
+  set_switching_activity -pin {cnt\[2\]$_DFF_PP0_/base/RESET_B} -activity 0.006666666666666667 -duty 0.9933333333333333 -period 2
+  set_switching_activity -pin {cnt\[3\]$_DFF_PP0_/RESET_B} -activity 0.006666666666666667 -duty 0.9933333333333333 -period 2
+  set_switching_activity -pin {cnt\[3\]$_DFF_PP0_/base/RESET_B} -activity 0.006666666666666667 -duty 0.9933333333333333 -period 2
+  set_switching_activity -pin {_06_/VPB} -activity 0 -duty 1 -period 2
+  set_switching_activity -pin {_06_/VPWR} -activity 0 -duty 1 -period 2
+  set_switching_activity -pin {_07_/VPB} -activity 0 -duty 1 -period 2
+  set_switching_activity -pin {_07_/VPWR} -activity 0 -duty 1 -period 2
+  set_switching_activity -pin {_08_/VPB} -activity 0 -duty 1 -period 2
+  set_switching_activity -pin {_08_/VPWR} -activity 0 -duty 1 -period 2
+  set_switching_activity -pin {_09_/VPB} -activity 0 -duty 1 -period 2
+  set_switching_activity -pin {_09_/VPWR} -activity 0 -duty 1 -period 2
+  set_switching_activity -pin {_10_/VPB} -activity 0 -duty 1 -period 2
+  set_switching_activity -pin {_10_/VPWR} -activity 0 -duty 1 -period 2
+  set_switching_activity -pin {_11_/VPB} -activity 0 -duty 1 -period 2
+  set_switching_activity -pin {_11_/VPWR} -activity 0 -duty 1 -period 2
+  set_switching_activity -pin {_12_/VPB} -activity 0 -duty 1 -period 2
+  set_switching_activity -pin {_12_/VPWR} -activity 0 -duty 1 -period 2
+  set_switching_activity -pin {cnt\[0\]$_DFF_PP0_/VPB} -activity 0 -duty 1 -period 2
+  set_switching_activity -pin {cnt\[0\]$_DFF_PP0_/VPWR} -activity 0 -duty 1 -period 2
+  set_switching_activity -pin {cnt\[1\]$_DFF_PP0_/VPB} -activity 0 -duty 1 -period 2
+  set_switching_activity -pin {cnt\[1\]$_DFF_PP0_/VPWR} -activity 0 -duty 1 -period 2
+  set_switching_activity -pin {cnt\[2\]$_DFF_PP0_/VPB} -activity 0 -duty 1 -period 2
+  set_switching_activity -pin {cnt\[2\]$_DFF_PP0_/VPWR} -activity 0 -duty 1 -period 2
+  set_switching_activity -pin {cnt\[3\]$_DFF_PP0_/VPB} -activity 0 -duty 1 -period 2
+  set_switching_activity -pin {cnt\[3\]$_DFF_PP0_/VPWR} -activity 0 -duty 1 -period 2
+}